@@ -5,9 +5,14 @@ use crate::{
         CARGO_SRC_DIRECTORY, CARGO_TARGET_DIRECTORY,
     },
 };
-use std::{env::current_dir, path::PathBuf};
+use std::{
+    env::current_dir,
+    path::{Path, PathBuf},
+};
 
 const DEFAULT_PIPE_MANIFEST: &str = "pipe.yml";
+// manifest file names lookup in order if not specified
+const PIPE_MANIFESTS: &[&str] = &["pipe.yml", "pipe.yaml", "pipe.json", "pipe.toml"];
 const DEFAULT_APP_DIRECTORY: &str = "app";
 
 pub struct Config {
@@ -21,13 +26,24 @@ impl Config {
             Some(directory) => PathBuf::from(directory),
             None => current_dir()?,
         };
-        let manifest = String::from(manifest.unwrap_or(DEFAULT_PIPE_MANIFEST));
+        let manifest = match manifest {
+            Some(manifest) => String::from(manifest),
+            None => Self::find_pipe_manifest(&working_drectory),
+        };
         Ok(Config {
             working_drectory,
             manifest,
         })
     }
 
+    fn find_pipe_manifest(working_drectory: &Path) -> String {
+        let manifest = PIPE_MANIFESTS
+            .iter()
+            .find(|manifest| working_drectory.join(manifest).is_file())
+            .unwrap_or(&DEFAULT_PIPE_MANIFEST);
+        String::from(*manifest)
+    }
+
     pub fn get_pipe_manifest_path(&self) -> PathBuf {
         let mut manifest_path = self.working_drectory.to_owned();
         manifest_path.push(&self.manifest);
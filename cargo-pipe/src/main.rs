@@ -39,7 +39,7 @@ fn run() -> CmdResult<()> {
             clap::Arg::new("manifest")
                 .short('m')
                 .takes_value(true)
-                .help("Manifest file name in working directory"),
+                .help("Manifest file name (yaml, json or toml) in working directory"),
         ])
         .subcommands(commands::cmds())
         .get_matches_from(cmd_and_args);
@@ -95,6 +95,7 @@ where
 #[cfg(test)]
 mod tests {
     use crate::prelude::*;
+    use crate::testing::*;
     use tokio::sync::mpsc::Receiver;
 
     #[derive(Clone, Debug)]
@@ -0,0 +1,291 @@
+use super::Collect;
+use crate::common::{ConfigInto, FromConfig, FromPath, Period};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::Deserialize;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use tokio::time::Interval;
+
+#[derive(Clone, Deserialize)]
+pub enum FileCompression {
+    Gzip,
+}
+
+impl FileCompression {
+    fn extension(&self) -> &'static str {
+        match self {
+            FileCompression::Gzip => "gz",
+        }
+    }
+
+    fn compressed_path(&self, path: &Path) -> PathBuf {
+        let mut compressed_path = path.as_os_str().to_owned();
+        compressed_path.push(".");
+        compressed_path.push(self.extension());
+        PathBuf::from(compressed_path)
+    }
+
+    /// Compress file into `<path>.<ext>` and remove origin, never overwrite existing file
+    fn compress(&self, path: &Path) -> io::Result<PathBuf> {
+        let compressed_path = self.compressed_path(path);
+        let mut reader = BufReader::new(File::open(path)?);
+        match self {
+            FileCompression::Gzip => {
+                let file = OpenOptions::new()
+                    .write(true)
+                    .create_new(true)
+                    .open(&compressed_path)?;
+                let writer = BufWriter::new(file);
+                let mut encoder = GzEncoder::new(writer, Compression::default());
+                io::copy(&mut reader, &mut encoder)?;
+                encoder.finish()?.flush()?;
+            }
+        }
+        fs::remove_file(path)?;
+        Ok(compressed_path)
+    }
+}
+
+#[derive(Deserialize)]
+pub struct RollingFileWriterConfig {
+    /// Directory of rolling files
+    pub directory: String,
+    /// File name template, placeholders:
+    /// `{name}`, `{date}` (%Y%m%d), `{hour}` (%H), `{minute}` (%M), `{timestamp}` (millis), `{seq}`
+    pub filename: String,
+    /// Value of `{name}` placeholder, e.g. pipe name
+    pub name: Option<String>,
+    /// Rotate once file size exceeds bytes
+    pub max_size: Option<u64>,
+    /// Rotate once file contains records
+    pub max_records: Option<u64>,
+    /// Rotate current file and send closed file paths in period
+    pub rotate_period: Period,
+    /// Appended after each record
+    pub separator: Option<String>,
+    /// Compress file on close
+    pub compression: Option<FileCompression>,
+}
+
+impl FromPath for RollingFileWriterConfig {}
+
+impl ConfigInto<RollingFileWriter> for RollingFileWriterConfig {}
+
+struct RollingFile {
+    path: PathBuf,
+    writer: BufWriter<File>,
+    size: u64,
+    records: u64,
+}
+
+/// Append records to current file, rotate by size, record count or period
+pub struct RollingFileWriter {
+    directory: PathBuf,
+    filename: String,
+    name: String,
+    max_size: Option<u64>,
+    max_records: Option<u64>,
+    rotate_period: Period,
+    separator: Vec<u8>,
+    compression: Option<FileCompression>,
+    /// Sequence of next file
+    seq: u64,
+    current: Option<RollingFile>,
+    /// Closed file paths not yet sent
+    closed: Vec<PathBuf>,
+}
+
+#[async_trait]
+impl FromConfig<RollingFileWriterConfig> for RollingFileWriter {
+    async fn from_config(config: RollingFileWriterConfig) -> anyhow::Result<Self> {
+        let directory = PathBuf::from(config.directory);
+        fs::create_dir_all(&directory)?;
+        Ok(RollingFileWriter {
+            directory,
+            filename: config.filename,
+            name: config.name.unwrap_or_default(),
+            max_size: config.max_size,
+            max_records: config.max_records,
+            rotate_period: config.rotate_period,
+            separator: config.separator.unwrap_or_default().into_bytes(),
+            compression: config.compression,
+            seq: 0,
+            current: None,
+            closed: vec![],
+        })
+    }
+}
+
+impl RollingFileWriter {
+    fn render_filename(&self, now: &DateTime<Utc>, seq: u64) -> String {
+        self.filename
+            .replace("{name}", &self.name)
+            .replace("{date}", &now.format("%Y%m%d").to_string())
+            .replace("{hour}", &now.format("%H").to_string())
+            .replace("{minute}", &now.format("%M").to_string())
+            .replace("{timestamp}", &now.timestamp_millis().to_string())
+            .replace("{seq}", &seq.to_string())
+    }
+
+    /// Whether file or its compressed file exists, e.g written before restart
+    fn exists(&self, path: &Path) -> bool {
+        path.exists()
+            || matches!(self.compression, Some(ref compression) if compression.compressed_path(path).exists())
+    }
+
+    /// Open file with next sequence not yet exists
+    fn open(&mut self) -> anyhow::Result<RollingFile> {
+        let now = Utc::now();
+        loop {
+            let path = self.directory.join(self.render_filename(&now, self.seq));
+            self.seq += 1;
+            if self.exists(&path) {
+                anyhow::ensure!(
+                    self.filename.contains("{seq}") || self.filename.contains("{timestamp}"),
+                    "rolling file '{}' exists, add {{seq}} to filename",
+                    path.display()
+                );
+                continue;
+            }
+            let writer = BufWriter::new(File::create(&path)?);
+            return Ok(RollingFile {
+                path,
+                writer,
+                size: 0,
+                records: 0,
+            });
+        }
+    }
+
+    fn close(&mut self) -> anyhow::Result<()> {
+        let mut file = match self.current.take() {
+            Some(file) => file,
+            None => return Ok(()),
+        };
+        file.writer.flush()?;
+        drop(file.writer);
+        let path = match self.compression {
+            Some(ref compression) => compression.compress(&file.path)?,
+            None => file.path,
+        };
+        self.closed.push(path);
+        Ok(())
+    }
+
+    fn is_full(&self, file: &RollingFile) -> bool {
+        matches!(self.max_size, Some(max_size) if file.size >= max_size)
+            || matches!(self.max_records, Some(max_records) if file.records >= max_records)
+    }
+}
+
+/// # Parameters
+/// * T: input
+/// * Vec<PathBuf>: output, closed file paths
+#[async_trait]
+impl<T> Collect<T, Vec<PathBuf>, RollingFileWriterConfig> for RollingFileWriter
+where
+    T: AsRef<[u8]> + Send + 'static,
+{
+    async fn collect(&mut self, t: T) -> anyhow::Result<()> {
+        if self.current.is_none() {
+            self.current = Some(self.open()?);
+        }
+        let file = self.current.as_mut().unwrap();
+        let bytes = t.as_ref();
+        file.writer.write_all(bytes)?;
+        file.writer.write_all(&self.separator)?;
+        file.size += (bytes.len() + self.separator.len()) as u64;
+        file.records += 1;
+        if self.is_full(self.current.as_ref().unwrap()) {
+            self.close()?;
+        }
+        Ok(())
+    }
+
+    async fn flush(&mut self) -> anyhow::Result<Option<Vec<PathBuf>>> {
+        self.close()?;
+        if self.closed.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(std::mem::take(&mut self.closed)))
+    }
+
+    fn get_flush_interval(&self) -> Interval {
+        let rotate_period = self.rotate_period.clone();
+        tokio::time::interval(rotate_period.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::prelude::*;
+    use crate::testing::*;
+    use flate2::read::GzDecoder;
+    use std::fs::{self, File};
+    use std::io::Read;
+    use std::path::PathBuf;
+
+    fn records() -> Vec<String> {
+        (0..5).map(|i| format!("record{}", i)).collect()
+    }
+
+    fn read_gzip(path: &PathBuf) -> String {
+        let mut decoder = GzDecoder::new(File::open(path).unwrap());
+        let mut content = String::new();
+        decoder.read_to_string(&mut content).unwrap();
+        content
+    }
+
+    #[tokio::test]
+    async fn test_rolling_file_writer() {
+        let pipe = collector!("rolling_file_writer");
+        let config = config!(
+            RollingFileWriterConfig,
+            "resources/catalogs/rolling_file_writer.yml"
+        );
+        let outputs = run_pipe_with_records(pipe, config, records())
+            .await
+            .expect("rolling file writer failed");
+        let mut paths: Vec<PathBuf> = outputs.into_records().into_iter().flatten().collect();
+        paths.sort();
+        let expected: Vec<PathBuf> = (0..3)
+            .map(|seq| PathBuf::from(format!("resources/rolling/events-{}.txt.gz", seq)))
+            .collect();
+        assert_eq!(expected, paths);
+        assert_eq!("record0\nrecord1\n", read_gzip(&paths[0]));
+        assert_eq!("record2\nrecord3\n", read_gzip(&paths[1]));
+        assert_eq!("record4\n", read_gzip(&paths[2]));
+        fs::remove_dir_all("resources/rolling").unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_rolling_file_writer_restart() {
+        let _ = fs::remove_dir_all("resources/rolling_restart");
+        let mut paths: Vec<PathBuf> = vec![];
+        // writer restarts with sequence from 0
+        for _ in 0..2 {
+            let pipe = collector!("rolling_file_writer");
+            let config = config!(
+                RollingFileWriterConfig,
+                "resources/catalogs/rolling_file_writer_restart.yml"
+            );
+            let outputs = run_pipe_with_records(pipe, config, records())
+                .await
+                .expect("rolling file writer failed");
+            paths.extend(outputs.into_records().into_iter().flatten());
+        }
+        paths.sort();
+        let expected: Vec<PathBuf> = (0..6)
+            .map(|seq| PathBuf::from(format!("resources/rolling_restart/events-{}.txt.gz", seq)))
+            .collect();
+        assert_eq!(expected, paths);
+        assert_eq!("record0\nrecord1\n", read_gzip(&paths[0]));
+        assert_eq!("record0\nrecord1\n", read_gzip(&paths[3]));
+        fs::remove_dir_all("resources/rolling_restart").unwrap();
+    }
+}
@@ -1,9 +1,11 @@
 mod bag;
+mod file;
 mod runtime;
 mod set;
 mod text;
 mod window;
 pub use bag::*;
+pub use file::*;
 pub use runtime::*;
 pub use set::*;
 pub use text::*;
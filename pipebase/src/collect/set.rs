@@ -97,6 +97,7 @@ where
 #[cfg(test)]
 mod tests {
     use crate::prelude::*;
+    use crate::testing::*;
 
     #[derive(Clone, Debug, Eq, HashedBy, Equal)]
     struct Record {
@@ -184,6 +184,7 @@ mod left_right_tests {
 mod pair_tests {
 
     use crate::prelude::*;
+    use crate::testing::*;
     use std::collections::hash_map::DefaultHasher;
     use std::hash::{Hash, Hasher};
 
@@ -0,0 +1,23 @@
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+/// Record captured on pipe edge, one json line per record in capture file
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct CapturedRecord<T> {
+    /// Capture time in milliseconds since epoch
+    pub timestamp: i64,
+    pub record: T,
+}
+
+impl<T> CapturedRecord<T> {
+    pub fn new(record: T) -> Self {
+        CapturedRecord {
+            timestamp: Utc::now().timestamp_millis(),
+            record,
+        }
+    }
+
+    pub fn into_record(self) -> T {
+        self.record
+    }
+}
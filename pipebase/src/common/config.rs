@@ -1,5 +1,45 @@
 use async_trait::async_trait;
 use serde::de::DeserializeOwned;
+use std::path::Path;
+
+/// Config file format, resolved by file extension
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ConfigFormat {
+    Yaml,
+    Json,
+    Toml,
+}
+
+impl ConfigFormat {
+    /// `.json` and `.toml` files are parsed accordingly, yaml otherwise
+    pub fn from_path<P>(path: P) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        let extension = path
+            .as_ref()
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(|extension| extension.to_lowercase());
+        match extension.as_deref() {
+            Some("json") => ConfigFormat::Json,
+            Some("toml") => ConfigFormat::Toml,
+            _ => ConfigFormat::Yaml,
+        }
+    }
+
+    pub fn deserialize<T>(&self, bytes: &[u8]) -> anyhow::Result<T>
+    where
+        T: DeserializeOwned,
+    {
+        let t = match self {
+            ConfigFormat::Yaml => serde_yaml::from_slice::<T>(bytes)?,
+            ConfigFormat::Json => serde_json::from_slice::<T>(bytes)?,
+            ConfigFormat::Toml => toml::from_slice::<T>(bytes)?,
+        };
+        Ok(t)
+    }
+}
 
 #[async_trait]
 pub trait FromPath: Sized + DeserializeOwned {
@@ -7,8 +47,9 @@ pub trait FromPath: Sized + DeserializeOwned {
     where
         P: AsRef<std::path::Path> + Send,
     {
-        let file = std::fs::File::open(path)?;
-        let config = serde_yaml::from_reader::<std::fs::File, Self>(file)?;
+        let format = ConfigFormat::from_path(&path);
+        let bytes = std::fs::read(path)?;
+        let config = format.deserialize::<Self>(&bytes)?;
         Ok(config)
     }
 }
@@ -42,3 +83,34 @@ macro_rules! config {
         }
     };
 }
+
+#[cfg(test)]
+mod tests {
+
+    use crate::prelude::*;
+    use std::time::Duration;
+
+    async fn assert_context_printer_config(path: &str) {
+        let config = config!(ContextPrinterConfig, path);
+        let interval: Duration = config.interval.into();
+        let delay: Duration = config.delay.unwrap().into();
+        assert_eq!(Duration::from_millis(1000), interval);
+        assert_eq!(Duration::from_millis(1000), delay);
+    }
+
+    #[test]
+    fn test_config_format() {
+        assert_eq!(ConfigFormat::Yaml, ConfigFormat::from_path("config.yml"));
+        assert_eq!(ConfigFormat::Yaml, ConfigFormat::from_path("config.yaml"));
+        assert_eq!(ConfigFormat::Yaml, ConfigFormat::from_path("config"));
+        assert_eq!(ConfigFormat::Json, ConfigFormat::from_path("config.json"));
+        assert_eq!(ConfigFormat::Toml, ConfigFormat::from_path("config.TOML"));
+    }
+
+    #[tokio::test]
+    async fn test_config_from_yaml_json_toml() {
+        assert_context_printer_config("resources/catalogs/context_printer.yml").await;
+        assert_context_printer_config("resources/catalogs/context_printer.json").await;
+        assert_context_printer_config("resources/catalogs/context_printer.toml").await;
+    }
+}
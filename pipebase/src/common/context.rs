@@ -85,7 +85,7 @@ impl Context {
     }
 }
 
-#[derive(Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct PipeContext {
     name: String,
     state: String,
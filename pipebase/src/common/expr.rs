@@ -0,0 +1,726 @@
+use serde::Serialize;
+use serde_json::{Map, Number, Value};
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+/// Convert serializable record into json value evaluated by expression
+pub fn to_expr_value<T: Serialize>(t: &T) -> anyhow::Result<Value> {
+    Ok(serde_json::to_value(t)?)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Number(Value),
+    Str(String),
+    Ident(String),
+    Root,
+    LParen,
+    RParen,
+    LBracket,
+    RBracket,
+    Comma,
+    Dot,
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Percent,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    And,
+    Or,
+    Not,
+}
+
+fn tokenize(source: &str) -> anyhow::Result<Vec<Token>> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens: Vec<Token> = vec![];
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        if c.is_ascii_digit() {
+            let start = i;
+            let mut is_float = false;
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                // member access after number is not supported, dot is decimal point
+                is_float |= chars[i] == '.';
+                i += 1;
+            }
+            let text: String = chars[start..i].iter().collect();
+            let number = match is_float {
+                true => Number::from_f64(text.parse::<f64>()?)
+                    .ok_or_else(|| anyhow::anyhow!("invalid number '{}'", text))?,
+                false => Number::from(text.parse::<i64>()?),
+            };
+            tokens.push(Token::Number(Value::Number(number)));
+            continue;
+        }
+        if c.is_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push(Token::Ident(chars[start..i].iter().collect()));
+            continue;
+        }
+        if c == '\'' || c == '"' {
+            let quote = c;
+            let mut text = String::new();
+            i += 1;
+            loop {
+                let c = match chars.get(i) {
+                    Some(c) => *c,
+                    None => anyhow::bail!("unterminated string in '{}'", source),
+                };
+                i += 1;
+                if c == quote {
+                    break;
+                }
+                if c == '\\' {
+                    match chars.get(i) {
+                        Some('n') => text.push('\n'),
+                        Some('t') => text.push('\t'),
+                        Some(c) => text.push(*c),
+                        None => anyhow::bail!("unterminated string in '{}'", source),
+                    }
+                    i += 1;
+                    continue;
+                }
+                text.push(c);
+            }
+            tokens.push(Token::Str(text));
+            continue;
+        }
+        let next = chars.get(i + 1).copied();
+        let (token, width) = match (c, next) {
+            ('=', Some('=')) => (Token::Eq, 2),
+            ('!', Some('=')) => (Token::Ne, 2),
+            ('<', Some('=')) => (Token::Le, 2),
+            ('>', Some('=')) => (Token::Ge, 2),
+            ('&', Some('&')) => (Token::And, 2),
+            ('|', Some('|')) => (Token::Or, 2),
+            ('<', _) => (Token::Lt, 1),
+            ('>', _) => (Token::Gt, 1),
+            ('!', _) => (Token::Not, 1),
+            ('(', _) => (Token::LParen, 1),
+            (')', _) => (Token::RParen, 1),
+            ('[', _) => (Token::LBracket, 1),
+            (']', _) => (Token::RBracket, 1),
+            (',', _) => (Token::Comma, 1),
+            ('.', _) => (Token::Dot, 1),
+            ('+', _) => (Token::Plus, 1),
+            ('-', _) => (Token::Minus, 1),
+            ('*', _) => (Token::Star, 1),
+            ('/', _) => (Token::Slash, 1),
+            ('%', _) => (Token::Percent, 1),
+            ('$', _) => (Token::Root, 1),
+            _ => anyhow::bail!("unexpected character '{}' at {} in '{}'", c, i, source),
+        };
+        tokens.push(token);
+        i += width;
+    }
+    Ok(tokens)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    And,
+    Or,
+}
+
+impl BinaryOp {
+    fn from_token(token: &Token) -> Option<(BinaryOp, u8)> {
+        let op = match token {
+            Token::Or => (BinaryOp::Or, 1),
+            Token::And => (BinaryOp::And, 2),
+            Token::Eq => (BinaryOp::Eq, 3),
+            Token::Ne => (BinaryOp::Ne, 3),
+            Token::Lt => (BinaryOp::Lt, 4),
+            Token::Le => (BinaryOp::Le, 4),
+            Token::Gt => (BinaryOp::Gt, 4),
+            Token::Ge => (BinaryOp::Ge, 4),
+            Token::Plus => (BinaryOp::Add, 5),
+            Token::Minus => (BinaryOp::Sub, 5),
+            Token::Star => (BinaryOp::Mul, 6),
+            Token::Slash => (BinaryOp::Div, 6),
+            Token::Percent => (BinaryOp::Rem, 6),
+            _ => return None,
+        };
+        Some(op)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Function {
+    Len,
+    Lower,
+    Upper,
+    Trim,
+    Contains,
+    StartsWith,
+    EndsWith,
+    Concat,
+    Substr,
+    Abs,
+    Round,
+    ToString,
+    ToNumber,
+    IsNull,
+    Coalesce,
+    If,
+}
+
+impl FromStr for Function {
+    type Err = anyhow::Error;
+
+    fn from_str(name: &str) -> anyhow::Result<Self> {
+        let function = match name {
+            "len" => Function::Len,
+            "lower" => Function::Lower,
+            "upper" => Function::Upper,
+            "trim" => Function::Trim,
+            "contains" => Function::Contains,
+            "starts_with" => Function::StartsWith,
+            "ends_with" => Function::EndsWith,
+            "concat" => Function::Concat,
+            "substr" => Function::Substr,
+            "abs" => Function::Abs,
+            "round" => Function::Round,
+            "to_string" => Function::ToString,
+            "to_number" => Function::ToNumber,
+            "is_null" => Function::IsNull,
+            "coalesce" => Function::Coalesce,
+            "if" => Function::If,
+            _ => anyhow::bail!("unknown function '{}'", name),
+        };
+        Ok(function)
+    }
+}
+
+impl Function {
+    /// Accepted number of arguments, None if variadic
+    fn arity(&self) -> Option<usize> {
+        match self {
+            Function::Concat | Function::Coalesce => None,
+            Function::Contains | Function::StartsWith | Function::EndsWith => Some(2),
+            Function::Substr | Function::If => Some(3),
+            _ => Some(1),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Node {
+    Literal(Value),
+    Root,
+    Field(String),
+    Member(Box<Node>, String),
+    Index(Box<Node>, Box<Node>),
+    Neg(Box<Node>),
+    Not(Box<Node>),
+    Binary(BinaryOp, Box<Node>, Box<Node>),
+    Call(Function, Vec<Node>),
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn expect(&mut self, expected: Token) -> anyhow::Result<()> {
+        match self.next() {
+            Some(token) if token == expected => Ok(()),
+            Some(token) => anyhow::bail!("expect {:?}, found {:?}", expected, token),
+            None => anyhow::bail!("expect {:?}, found end of expression", expected),
+        }
+    }
+
+    fn parse_expr(&mut self, min_precedence: u8) -> anyhow::Result<Node> {
+        let mut lhs = self.parse_unary()?;
+        while let Some((op, precedence)) = self.peek().and_then(BinaryOp::from_token) {
+            if precedence < min_precedence {
+                break;
+            }
+            self.next();
+            // left associative
+            let rhs = self.parse_expr(precedence + 1)?;
+            lhs = Node::Binary(op, Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_unary(&mut self) -> anyhow::Result<Node> {
+        match self.peek() {
+            Some(Token::Minus) => {
+                self.next();
+                Ok(Node::Neg(Box::new(self.parse_unary()?)))
+            }
+            Some(Token::Not) => {
+                self.next();
+                Ok(Node::Not(Box::new(self.parse_unary()?)))
+            }
+            _ => self.parse_postfix(),
+        }
+    }
+
+    fn parse_postfix(&mut self) -> anyhow::Result<Node> {
+        let mut node = self.parse_primary()?;
+        loop {
+            match self.peek() {
+                Some(Token::Dot) => {
+                    self.next();
+                    match self.next() {
+                        Some(Token::Ident(name)) => node = Node::Member(Box::new(node), name),
+                        token => anyhow::bail!("expect field name after '.', found {:?}", token),
+                    }
+                }
+                Some(Token::LBracket) => {
+                    self.next();
+                    let index = self.parse_expr(0)?;
+                    self.expect(Token::RBracket)?;
+                    node = Node::Index(Box::new(node), Box::new(index));
+                }
+                _ => return Ok(node),
+            }
+        }
+    }
+
+    fn parse_primary(&mut self) -> anyhow::Result<Node> {
+        let token = match self.next() {
+            Some(token) => token,
+            None => anyhow::bail!("unexpected end of expression"),
+        };
+        match token {
+            Token::Number(number) => Ok(Node::Literal(number)),
+            Token::Str(text) => Ok(Node::Literal(Value::String(text))),
+            Token::Root => Ok(Node::Root),
+            Token::LParen => {
+                let node = self.parse_expr(0)?;
+                self.expect(Token::RParen)?;
+                Ok(node)
+            }
+            Token::Ident(name) => match name.as_str() {
+                "true" => Ok(Node::Literal(Value::Bool(true))),
+                "false" => Ok(Node::Literal(Value::Bool(false))),
+                "null" => Ok(Node::Literal(Value::Null)),
+                _ if self.peek() == Some(&Token::LParen) => self.parse_call(&name),
+                _ => Ok(Node::Field(name)),
+            },
+            token => anyhow::bail!("unexpected token {:?}", token),
+        }
+    }
+
+    fn parse_call(&mut self, name: &str) -> anyhow::Result<Node> {
+        let function: Function = name.parse()?;
+        self.expect(Token::LParen)?;
+        let mut args: Vec<Node> = vec![];
+        if self.peek() != Some(&Token::RParen) {
+            loop {
+                args.push(self.parse_expr(0)?);
+                match self.next() {
+                    Some(Token::Comma) => continue,
+                    Some(Token::RParen) => break,
+                    token => {
+                        anyhow::bail!("expect ',' or ')' in call '{}', found {:?}", name, token)
+                    }
+                }
+            }
+        } else {
+            self.next();
+        }
+        if let Some(arity) = function.arity() {
+            anyhow::ensure!(
+                args.len() == arity,
+                "function '{}' expects {} arguments, found {}",
+                name,
+                arity,
+                args.len()
+            );
+        }
+        Ok(Node::Call(function, args))
+    }
+}
+
+/// Expression evaluated against json value of record, supports
+/// * literals: `1`, `2.5`, `'text'`, `true`, `null`
+/// * field access: `price`, `user.name`, `items[0]`, `$` for whole record
+/// * operators: `+ - * / %`, `== != < <= > >=`, `&& || !`
+/// * functions: `len`, `lower`, `upper`, `trim`, `contains`, `starts_with`, `ends_with`,
+///   `concat`, `substr`, `abs`, `round`, `to_string`, `to_number`, `is_null`, `coalesce`, `if`
+#[derive(Clone, Debug)]
+pub struct Expression {
+    source: String,
+    node: Node,
+}
+
+impl FromStr for Expression {
+    type Err = anyhow::Error;
+
+    fn from_str(source: &str) -> anyhow::Result<Self> {
+        Expression::parse(source)
+    }
+}
+
+impl Display for Expression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
+
+impl Expression {
+    pub fn parse(source: &str) -> anyhow::Result<Self> {
+        let mut parser = Parser {
+            tokens: tokenize(source)?,
+            position: 0,
+        };
+        let node = parser.parse_expr(0)?;
+        if let Some(token) = parser.peek() {
+            anyhow::bail!("unexpected token {:?} in '{}'", token, source);
+        }
+        Ok(Expression {
+            source: source.to_owned(),
+            node,
+        })
+    }
+
+    pub fn eval(&self, record: &Value) -> anyhow::Result<Value> {
+        match eval(&self.node, record) {
+            Ok(value) => Ok(value),
+            Err(err) => Err(err.context(format!("failed to evaluate '{}'", self.source))),
+        }
+    }
+
+    /// Evaluate predicate, null is false
+    pub fn eval_bool(&self, record: &Value) -> anyhow::Result<bool> {
+        let value = self.eval(record)?;
+        match as_bool(&value) {
+            Ok(b) => Ok(b),
+            Err(err) => Err(err.context(format!("failed to evaluate '{}'", self.source))),
+        }
+    }
+}
+
+fn as_bool(value: &Value) -> anyhow::Result<bool> {
+    match value {
+        Value::Bool(b) => Ok(*b),
+        Value::Null => Ok(false),
+        _ => anyhow::bail!("expect bool, found {}", value),
+    }
+}
+
+fn as_f64(value: &Value) -> anyhow::Result<f64> {
+    match value.as_f64() {
+        Some(f) => Ok(f),
+        None => anyhow::bail!("expect number, found {}", value),
+    }
+}
+
+fn as_str(value: &Value) -> anyhow::Result<&str> {
+    match value.as_str() {
+        Some(s) => Ok(s),
+        None => anyhow::bail!("expect string, found {}", value),
+    }
+}
+
+fn from_f64(f: f64) -> anyhow::Result<Value> {
+    match Number::from_f64(f) {
+        Some(number) => Ok(Value::Number(number)),
+        None => anyhow::bail!("invalid number {}", f),
+    }
+}
+
+fn to_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.to_owned(),
+        _ => value.to_string(),
+    }
+}
+
+fn eval(node: &Node, record: &Value) -> anyhow::Result<Value> {
+    let value = match node {
+        Node::Literal(value) => value.to_owned(),
+        Node::Root => record.to_owned(),
+        Node::Field(name) => record.get(name).cloned().unwrap_or(Value::Null),
+        Node::Member(node, name) => eval(node, record)?
+            .get(name)
+            .cloned()
+            .unwrap_or(Value::Null),
+        Node::Index(node, index) => {
+            let value = eval(node, record)?;
+            match eval(index, record)? {
+                Value::String(key) => value.get(key.as_str()).cloned(),
+                Value::Number(i) => i.as_u64().and_then(|i| value.get(i as usize)).cloned(),
+                index => anyhow::bail!("invalid index {}", index),
+            }
+            .unwrap_or(Value::Null)
+        }
+        Node::Neg(node) => {
+            let value = eval(node, record)?;
+            // i64::MIN overflows, fall back to f64
+            match value.as_i64().and_then(i64::checked_neg) {
+                Some(i) => Value::from(i),
+                None => from_f64(-as_f64(&value)?)?,
+            }
+        }
+        Node::Not(node) => Value::Bool(!as_bool(&eval(node, record)?)?),
+        Node::Binary(op, lhs, rhs) => eval_binary(*op, lhs, rhs, record)?,
+        Node::Call(function, args) => eval_call(*function, args, record)?,
+    };
+    Ok(value)
+}
+
+fn eval_binary(op: BinaryOp, lhs: &Node, rhs: &Node, record: &Value) -> anyhow::Result<Value> {
+    // short circuit
+    match op {
+        BinaryOp::And => {
+            let value = as_bool(&eval(lhs, record)?)? && as_bool(&eval(rhs, record)?)?;
+            return Ok(Value::Bool(value));
+        }
+        BinaryOp::Or => {
+            let value = as_bool(&eval(lhs, record)?)? || as_bool(&eval(rhs, record)?)?;
+            return Ok(Value::Bool(value));
+        }
+        _ => (),
+    };
+    let lhs = eval(lhs, record)?;
+    let rhs = eval(rhs, record)?;
+    let value = match op {
+        BinaryOp::Eq => Value::Bool(equals(&lhs, &rhs)),
+        BinaryOp::Ne => Value::Bool(!equals(&lhs, &rhs)),
+        BinaryOp::Lt | BinaryOp::Le | BinaryOp::Gt | BinaryOp::Ge => {
+            let ordering = compare(&lhs, &rhs)?;
+            let value = match op {
+                BinaryOp::Lt => ordering.is_lt(),
+                BinaryOp::Le => ordering.is_le(),
+                BinaryOp::Gt => ordering.is_gt(),
+                _ => ordering.is_ge(),
+            };
+            Value::Bool(value)
+        }
+        BinaryOp::Add if lhs.is_string() || rhs.is_string() => {
+            Value::String(format!("{}{}", to_text(&lhs), to_text(&rhs)))
+        }
+        _ => arithmetic(op, &lhs, &rhs)?,
+    };
+    Ok(value)
+}
+
+fn equals(lhs: &Value, rhs: &Value) -> bool {
+    match (lhs.as_f64(), rhs.as_f64()) {
+        (Some(l), Some(r)) => l == r,
+        _ => lhs == rhs,
+    }
+}
+
+fn compare(lhs: &Value, rhs: &Value) -> anyhow::Result<std::cmp::Ordering> {
+    if let (Some(l), Some(r)) = (lhs.as_str(), rhs.as_str()) {
+        return Ok(l.cmp(r));
+    }
+    let l = as_f64(lhs)?;
+    let r = as_f64(rhs)?;
+    match l.partial_cmp(&r) {
+        Some(ordering) => Ok(ordering),
+        None => anyhow::bail!("can not compare {} with {}", lhs, rhs),
+    }
+}
+
+fn arithmetic(op: BinaryOp, lhs: &Value, rhs: &Value) -> anyhow::Result<Value> {
+    if let (Some(l), Some(r)) = (lhs.as_i64(), rhs.as_i64()) {
+        let value = match op {
+            BinaryOp::Add => l.checked_add(r),
+            BinaryOp::Sub => l.checked_sub(r),
+            BinaryOp::Mul => l.checked_mul(r),
+            BinaryOp::Rem => l.checked_rem(r),
+            _ => None,
+        };
+        if let Some(value) = value {
+            return Ok(Value::from(value));
+        }
+    }
+    let l = as_f64(lhs)?;
+    let r = as_f64(rhs)?;
+    let value = match op {
+        BinaryOp::Add => l + r,
+        BinaryOp::Sub => l - r,
+        BinaryOp::Mul => l * r,
+        BinaryOp::Div => l / r,
+        BinaryOp::Rem => l % r,
+        _ => unreachable!(),
+    };
+    from_f64(value)
+}
+
+fn eval_call(function: Function, args: &[Node], record: &Value) -> anyhow::Result<Value> {
+    // lazy branches
+    match function {
+        Function::If => {
+            let branch = match as_bool(&eval(&args[0], record)?)? {
+                true => &args[1],
+                false => &args[2],
+            };
+            return eval(branch, record);
+        }
+        Function::Coalesce => {
+            for arg in args {
+                let value = eval(arg, record)?;
+                if !value.is_null() {
+                    return Ok(value);
+                }
+            }
+            return Ok(Value::Null);
+        }
+        _ => (),
+    };
+    let values = args
+        .iter()
+        .map(|arg| eval(arg, record))
+        .collect::<anyhow::Result<Vec<Value>>>()?;
+    let value = match function {
+        Function::Len => match &values[0] {
+            Value::String(s) => Value::from(s.chars().count()),
+            Value::Array(a) => Value::from(a.len()),
+            Value::Object(o) => Value::from(o.len()),
+            Value::Null => Value::from(0),
+            value => anyhow::bail!("len of {} is not supported", value),
+        },
+        Function::Lower => Value::String(as_str(&values[0])?.to_lowercase()),
+        Function::Upper => Value::String(as_str(&values[0])?.to_uppercase()),
+        Function::Trim => Value::String(as_str(&values[0])?.trim().to_owned()),
+        Function::Contains => match &values[0] {
+            Value::String(s) => Value::Bool(s.contains(as_str(&values[1])?)),
+            Value::Array(a) => Value::Bool(a.iter().any(|v| equals(v, &values[1]))),
+            Value::Object(o) => Value::Bool(o.contains_key(as_str(&values[1])?)),
+            value => anyhow::bail!("contains on {} is not supported", value),
+        },
+        Function::StartsWith => Value::Bool(as_str(&values[0])?.starts_with(as_str(&values[1])?)),
+        Function::EndsWith => Value::Bool(as_str(&values[0])?.ends_with(as_str(&values[1])?)),
+        Function::Concat => Value::String(values.iter().map(to_text).collect()),
+        Function::Substr => {
+            let s = as_str(&values[0])?;
+            let start = as_f64(&values[1])? as usize;
+            let len = as_f64(&values[2])? as usize;
+            Value::String(s.chars().skip(start).take(len).collect())
+        }
+        Function::Abs => match values[0].as_i64().and_then(i64::checked_abs) {
+            Some(i) => Value::from(i),
+            None => from_f64(as_f64(&values[0])?.abs())?,
+        },
+        Function::Round => match values[0].as_i64() {
+            Some(i) => Value::from(i),
+            None => Value::from(as_f64(&values[0])?.round() as i64),
+        },
+        Function::ToString => Value::String(to_text(&values[0])),
+        Function::ToNumber => match &values[0] {
+            Value::Number(n) => Value::Number(n.to_owned()),
+            Value::String(s) => match s.trim().parse::<i64>() {
+                Ok(i) => Value::from(i),
+                Err(_) => from_f64(s.trim().parse::<f64>()?)?,
+            },
+            Value::Bool(b) => Value::from(*b as i64),
+            value => anyhow::bail!("can not convert {} to number", value),
+        },
+        Function::IsNull => Value::Bool(values[0].is_null()),
+        Function::If | Function::Coalesce => unreachable!(),
+    };
+    Ok(value)
+}
+
+/// Named expressions evaluated into json object
+pub fn eval_object(
+    fields: &[(String, Expression)],
+    record: &Value,
+) -> anyhow::Result<Map<String, Value>> {
+    let mut object = Map::new();
+    for (name, expr) in fields {
+        object.insert(name.to_owned(), expr.eval(record)?);
+    }
+    Ok(object)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use serde_json::json;
+
+    fn eval(source: &str, record: &Value) -> Value {
+        Expression::parse(source).unwrap().eval(record).unwrap()
+    }
+
+    #[test]
+    fn test_expression() {
+        let record = json!({
+            "r0": 1,
+            "r1": 2.5,
+            "name": " Foo ",
+            "tags": ["a", "b"],
+            "user": { "id": 7, "email": "foo@bar.com" },
+        });
+        assert_eq!(json!(3.5), eval("r0 + r1", &record));
+        assert_eq!(json!(7), eval("1 + 2 * 3", &record));
+        assert_eq!(json!(9), eval("(1 + 2) * 3", &record));
+        assert_eq!(json!(-1), eval("-r0", &record));
+        assert_eq!(json!(2.5), eval("5 / 2", &record));
+        assert_eq!(json!(true), eval("r0 + r1 < 4 && !(r0 == 2)", &record));
+        assert_eq!(json!("foo"), eval("lower(trim(name))", &record));
+        assert_eq!(json!(7), eval("user.id", &record));
+        assert_eq!(json!("b"), eval("tags[1]", &record));
+        assert_eq!(
+            json!(true),
+            eval("ends_with(user['email'], '.com')", &record)
+        );
+        assert_eq!(json!(true), eval("contains(tags, 'a')", &record));
+        assert_eq!(json!(null), eval("missing.field", &record));
+        assert_eq!(json!("id-7"), eval("'id-' + user.id", &record));
+        assert_eq!(json!("big"), eval("if(r1 > 2, 'big', 'small')", &record));
+        assert_eq!(json!(0), eval("coalesce(missing, 0)", &record));
+        assert_eq!(json!(12), eval("to_number('12')", &record));
+        assert_eq!(json!(2), eval("len(tags)", &record));
+        assert_eq!(json!(true), eval("$.r0 == 1.0", &record));
+        let min = json!({ "i": i64::MIN });
+        assert_eq!(json!(-(i64::MIN as f64)), eval("-i", &min));
+        assert_eq!(json!(-(i64::MIN as f64)), eval("abs(i)", &min));
+        assert_eq!(json!(i64::MAX), eval("-(i + 1)", &min));
+        assert!(Expression::parse("missing > 1 || false")
+            .unwrap()
+            .eval_bool(&json!({}))
+            .is_err());
+        assert!(!Expression::parse("is_null(missing) && false")
+            .unwrap()
+            .eval_bool(&json!({}))
+            .unwrap());
+        assert!(Expression::parse("r0 +").is_err());
+        assert!(Expression::parse("unknown(r0)").is_err());
+        assert!(Expression::parse("lower(a, b)").is_err());
+    }
+}
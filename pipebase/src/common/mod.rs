@@ -1,16 +1,20 @@
 mod aggregate;
 mod bag;
 mod bootstrap;
+mod capture;
 mod config;
 mod context;
 mod convert;
 mod error;
+mod expr;
 mod field;
 mod filter;
 mod logging;
 mod pipe;
+mod pool;
 mod project;
 mod render;
+mod schema;
 mod set;
 mod split;
 mod time;
@@ -19,15 +23,19 @@ mod value;
 pub use aggregate::*;
 pub use bag::*;
 pub use bootstrap::*;
+pub use capture::*;
 pub use config::*;
 pub use context::*;
 pub use convert::*;
+pub use expr::*;
 pub use field::*;
 pub use filter::*;
 pub use logging::*;
 pub use pipe::*;
+pub use pool::*;
 pub use project::*;
 pub use render::*;
+pub use schema::*;
 pub use set::*;
 pub use split::*;
 pub use time::*;
@@ -113,16 +113,6 @@ where
     replicas
 }
 
-#[cfg(test)]
-pub(crate) async fn populate_records<T, U>(tx: Sender<T>, records: U)
-where
-    U: IntoIterator<Item = T>,
-{
-    for record in records {
-        let _ = tx.send(record).await;
-    }
-}
-
 #[macro_export]
 macro_rules! pipe_channels {
     {
@@ -0,0 +1,352 @@
+use super::Period;
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::{
+    future::Future,
+    ops::{Deref, DerefMut},
+    sync::Mutex,
+    time::Duration,
+};
+use tokio::sync::{Semaphore, SemaphorePermit};
+use tracing::warn;
+
+const DEFAULT_BACKOFF_INITIAL_MILLIS: u64 = 100;
+const DEFAULT_BACKOFF_MAX_MILLIS: u64 = 10000;
+const DEFAULT_BACKOFF_RETRIES: usize = 5;
+const DEFAULT_POOL_SIZE: usize = 1;
+
+#[derive(Clone, Default, Deserialize)]
+pub struct BackoffConfig {
+    /// Delay before first retry, 100 millis by default
+    pub initial: Option<Period>,
+    /// Delay doubled per retry up to max, 10 secs by default
+    pub max: Option<Period>,
+    /// Max number of retries, 5 by default
+    pub retries: Option<usize>,
+}
+
+/// Exponential backoff between retries
+#[derive(Clone)]
+pub struct Backoff {
+    initial: Duration,
+    max: Duration,
+    retries: usize,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Backoff::from(BackoffConfig::default())
+    }
+}
+
+impl From<BackoffConfig> for Backoff {
+    fn from(config: BackoffConfig) -> Self {
+        let initial = match config.initial {
+            Some(initial) => initial.into(),
+            None => Duration::from_millis(DEFAULT_BACKOFF_INITIAL_MILLIS),
+        };
+        let max = match config.max {
+            Some(max) => max.into(),
+            None => Duration::from_millis(DEFAULT_BACKOFF_MAX_MILLIS),
+        };
+        Backoff {
+            initial,
+            max,
+            retries: config.retries.unwrap_or(DEFAULT_BACKOFF_RETRIES),
+        }
+    }
+}
+
+impl Backoff {
+    /// Delay before each retry
+    pub fn delays(&self) -> impl Iterator<Item = Duration> {
+        let max = self.max;
+        std::iter::successors(Some(self.initial.min(max)), move |delay| {
+            Some((*delay * 2).min(max))
+        })
+        .take(self.retries)
+    }
+
+    /// Run until success or retries exhausted, last error returned
+    pub async fn retry<T, F, Fut>(&self, mut f: F) -> anyhow::Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = anyhow::Result<T>>,
+    {
+        let mut delays = self.delays();
+        loop {
+            let err = match f().await {
+                Ok(t) => return Ok(t),
+                Err(err) => err,
+            };
+            match delays.next() {
+                Some(delay) => {
+                    warn!("retry in {:?}, error: {:#}", delay, err);
+                    tokio::time::sleep(delay).await
+                }
+                None => return Err(err),
+            }
+        }
+    }
+
+    /// Blocking version of retry for sync clients
+    pub fn retry_blocking<T, F>(&self, mut f: F) -> anyhow::Result<T>
+    where
+        F: FnMut() -> anyhow::Result<T>,
+    {
+        let mut delays = self.delays();
+        loop {
+            let err = match f() {
+                Ok(t) => return Ok(t),
+                Err(err) => err,
+            };
+            match delays.next() {
+                Some(delay) => {
+                    warn!("retry in {:?}, error: {:#}", delay, err);
+                    std::thread::sleep(delay)
+                }
+                None => return Err(err),
+            }
+        }
+    }
+}
+
+#[derive(Clone, Default, Deserialize)]
+pub struct PoolConfig {
+    /// Max number of connections, 1 by default
+    pub size: Option<usize>,
+    /// Backoff between reconnects
+    pub backoff: Option<BackoffConfig>,
+}
+
+impl PoolConfig {
+    pub fn get_size(&self) -> usize {
+        self.size.unwrap_or(DEFAULT_POOL_SIZE).max(1)
+    }
+
+    pub fn get_backoff(&self) -> Backoff {
+        self.backoff.to_owned().unwrap_or_default().into()
+    }
+}
+
+/// Create and health check pooled connection
+#[async_trait]
+pub trait ConnectionManager: Send + Sync {
+    type Connection: Send;
+
+    async fn connect(&self) -> anyhow::Result<Self::Connection>;
+
+    /// Check connection before handed out, broken connection dropped and reconnected
+    async fn check(&self, connection: &mut Self::Connection) -> anyhow::Result<()>;
+}
+
+/// Pool of connections, reconnect with backoff
+pub struct Pool<M>
+where
+    M: ConnectionManager,
+{
+    manager: M,
+    idle: Mutex<Vec<M::Connection>>,
+    permits: Semaphore,
+    backoff: Backoff,
+}
+
+impl<M> Pool<M>
+where
+    M: ConnectionManager,
+{
+    /// Create pool with first connection established
+    pub async fn new(manager: M, config: PoolConfig) -> anyhow::Result<Self> {
+        let backoff = config.get_backoff();
+        let connection = backoff.retry(|| manager.connect()).await?;
+        Ok(Pool {
+            manager,
+            idle: Mutex::new(vec![connection]),
+            permits: Semaphore::new(config.get_size()),
+            backoff,
+        })
+    }
+
+    /// Get healthy idle connection or connect, wait if all connections in use
+    pub async fn get(&self) -> anyhow::Result<PooledConnection<'_, M>> {
+        let permit = self.permits.acquire().await?;
+        while let Some(mut connection) = self.pop_idle() {
+            match self.manager.check(&mut connection).await {
+                Ok(_) => return Ok(PooledConnection::new(self, connection, permit)),
+                Err(err) => warn!("drop broken connection, error: {:#}", err),
+            }
+        }
+        let connection = self.backoff.retry(|| self.manager.connect()).await?;
+        Ok(PooledConnection::new(self, connection, permit))
+    }
+
+    fn pop_idle(&self) -> Option<M::Connection> {
+        match self.idle.lock() {
+            Ok(mut idle) => idle.pop(),
+            Err(_) => None,
+        }
+    }
+
+    fn put_idle(&self, connection: M::Connection) {
+        if let Ok(mut idle) = self.idle.lock() {
+            idle.push(connection)
+        }
+    }
+}
+
+/// Connection returned to pool on drop
+pub struct PooledConnection<'a, M>
+where
+    M: ConnectionManager,
+{
+    pool: &'a Pool<M>,
+    connection: Option<M::Connection>,
+    _permit: SemaphorePermit<'a>,
+}
+
+impl<'a, M> PooledConnection<'a, M>
+where
+    M: ConnectionManager,
+{
+    fn new(pool: &'a Pool<M>, connection: M::Connection, permit: SemaphorePermit<'a>) -> Self {
+        PooledConnection {
+            pool,
+            connection: Some(connection),
+            _permit: permit,
+        }
+    }
+}
+
+impl<'a, M> Deref for PooledConnection<'a, M>
+where
+    M: ConnectionManager,
+{
+    type Target = M::Connection;
+
+    fn deref(&self) -> &Self::Target {
+        self.connection.as_ref().expect("pooled connection taken")
+    }
+}
+
+impl<'a, M> DerefMut for PooledConnection<'a, M>
+where
+    M: ConnectionManager,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.connection.as_mut().expect("pooled connection taken")
+    }
+}
+
+impl<'a, M> Drop for PooledConnection<'a, M>
+where
+    M: ConnectionManager,
+{
+    fn drop(&mut self) {
+        if let Some(connection) = self.connection.take() {
+            self.pool.put_idle(connection)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::prelude::*;
+    use async_trait::async_trait;
+    use std::sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    };
+
+    #[derive(Default)]
+    struct Server {
+        // number of connect attempts
+        attempts: AtomicUsize,
+        // connect fails while down
+        down: AtomicBool,
+    }
+
+    struct TestConnectionManager {
+        server: Arc<Server>,
+    }
+
+    struct TestConnection {
+        id: usize,
+        broken: bool,
+    }
+
+    #[async_trait]
+    impl ConnectionManager for TestConnectionManager {
+        type Connection = TestConnection;
+
+        async fn connect(&self) -> anyhow::Result<TestConnection> {
+            let id = self.server.attempts.fetch_add(1, Ordering::SeqCst);
+            if self.server.down.load(Ordering::SeqCst) {
+                anyhow::bail!("server down")
+            }
+            Ok(TestConnection { id, broken: false })
+        }
+
+        async fn check(&self, connection: &mut TestConnection) -> anyhow::Result<()> {
+            match connection.broken {
+                true => anyhow::bail!("connection {} broken", connection.id),
+                false => Ok(()),
+            }
+        }
+    }
+
+    fn pool_config(size: usize, retries: usize) -> PoolConfig {
+        PoolConfig {
+            size: Some(size),
+            backoff: Some(BackoffConfig {
+                initial: Some(Period::Millis(1)),
+                max: Some(Period::Millis(4)),
+                retries: Some(retries),
+            }),
+        }
+    }
+
+    #[test]
+    fn test_backoff_delays() {
+        let backoff: Backoff = BackoffConfig {
+            initial: Some(Period::Millis(100)),
+            max: Some(Period::Millis(300)),
+            retries: Some(4),
+        }
+        .into();
+        let delays: Vec<u128> = backoff.delays().map(|delay| delay.as_millis()).collect();
+        assert_eq!(vec![100, 200, 300, 300], delays)
+    }
+
+    #[tokio::test]
+    async fn test_pool_reconnect_broken_connection() {
+        let server = Arc::new(Server::default());
+        let manager = TestConnectionManager {
+            server: server.to_owned(),
+        };
+        let pool = Pool::new(manager, pool_config(2, 0)).await.unwrap();
+        {
+            let mut connection = pool.get().await.unwrap();
+            assert_eq!(0, connection.id);
+            connection.broken = true;
+        }
+        // broken connection dropped on checkout
+        let connection = pool.get().await.unwrap();
+        assert_eq!(1, connection.id);
+        // second connection opened while first in use
+        let other = pool.get().await.unwrap();
+        assert_eq!(2, other.id);
+    }
+
+    #[tokio::test]
+    async fn test_pool_connect_backoff() {
+        let server = Arc::new(Server::default());
+        server.down.store(true, Ordering::SeqCst);
+        let manager = TestConnectionManager {
+            server: server.to_owned(),
+        };
+        assert!(Pool::new(manager, pool_config(1, 2)).await.is_err());
+        // first attempt and 2 retries
+        assert_eq!(3, server.attempts.load(Ordering::SeqCst));
+    }
+}
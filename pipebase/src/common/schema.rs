@@ -0,0 +1,190 @@
+use super::{Period, Timestamp};
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveDateTime, Utc};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// Table created if not exists and missing columns added at startup,
+/// columns derived from record fields
+#[derive(Clone, Deserialize)]
+pub struct CreateTableConfig {
+    pub table: String,
+    /// Columns of primary key
+    pub keys: Option<Vec<String>>,
+}
+
+impl CreateTableConfig {
+    pub fn get_keys(&self) -> Vec<String> {
+        self.keys.to_owned().unwrap_or_default()
+    }
+}
+
+/// Column type named after `Value` variant
+#[derive(Clone, Debug, PartialEq)]
+pub enum ColumnType {
+    Bool,
+    UnsignedInteger,
+    Integer,
+    UnsignedLong,
+    Long,
+    Float,
+    Double,
+    String,
+    Date,
+    DateTime,
+    Duration,
+    LocalTime,
+    UtcTime,
+    UnsignedBytes,
+    /// Array of element type
+    Array(Box<ColumnType>),
+    /// Attributes of value type
+    Attributes(Box<ColumnType>),
+}
+
+impl ColumnType {
+    pub fn is_collection(&self) -> bool {
+        matches!(self, ColumnType::Array(_) | ColumnType::Attributes(_))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Column {
+    pub name: String,
+    pub ty: ColumnType,
+}
+
+/// Columns of record, e.g derived `Schema` with field alias as column name
+pub trait Schema {
+    fn schema() -> Vec<Column>;
+}
+
+/// Column type of record field, same `Value` variant the field converted into
+pub trait ColumnTyped {
+    fn column_type() -> ColumnType;
+}
+
+macro_rules! impl_column_typed {
+    ($ty:ty, $column_type:expr) => {
+        impl ColumnTyped for $ty {
+            fn column_type() -> ColumnType {
+                $column_type
+            }
+        }
+    };
+}
+
+impl_column_typed!(bool, ColumnType::Bool);
+impl_column_typed!(u32, ColumnType::UnsignedInteger);
+impl_column_typed!(i32, ColumnType::Integer);
+impl_column_typed!(u64, ColumnType::UnsignedLong);
+impl_column_typed!(i64, ColumnType::Long);
+impl_column_typed!(f32, ColumnType::Float);
+impl_column_typed!(f64, ColumnType::Double);
+impl_column_typed!(String, ColumnType::String);
+impl_column_typed!(NaiveDate, ColumnType::Date);
+impl_column_typed!(NaiveDateTime, ColumnType::DateTime);
+impl_column_typed!(Duration, ColumnType::Duration);
+impl_column_typed!(Period, ColumnType::Duration);
+impl_column_typed!(Timestamp, ColumnType::Duration);
+impl_column_typed!(DateTime<Local>, ColumnType::LocalTime);
+impl_column_typed!(DateTime<Utc>, ColumnType::UtcTime);
+impl_column_typed!(Vec<u8>, ColumnType::UnsignedBytes);
+
+impl<T> ColumnTyped for Option<T>
+where
+    T: ColumnTyped,
+{
+    fn column_type() -> ColumnType {
+        T::column_type()
+    }
+}
+
+impl<T> ColumnTyped for Vec<T>
+where
+    T: ColumnTyped,
+{
+    fn column_type() -> ColumnType {
+        ColumnType::Array(Box::new(T::column_type()))
+    }
+}
+
+impl<T> ColumnTyped for HashMap<String, T>
+where
+    T: ColumnTyped,
+{
+    fn column_type() -> ColumnType {
+        ColumnType::Attributes(Box::new(T::column_type()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::prelude::*;
+    use chrono::NaiveDateTime;
+    use std::collections::HashMap;
+
+    #[derive(IntoAttributes, Schema)]
+    struct Record {
+        id: u64,
+        #[attribute(alias = "label")]
+        name: Option<String>,
+        scores: Vec<i32>,
+        updated: Option<NaiveDateTime>,
+        payload: Vec<u8>,
+        tags: HashMap<String, String>,
+    }
+
+    #[test]
+    fn test_schema() {
+        let expected = vec![
+            Column {
+                name: "id".to_owned(),
+                ty: ColumnType::UnsignedLong,
+            },
+            Column {
+                name: "label".to_owned(),
+                ty: ColumnType::String,
+            },
+            Column {
+                name: "scores".to_owned(),
+                ty: ColumnType::Array(Box::new(ColumnType::Integer)),
+            },
+            Column {
+                name: "updated".to_owned(),
+                ty: ColumnType::DateTime,
+            },
+            Column {
+                name: "payload".to_owned(),
+                ty: ColumnType::UnsignedBytes,
+            },
+            Column {
+                name: "tags".to_owned(),
+                ty: ColumnType::Attributes(Box::new(ColumnType::String)),
+            },
+        ];
+        assert_eq!(expected, Record::schema())
+    }
+
+    #[test]
+    fn test_schema_column_names() {
+        let record = Record {
+            id: 1,
+            name: None,
+            scores: vec![],
+            updated: None,
+            payload: vec![],
+            tags: HashMap::new(),
+        };
+        let names: Vec<String> = record
+            .into_attribute_tuples()
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        let columns: Vec<String> = Record::schema()
+            .into_iter()
+            .map(|column| column.name)
+            .collect();
+        assert_eq!(names, columns)
+    }
+}
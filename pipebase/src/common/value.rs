@@ -1,10 +1,22 @@
-use chrono::{DateTime, Duration, Local, NaiveDate, NaiveDateTime, Utc};
-use std::collections::HashMap;
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use serde::de::{self, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::ser::Serializer;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::convert::TryFrom;
+use std::fmt::{self, Display};
+use std::str::FromStr;
 
 use super::{Period, Timestamp};
 
+const DATE_FORMAT: &str = "%Y-%m-%d";
+const DATETIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.f";
+
+/// Dynamic record or field value, serialized as plain json / yaml value where
+/// date as `%Y-%m-%d`, datetime as `%Y-%m-%dT%H:%M:%S%.f`, time as rfc3339 and duration as millis
 #[derive(Debug, Clone, PartialEq)]
 pub enum Value {
+    Null,
     Bool(Option<bool>),
     UnsignedInteger(Option<u32>),
     Integer(Option<i32>),
@@ -113,6 +125,12 @@ impl From<String> for Value {
     }
 }
 
+impl From<&str> for Value {
+    fn from(v: &str) -> Self {
+        Value::String(Some(v.to_owned()))
+    }
+}
+
 impl From<Option<String>> for Value {
     fn from(v: Option<String>) -> Self {
         Value::String(v)
@@ -245,16 +263,526 @@ where
     }
 }
 
+impl Serialize for Value {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Value::Null => serializer.serialize_none(),
+            Value::Bool(v) => v.serialize(serializer),
+            Value::UnsignedInteger(v) => v.serialize(serializer),
+            Value::Integer(v) => v.serialize(serializer),
+            Value::UnsignedLong(v) => v.serialize(serializer),
+            Value::Long(v) => v.serialize(serializer),
+            Value::Float(v) => v.serialize(serializer),
+            Value::Double(v) => v.serialize(serializer),
+            Value::String(v) => v.serialize(serializer),
+            Value::Date(v) => v
+                .map(|v| v.format(DATE_FORMAT).to_string())
+                .serialize(serializer),
+            Value::DateTime(v) => v
+                .map(|v| v.format(DATETIME_FORMAT).to_string())
+                .serialize(serializer),
+            Value::Duration(v) => v.map(|v| v.num_milliseconds()).serialize(serializer),
+            Value::LocalTime(v) => v.map(|v| v.to_rfc3339()).serialize(serializer),
+            Value::UtcTime(v) => v.map(|v| v.to_rfc3339()).serialize(serializer),
+            Value::UnsignedBytes(v) => serializer.serialize_bytes(v),
+            Value::Array(v) => v.serialize(serializer),
+            Value::Attributes(v) => {
+                // sorted keys for stable output
+                let v: BTreeMap<&String, &Value> = v.iter().collect();
+                v.serialize(serializer)
+            }
+        }
+    }
+}
+
+struct ValueVisitor;
+
+impl<'de> Visitor<'de> for ValueVisitor {
+    type Value = Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("any value")
+    }
+
+    fn visit_bool<E>(self, v: bool) -> Result<Value, E> {
+        Ok(Value::from(v))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Value, E> {
+        Ok(Value::from(v))
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Value, E> {
+        match i64::try_from(v) {
+            Ok(v) => Ok(Value::from(v)),
+            Err(_) => Ok(Value::from(v)),
+        }
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<Value, E> {
+        Ok(Value::from(v))
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Value, E> {
+        Ok(Value::from(v))
+    }
+
+    fn visit_string<E>(self, v: String) -> Result<Value, E> {
+        Ok(Value::from(v))
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Value, E> {
+        Ok(Value::UnsignedBytes(v.to_vec()))
+    }
+
+    fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Value, E> {
+        Ok(Value::UnsignedBytes(v))
+    }
+
+    fn visit_none<E>(self) -> Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_unit<E>(self) -> Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Value::deserialize(deserializer)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut values: Vec<Value> = vec![];
+        while let Some(value) = seq.next_element()? {
+            values.push(value);
+        }
+        Ok(Value::Array(values))
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut attributes: HashMap<String, Value> = HashMap::new();
+        while let Some((key, value)) = map.next_entry::<String, Value>()? {
+            attributes.insert(key, value);
+        }
+        Ok(Value::Attributes(attributes))
+    }
+}
+
+/// Integer as `Long`, unsigned integer exceeds `i64` as `UnsignedLong`, float as `Double`
+/// string as `String`, sequence as `Array` and map as `Attributes`
+impl<'de> Deserialize<'de> for Value {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(ValueVisitor)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+/// Path to nested value, e.g `user.name`, `items[0].price`
+#[derive(Clone, Debug, PartialEq)]
+pub struct ValuePath {
+    segments: Vec<PathSegment>,
+}
+
+impl FromStr for ValuePath {
+    type Err = anyhow::Error;
+
+    fn from_str(path: &str) -> anyhow::Result<Self> {
+        let mut segments: Vec<PathSegment> = vec![];
+        for part in path.split('.') {
+            let (key, mut indices) = match part.find('[') {
+                Some(i) => (&part[..i], &part[i..]),
+                None => (part, ""),
+            };
+            match key.is_empty() {
+                true => anyhow::ensure!(
+                    segments.is_empty() && !indices.is_empty(),
+                    "empty key in path '{}'",
+                    path
+                ),
+                false => segments.push(PathSegment::Key(key.to_owned())),
+            }
+            while !indices.is_empty() {
+                let end = match (indices.starts_with('['), indices.find(']')) {
+                    (true, Some(end)) => end,
+                    _ => anyhow::bail!("invalid index in path '{}'", path),
+                };
+                segments.push(PathSegment::Index(indices[1..end].parse()?));
+                indices = &indices[end + 1..];
+            }
+        }
+        Ok(ValuePath { segments })
+    }
+}
+
+impl<'de> Deserialize<'de> for ValuePath {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let path = String::deserialize(deserializer)?;
+        path.parse().map_err(de::Error::custom)
+    }
+}
+
+impl Display for ValuePath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, segment) in self.segments.iter().enumerate() {
+            match segment {
+                PathSegment::Key(key) if i == 0 => write!(f, "{}", key)?,
+                PathSegment::Key(key) => write!(f, ".{}", key)?,
+                PathSegment::Index(index) => write!(f, "[{}]", index)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ValuePath {
+    pub fn get_segments(&self) -> &[PathSegment] {
+        &self.segments
+    }
+
+    /// Last key in path
+    pub fn get_name(&self) -> Option<&str> {
+        self.segments
+            .iter()
+            .rev()
+            .find_map(|segment| match segment {
+                PathSegment::Key(key) => Some(key.as_str()),
+                PathSegment::Index(_) => None,
+            })
+    }
+}
+
+/// Target type of value cast
+#[derive(Clone, Debug, Deserialize)]
+pub enum ValueType {
+    Bool,
+    UnsignedInteger,
+    Integer,
+    UnsignedLong,
+    Long,
+    Float,
+    Double,
+    String,
+    Date,
+    DateTime,
+    Duration,
+    LocalTime,
+    UtcTime,
+}
+
+impl Value {
+    /// Null or any typed value absent
+    pub fn is_null(&self) -> bool {
+        matches!(
+            self,
+            Value::Null
+                | Value::Bool(None)
+                | Value::UnsignedInteger(None)
+                | Value::Integer(None)
+                | Value::UnsignedLong(None)
+                | Value::Long(None)
+                | Value::Float(None)
+                | Value::Double(None)
+                | Value::String(None)
+                | Value::Date(None)
+                | Value::DateTime(None)
+                | Value::Duration(None)
+                | Value::LocalTime(None)
+                | Value::UtcTime(None)
+        )
+    }
+
+    pub fn as_attributes(&self) -> Option<&HashMap<String, Value>> {
+        match self {
+            Value::Attributes(attributes) => Some(attributes),
+            _ => None,
+        }
+    }
+
+    pub fn as_attributes_mut(&mut self) -> Option<&mut HashMap<String, Value>> {
+        match self {
+            Value::Attributes(attributes) => Some(attributes),
+            _ => None,
+        }
+    }
+
+    pub fn get_path(&self, path: &ValuePath) -> Option<&Value> {
+        let mut value = self;
+        for segment in path.get_segments() {
+            value = match (segment, value) {
+                (PathSegment::Key(key), Value::Attributes(attributes)) => attributes.get(key)?,
+                (PathSegment::Index(index), Value::Array(values)) => values.get(*index)?,
+                _ => return None,
+            };
+        }
+        Some(value)
+    }
+
+    pub fn get_path_mut(&mut self, path: &ValuePath) -> Option<&mut Value> {
+        let mut value = self;
+        for segment in path.get_segments() {
+            value = match (segment, value) {
+                (PathSegment::Key(key), Value::Attributes(attributes)) => {
+                    attributes.get_mut(key)?
+                }
+                (PathSegment::Index(index), Value::Array(values)) => values.get_mut(*index)?,
+                _ => return None,
+            };
+        }
+        Some(value)
+    }
+
+    /// Set value at path, missing attributes along path are created
+    pub fn set_path(&mut self, path: &ValuePath, new_value: Value) -> anyhow::Result<()> {
+        let mut value = self;
+        for segment in path.get_segments() {
+            if value.is_null() {
+                *value = match segment {
+                    PathSegment::Key(_) => Value::Attributes(HashMap::new()),
+                    PathSegment::Index(_) => Value::Array(vec![]),
+                }
+            }
+            value = match (segment, value) {
+                (PathSegment::Key(key), Value::Attributes(attributes)) => {
+                    attributes.entry(key.to_owned()).or_insert(Value::Null)
+                }
+                (PathSegment::Index(index), Value::Array(values)) => {
+                    anyhow::ensure!(
+                        *index <= values.len(),
+                        "index out of bound at '{}', length {}",
+                        path,
+                        values.len()
+                    );
+                    if *index == values.len() {
+                        values.push(Value::Null);
+                    }
+                    &mut values[*index]
+                }
+                _ => anyhow::bail!("can not set '{}', parent is not attributes or array", path),
+            };
+        }
+        *value = new_value;
+        Ok(())
+    }
+
+    /// Remove and return value at path
+    pub fn remove_path(&mut self, path: &ValuePath) -> Option<Value> {
+        let (last, parent) = path.get_segments().split_last()?;
+        let mut value = self;
+        for segment in parent {
+            value = match (segment, value) {
+                (PathSegment::Key(key), Value::Attributes(attributes)) => {
+                    attributes.get_mut(key)?
+                }
+                (PathSegment::Index(index), Value::Array(values)) => values.get_mut(*index)?,
+                _ => return None,
+            };
+        }
+        match (last, value) {
+            (PathSegment::Key(key), Value::Attributes(attributes)) => attributes.remove(key),
+            (PathSegment::Index(index), Value::Array(values)) if *index < values.len() => {
+                Some(values.remove(*index))
+            }
+            _ => None,
+        }
+    }
+
+    /// Flatten nested attributes into top level keys joined by separator
+    pub fn flatten(self, separator: &str) -> Value {
+        let attributes = match self {
+            Value::Attributes(attributes) => attributes,
+            value => return value,
+        };
+        let mut flattened: HashMap<String, Value> = HashMap::new();
+        flatten_attributes(attributes, None, separator, &mut flattened);
+        Value::Attributes(flattened)
+    }
+
+    fn as_i128(&self) -> Option<i128> {
+        match self {
+            Value::UnsignedInteger(Some(v)) => Some(*v as i128),
+            Value::Integer(Some(v)) => Some(*v as i128),
+            Value::UnsignedLong(Some(v)) => Some(*v as i128),
+            Value::Long(Some(v)) => Some(*v as i128),
+            Value::Float(Some(v)) if v.fract() == 0.0 => Some(*v as i128),
+            Value::Double(Some(v)) if v.fract() == 0.0 => Some(*v as i128),
+            Value::Bool(Some(v)) => Some(*v as i128),
+            Value::Duration(Some(v)) => Some(v.num_milliseconds() as i128),
+            Value::String(Some(v)) => v.trim().parse().ok(),
+            _ => None,
+        }
+    }
+
+    fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Float(Some(v)) => Some(*v as f64),
+            Value::Double(Some(v)) => Some(*v),
+            Value::String(Some(v)) => v.trim().parse().ok(),
+            _ => self.as_i128().map(|v| v as f64),
+        }
+    }
+
+    fn as_utc_time(&self) -> Option<DateTime<Utc>> {
+        match self {
+            Value::UtcTime(Some(v)) => Some(*v),
+            Value::LocalTime(Some(v)) => Some(v.with_timezone(&Utc)),
+            Value::DateTime(Some(v)) => Some(Utc.from_utc_datetime(v)),
+            Value::Date(Some(v)) => Some(Utc.from_utc_datetime(&v.and_hms_opt(0, 0, 0)?)),
+            Value::String(Some(v)) => match DateTime::parse_from_rfc3339(v.trim()) {
+                Ok(v) => Some(v.with_timezone(&Utc)),
+                Err(_) => Some(Utc.from_utc_datetime(&parse_datetime(v)?)),
+            },
+            Value::Float(_) | Value::Double(_) => None,
+            _ => Utc
+                .timestamp_millis_opt(i64::try_from(self.as_i128()?).ok()?)
+                .single(),
+        }
+    }
+
+    fn as_text(&self) -> Option<String> {
+        let text = match self {
+            Value::String(Some(v)) => v.to_owned(),
+            Value::Bool(Some(v)) => v.to_string(),
+            Value::Float(Some(v)) => v.to_string(),
+            Value::Double(Some(v)) => v.to_string(),
+            Value::Date(Some(v)) => v.format(DATE_FORMAT).to_string(),
+            Value::DateTime(Some(v)) => v.format(DATETIME_FORMAT).to_string(),
+            Value::LocalTime(Some(v)) => v.to_rfc3339(),
+            Value::UtcTime(Some(v)) => v.to_rfc3339(),
+            Value::UnsignedBytes(v) => String::from_utf8(v.to_owned()).ok()?,
+            Value::Array(_) | Value::Attributes(_) => serde_json::to_string(self).ok()?,
+            _ => self.as_i128()?.to_string(),
+        };
+        Some(text)
+    }
+
+    /// Cast value into type, null cast into typed null
+    pub fn cast(&self, ty: &ValueType) -> anyhow::Result<Value> {
+        let is_null = self.is_null();
+        let value = match ty {
+            ValueType::Bool => Value::Bool(cast(is_null, || match self {
+                Value::String(Some(v)) => v.trim().to_lowercase().parse::<bool>().ok(),
+                _ => self.as_i128().map(|v| v != 0),
+            })),
+            ValueType::UnsignedInteger => Value::UnsignedInteger(cast_integer(self)),
+            ValueType::Integer => Value::Integer(cast_integer(self)),
+            ValueType::UnsignedLong => Value::UnsignedLong(cast_integer(self)),
+            ValueType::Long => Value::Long(cast_integer(self)),
+            ValueType::Float => Value::Float(cast(is_null, || self.as_f64().map(|v| v as f32))),
+            ValueType::Double => Value::Double(cast(is_null, || self.as_f64())),
+            ValueType::String => Value::String(cast(is_null, || self.as_text())),
+            ValueType::Date => Value::Date(cast(is_null, || match self {
+                Value::String(Some(v)) => NaiveDate::parse_from_str(v.trim(), DATE_FORMAT).ok(),
+                _ => Some(self.as_utc_time()?.date_naive()),
+            })),
+            ValueType::DateTime => Value::DateTime(cast(is_null, || match self {
+                Value::String(Some(v)) => parse_datetime(v),
+                _ => Some(self.as_utc_time()?.naive_utc()),
+            })),
+            ValueType::Duration => Value::Duration(cast(is_null, || {
+                Some(Duration::milliseconds(i64::try_from(self.as_i128()?).ok()?))
+            })),
+            ValueType::LocalTime => Value::LocalTime(cast(is_null, || {
+                Some(self.as_utc_time()?.with_timezone(&Local))
+            })),
+            ValueType::UtcTime => Value::UtcTime(cast(is_null, || self.as_utc_time())),
+        };
+        match !is_null && value.is_null() {
+            true => anyhow::bail!("can not cast {:?} into {:?}", self, ty),
+            false => Ok(value),
+        }
+    }
+}
+
+fn cast<T, F>(is_null: bool, f: F) -> Option<T>
+where
+    F: FnOnce() -> Option<T>,
+{
+    match is_null {
+        true => None,
+        false => f(),
+    }
+}
+
+fn cast_integer<T>(value: &Value) -> Option<T>
+where
+    T: TryFrom<i128>,
+{
+    T::try_from(value.as_i128()?).ok()
+}
+
+fn parse_datetime(v: &str) -> Option<NaiveDateTime> {
+    let v = v.trim();
+    NaiveDateTime::parse_from_str(v, DATETIME_FORMAT)
+        .or_else(|_| NaiveDateTime::parse_from_str(v, "%Y-%m-%d %H:%M:%S%.f"))
+        .ok()
+}
+
+fn flatten_attributes(
+    attributes: HashMap<String, Value>,
+    prefix: Option<&str>,
+    separator: &str,
+    flattened: &mut HashMap<String, Value>,
+) {
+    for (key, value) in attributes {
+        let key = match prefix {
+            Some(prefix) => format!("{}{}{}", prefix, separator, key),
+            None => key,
+        };
+        match value {
+            Value::Attributes(attributes) => {
+                flatten_attributes(attributes, Some(&key), separator, flattened)
+            }
+            value => {
+                flattened.insert(key, value);
+            }
+        }
+    }
+}
+
 pub trait IntoAttributes {
     fn into_attributes(self) -> HashMap<String, Value>;
 
     fn into_attribute_tuples(self) -> Vec<(String, Value)>;
 }
 
+impl IntoAttributes for Vec<(String, Value)> {
+    fn into_attributes(self) -> HashMap<String, Value> {
+        self.into_iter().collect()
+    }
+
+    fn into_attribute_tuples(self) -> Vec<(String, Value)> {
+        self
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
     use crate::prelude::*;
+    use chrono::NaiveDate;
     use std::collections::HashMap;
 
     #[derive(IntoAttributes)]
@@ -297,4 +825,84 @@ mod tests {
         );
         assert_eq!(&Value::from(1 as u32), attributes.get("value").unwrap());
     }
+
+    fn path(path: &str) -> ValuePath {
+        path.parse().unwrap()
+    }
+
+    #[test]
+    fn test_value_serde() {
+        let json = r#"{"id":1,"name":"foo","score":1.5,"tags":["a",null],"user":{"active":true}}"#;
+        let value: Value = serde_json::from_str(json).unwrap();
+        assert_eq!(Some(&Value::from(1i64)), value.get_path(&path("id")));
+        assert_eq!(Some(&Value::from(1.5)), value.get_path(&path("score")));
+        assert_eq!(Some(&Value::Null), value.get_path(&path("tags[1]")));
+        assert_eq!(
+            Some(&Value::from(true)),
+            value.get_path(&path("user.active"))
+        );
+        assert_eq!(json, serde_json::to_string(&value).unwrap());
+        let yaml_value: Value =
+            serde_yaml::from_str(&serde_yaml::to_string(&value).unwrap()).unwrap();
+        assert_eq!(value, yaml_value);
+    }
+
+    #[test]
+    fn test_value_path() {
+        assert_eq!("items[0].price", path("items[0].price").to_string());
+        assert!("items..price".parse::<ValuePath>().is_err());
+        assert!("items[a]".parse::<ValuePath>().is_err());
+        let mut value = Value::Null;
+        value.set_path(&path("user.name"), "foo".into()).unwrap();
+        value.set_path(&path("user.tags[0]"), "a".into()).unwrap();
+        assert!(value.set_path(&path("user.tags[2]"), "c".into()).is_err());
+        assert_eq!(
+            Some(&Value::from("a")),
+            value.get_path(&path("user.tags[0]"))
+        );
+        assert_eq!(
+            Some(Value::from("foo")),
+            value.remove_path(&path("user.name"))
+        );
+        assert_eq!(None, value.get_path(&path("user.name")));
+        let flattened = value.flatten("_");
+        assert_eq!(
+            Some(&Value::Array(vec!["a".into()])),
+            flattened.get_path(&path("user_tags"))
+        );
+    }
+
+    #[test]
+    fn test_value_cast() {
+        assert_eq!(
+            Value::from(12i32),
+            Value::from("12").cast(&ValueType::Integer).unwrap()
+        );
+        assert_eq!(
+            Value::from("1.5"),
+            Value::from(1.5).cast(&ValueType::String).unwrap()
+        );
+        assert_eq!(
+            Value::from(true),
+            Value::from("True").cast(&ValueType::Bool).unwrap()
+        );
+        assert_eq!(
+            Value::Long(None),
+            Value::Null.cast(&ValueType::Long).unwrap()
+        );
+        assert!(Value::from(-1i64)
+            .cast(&ValueType::UnsignedInteger)
+            .is_err());
+        assert!(Value::from("foo").cast(&ValueType::Double).is_err());
+        let date = Value::from("2021-08-01").cast(&ValueType::Date).unwrap();
+        assert_eq!(
+            Value::from(NaiveDate::from_ymd_opt(2021, 8, 1).unwrap()),
+            date
+        );
+        let time = Value::from(0i64).cast(&ValueType::UtcTime).unwrap();
+        assert_eq!(
+            Value::from("1970-01-01T00:00:00+00:00"),
+            time.cast(&ValueType::String).unwrap()
+        );
+    }
 }
@@ -0,0 +1,275 @@
+use super::StoreContext;
+use crate::common::{ConfigInto, Context, FromConfig, FromPath, Period, PipeContext, State};
+use async_trait::async_trait;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::Interval;
+use tracing::info;
+
+const DEFAULT_HISTORY_RETENTION_HOURS: i64 = 24;
+
+/// Pipe context sampled at timestamp
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PipeContextSample {
+    /// Sample time in milliseconds since epoch
+    pub timestamp: i64,
+    pub context: PipeContext,
+}
+
+impl PipeContextSample {
+    pub fn new(timestamp: i64, context: PipeContext) -> Self {
+        PipeContextSample { timestamp, context }
+    }
+
+    pub fn get_timestamp(&self) -> i64 {
+        self.timestamp
+    }
+
+    pub fn get_context(&self) -> &PipeContext {
+        &self.context
+    }
+}
+
+/// Sample all contexts at current time
+pub fn sample_contexts(contexts: &HashMap<String, Arc<Context>>) -> Vec<PipeContextSample> {
+    let timestamp = Utc::now().timestamp_millis();
+    contexts
+        .iter()
+        .map(|(name, context)| {
+            PipeContextSample::new(
+                timestamp,
+                PipeContext::new(
+                    name.to_owned(),
+                    context.get_state(),
+                    context.get_total_run(),
+                    context.get_failure_run(),
+                ),
+            )
+        })
+        .collect()
+}
+
+/// Rolling jsonl store of context samples, current file rotated to `<path>.1`
+/// once its first sample is older than retention
+#[derive(Clone)]
+pub struct ContextHistory {
+    path: PathBuf,
+    rotated_path: PathBuf,
+    retention: i64,
+}
+
+impl ContextHistory {
+    pub fn new<P: AsRef<Path>>(path: P, retention: Duration) -> Self {
+        let path = path.as_ref().to_path_buf();
+        let mut rotated_path = path.clone().into_os_string();
+        rotated_path.push(".1");
+        ContextHistory {
+            path,
+            rotated_path: PathBuf::from(rotated_path),
+            retention: retention.as_millis() as i64,
+        }
+    }
+
+    /// Append samples, rotate current file if expired
+    pub fn append(&self, samples: &[PipeContextSample]) -> anyhow::Result<()> {
+        let timestamp = match samples.first() {
+            Some(sample) => sample.get_timestamp(),
+            None => return Ok(()),
+        };
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        if let Some(first) = Self::first_timestamp(&self.path)? {
+            if timestamp - first > self.retention {
+                fs::rename(&self.path, &self.rotated_path)?;
+            }
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        let mut writer = BufWriter::new(file);
+        for sample in samples {
+            serde_json::to_writer(&mut writer, sample)?;
+            writer.write_all(b"\n")?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Query samples of pipe with timestamp in [from, to]
+    pub fn query(
+        &self,
+        pipe_name: &str,
+        from: Option<i64>,
+        to: Option<i64>,
+    ) -> anyhow::Result<Vec<PipeContextSample>> {
+        let from = from.unwrap_or(i64::MIN);
+        let to = to.unwrap_or(i64::MAX);
+        let mut samples: Vec<PipeContextSample> = vec![];
+        for path in [&self.rotated_path, &self.path] {
+            for sample in Self::read_samples(path)? {
+                let timestamp = sample.get_timestamp();
+                if sample.get_context().get_name() == pipe_name
+                    && timestamp >= from
+                    && timestamp <= to
+                {
+                    samples.push(sample)
+                }
+            }
+        }
+        Ok(samples)
+    }
+
+    fn read_samples(path: &Path) -> anyhow::Result<Vec<PipeContextSample>> {
+        if !path.exists() {
+            return Ok(vec![]);
+        }
+        let reader = BufReader::new(File::open(path)?);
+        let mut samples: Vec<PipeContextSample> = vec![];
+        for line in reader.lines() {
+            // skip partial line being written
+            if let Ok(sample) = serde_json::from_str::<PipeContextSample>(&line?) {
+                samples.push(sample)
+            }
+        }
+        Ok(samples)
+    }
+
+    fn first_timestamp(path: &Path) -> anyhow::Result<Option<i64>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let mut reader = BufReader::new(File::open(path)?);
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        match serde_json::from_str::<PipeContextSample>(&line) {
+            Ok(sample) => Ok(Some(sample.get_timestamp())),
+            Err(_) => Ok(None),
+        }
+    }
+}
+
+#[derive(Clone, Deserialize)]
+pub struct ContextHistoryWriterConfig {
+    /// History file path
+    pub path: String,
+    /// Sample interval
+    pub interval: Period,
+    /// Rotate history older than retention, 24 hours by default
+    pub retention: Option<Period>,
+}
+
+impl FromPath for ContextHistoryWriterConfig {}
+
+#[async_trait]
+impl ConfigInto<ContextHistoryWriter> for ContextHistoryWriterConfig {}
+
+/// Sample contexts in period into rolling local history
+pub struct ContextHistoryWriter {
+    interval: Interval,
+    history: ContextHistory,
+    contexts: HashMap<String, Arc<Context>>,
+}
+
+#[async_trait]
+impl FromConfig<ContextHistoryWriterConfig> for ContextHistoryWriter {
+    async fn from_config(config: ContextHistoryWriterConfig) -> anyhow::Result<Self> {
+        let retention = config
+            .retention
+            .unwrap_or(Period::Hours(DEFAULT_HISTORY_RETENTION_HOURS));
+        Ok(ContextHistoryWriter {
+            interval: tokio::time::interval(config.interval.into()),
+            history: ContextHistory::new(config.path, retention.into()),
+            contexts: HashMap::new(),
+        })
+    }
+}
+
+impl ContextHistoryWriter {
+    pub fn get_history(&self) -> ContextHistory {
+        self.history.to_owned()
+    }
+}
+
+#[async_trait]
+impl StoreContext<ContextHistoryWriterConfig> for ContextHistoryWriter {
+    fn store_context(&mut self, pipe_name: String, context: Arc<Context>) {
+        self.contexts.insert(pipe_name, context);
+    }
+
+    fn load_context(&self, pipe_name: &str) -> Option<&Arc<Context>> {
+        self.contexts.get(pipe_name)
+    }
+
+    async fn run(&mut self) -> anyhow::Result<()> {
+        loop {
+            self.interval.tick().await;
+            let samples = sample_contexts(&self.contexts);
+            self.history.append(&samples)?;
+            let done = self
+                .contexts
+                .values()
+                .filter(|ctx| ctx.get_state() == State::Done)
+                .count();
+            if done == self.contexts.len() {
+                info!("all pipe in Done state, exit context history writer");
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::prelude::*;
+    use std::time::Duration;
+
+    #[derive(Bootstrap)]
+    #[pipe(
+        name = "timer",
+        ty = "poller",
+        config(ty = "TimerConfig", path = "resources/catalogs/timer.yml"),
+        output = "u128"
+    )]
+    #[pipe(
+        name = "printer",
+        ty = "exporter",
+        upstream = "timer",
+        config(ty = "PrinterConfig")
+    )]
+    #[cstore(
+        name = "context_history_writer",
+        config(
+            ty = "ContextHistoryWriterConfig",
+            path = "resources/catalogs/context_history_writer.yml"
+        )
+    )]
+    struct App {}
+
+    #[tokio::test(start_paused = true)]
+    async fn test_context_history_writer() {
+        let mut app = App {};
+        app.bootstrap().await;
+        let history = ContextHistory::new(
+            "resources/context_history/history.jsonl",
+            Duration::from_secs(3600),
+        );
+        let samples = history.query("timer", None, None).unwrap();
+        assert!(!samples.is_empty());
+        let last = samples.last().unwrap().get_context();
+        assert_eq!("done", last.get_state());
+        assert_eq!(10, last.get_total_run());
+        let samples = history.query("timer", Some(i64::MAX), None).unwrap();
+        assert!(samples.is_empty());
+        std::fs::remove_dir_all("resources/context_history").unwrap();
+    }
+}
@@ -1,6 +1,8 @@
+mod history;
 mod print;
 mod runtime;
 
+pub use history::*;
 pub use print::*;
 pub use runtime::*;
 
@@ -9,5 +9,6 @@ pub mod poll;
 pub mod prelude;
 pub mod select;
 pub mod stream;
+pub mod testing;
 
 pub use pipederive::main;
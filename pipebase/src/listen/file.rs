@@ -1,9 +1,11 @@
 use async_trait::async_trait;
-use serde::Deserialize;
-use std::fs::{self, DirEntry};
-use std::io;
-use std::path::PathBuf;
-use std::time::Duration;
+use glob::Pattern;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, DirEntry, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tokio::sync::mpsc::Sender;
 use tokio::time::sleep;
 
@@ -29,6 +31,122 @@ pub enum FilePathVisitMode {
 pub struct LocalFilePathVisitorConfig {
     pub root: String,
     pub mode: Option<FilePathVisitMode>,
+    /// Globs of path relative to root to include, include all by default
+    pub include: Option<Vec<String>>,
+    /// Globs of path relative to root to exclude
+    pub exclude: Option<Vec<String>>,
+    /// Skip file modified within the period, e.g. still being written
+    pub min_age: Option<Period>,
+    /// Skip file smaller than bytes
+    pub min_size: Option<u64>,
+    /// Skip file larger than bytes
+    pub max_size: Option<u64>,
+    /// Ledger file path, file recorded with same mtime and size is not sent again,
+    /// file is recorded by downstream `FileArchiver` with same ledger once completed
+    pub ledger: Option<String>,
+}
+
+/// Fingerprint of visited file
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct FileLedgerEntry {
+    pub path: String,
+    /// Modified time in milliseconds since epoch
+    pub modified: u128,
+    pub size: u64,
+}
+
+impl FileLedgerEntry {
+    pub fn new(path: &Path, metadata: &fs::Metadata) -> io::Result<Self> {
+        let modified = metadata
+            .modified()?
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        Ok(FileLedgerEntry {
+            path: path.to_string_lossy().into_owned(),
+            modified,
+            size: metadata.len(),
+        })
+    }
+}
+
+/// Append-only jsonl of completed files, latest entry of path wins
+pub struct FileLedger {
+    path: PathBuf,
+    entries: HashMap<String, FileLedgerEntry>,
+}
+
+impl FileLedger {
+    pub fn load<P: AsRef<Path>>(path: P) -> anyhow::Result<Self> {
+        let mut ledger = FileLedger {
+            path: path.as_ref().to_path_buf(),
+            entries: HashMap::new(),
+        };
+        ledger.reload()?;
+        Ok(ledger)
+    }
+
+    /// Read entries appended by other writers, e.g `FileArchiver`
+    pub fn reload(&mut self) -> anyhow::Result<()> {
+        self.entries.clear();
+        if !self.path.exists() {
+            return Ok(());
+        }
+        for line in BufReader::new(File::open(&self.path)?).lines() {
+            // skip partial line written before crash
+            if let Ok(entry) = serde_json::from_str::<FileLedgerEntry>(&line?) {
+                self.entries.insert(entry.path.to_owned(), entry);
+            }
+        }
+        Ok(())
+    }
+
+    /// Drop entries of file no longer exists and rewrite latest entry per path
+    pub fn compact(&mut self) -> anyhow::Result<()> {
+        if !self.path.exists() {
+            return Ok(());
+        }
+        self.entries.retain(|path, _| Path::new(path).exists());
+        let mut compacted = self.path.to_owned().into_os_string();
+        compacted.push(".compact");
+        let compacted = PathBuf::from(compacted);
+        let mut file = File::create(&compacted)?;
+        for entry in self.entries.values() {
+            let mut line = serde_json::to_vec(entry)?;
+            line.push(b'\n');
+            file.write_all(&line)?;
+        }
+        file.sync_all()?;
+        fs::rename(&compacted, &self.path)?;
+        Ok(())
+    }
+
+    pub fn contains(&self, entry: &FileLedgerEntry) -> bool {
+        self.entries.get(&entry.path) == Some(entry)
+    }
+
+    pub fn append(&mut self, entry: FileLedgerEntry) -> anyhow::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        let mut line = serde_json::to_vec(&entry)?;
+        line.push(b'\n');
+        file.write_all(&line)?;
+        self.entries.insert(entry.path.to_owned(), entry);
+        Ok(())
+    }
+}
+
+fn compile_patterns(globs: Option<Vec<String>>) -> anyhow::Result<Vec<Pattern>> {
+    let mut patterns: Vec<Pattern> = vec![];
+    for glob in globs.unwrap_or_default() {
+        patterns.push(Pattern::new(&glob)?)
+    }
+    Ok(patterns)
 }
 
 impl FromPath for LocalFilePathVisitorConfig {}
@@ -42,20 +160,71 @@ pub struct LocalFilePathVisitor {
     root: PathBuf,
     /// Either Once ot Cron
     mode: FilePathVisitMode,
+    include: Vec<Pattern>,
+    exclude: Vec<Pattern>,
+    min_age: Option<Duration>,
+    min_size: Option<u64>,
+    max_size: Option<u64>,
+    /// Completed files
+    ledger: Option<FileLedger>,
+    /// Files sent but not recorded in ledger yet
+    pending: HashMap<String, FileLedgerEntry>,
     /// Sender to notify downstreams
     tx: Option<Sender<PathBuf>>,
 }
 
 impl LocalFilePathVisitor {
-    pub fn new(config: LocalFilePathVisitorConfig) -> Self {
+    pub fn new(config: LocalFilePathVisitorConfig) -> anyhow::Result<Self> {
         let mode = match config.mode {
             Some(mode) => mode,
             None => FilePathVisitMode::Once,
         };
-        LocalFilePathVisitor {
+        let ledger = match config.ledger {
+            Some(path) => {
+                let mut ledger = FileLedger::load(path)?;
+                ledger.compact()?;
+                Some(ledger)
+            }
+            None => None,
+        };
+        Ok(LocalFilePathVisitor {
             root: PathBuf::from(config.root),
             mode,
+            include: compile_patterns(config.include)?,
+            exclude: compile_patterns(config.exclude)?,
+            min_age: config.min_age.map(Duration::from),
+            min_size: config.min_size,
+            max_size: config.max_size,
+            ledger,
+            pending: HashMap::new(),
             tx: None,
+        })
+    }
+
+    fn match_patterns(&self, path: &Path) -> bool {
+        let path = path.strip_prefix(&self.root).unwrap_or(path);
+        let included = self.include.is_empty() || self.include.iter().any(|p| p.matches_path(path));
+        included && !self.exclude.iter().any(|p| p.matches_path(path))
+    }
+
+    fn match_metadata(&self, metadata: &fs::Metadata) -> bool {
+        let size = metadata.len();
+        if matches!(self.min_size, Some(min_size) if size < min_size)
+            || matches!(self.max_size, Some(max_size) if size > max_size)
+        {
+            return false;
+        }
+        let min_age = match self.min_age {
+            Some(min_age) => min_age,
+            None => return true,
+        };
+        let age = metadata
+            .modified()
+            .ok()
+            .and_then(|modified| SystemTime::now().duration_since(modified).ok());
+        match age {
+            Some(age) => age >= min_age,
+            None => false,
         }
     }
 }
@@ -63,7 +232,7 @@ impl LocalFilePathVisitor {
 #[async_trait]
 impl FromConfig<LocalFilePathVisitorConfig> for LocalFilePathVisitor {
     async fn from_config(config: LocalFilePathVisitorConfig) -> anyhow::Result<Self> {
-        Ok(LocalFilePathVisitor::new(config))
+        LocalFilePathVisitor::new(config)
     }
 }
 
@@ -98,13 +267,50 @@ impl ListFile for LocalFilePathVisitor {
             }
         }
     }
+
+    /// Filter by globs, size and age
+    async fn filter(&self, entry: &DirEntry) -> bool {
+        if !self.match_patterns(&entry.path()) {
+            return false;
+        }
+        match entry.metadata() {
+            Ok(metadata) => self.match_metadata(&metadata),
+            Err(_) => false,
+        }
+    }
 }
 
 impl LocalFilePathVisitor {
     async fn run_once(&mut self) -> anyhow::Result<()> {
-        for path in self.list().await? {
-            self.tx.as_ref().unwrap().send(path).await?;
+        let paths = self.list().await?;
+        let ledger = match self.ledger {
+            Some(ref mut ledger) => ledger,
+            None => {
+                for path in paths {
+                    self.tx.as_ref().unwrap().send(path).await?;
+                }
+                return Ok(());
+            }
+        };
+        // pick up files completed downstream since last run
+        ledger.reload()?;
+        let mut pending: HashMap<String, FileLedgerEntry> = HashMap::new();
+        for path in paths {
+            let entry = match fs::metadata(&path) {
+                Ok(metadata) => FileLedgerEntry::new(&path, &metadata)?,
+                // file removed since listed
+                Err(_) => continue,
+            };
+            if ledger.contains(&entry) {
+                continue;
+            }
+            // sent in previous run and not completed yet
+            if self.pending.get(&entry.path) != Some(&entry) {
+                self.tx.as_ref().unwrap().send(path).await?;
+            }
+            pending.insert(entry.path.to_owned(), entry);
         }
+        self.pending = pending;
         Ok(())
     }
 
@@ -136,6 +342,7 @@ impl Listen<PathBuf, LocalFilePathVisitorConfig> for LocalFilePathVisitor {
 #[cfg(test)]
 mod tests {
     use crate::prelude::*;
+    use crate::testing::*;
     use std::collections::HashSet;
     use std::path::PathBuf;
 
@@ -166,4 +373,58 @@ mod tests {
         }
         assert_eq!(all_expected_files.len(), actual_files_total)
     }
+
+    async fn visit_with_ledger() -> Vec<PathBuf> {
+        let pipe = listener!("file_visitor");
+        let config = config!(
+            LocalFilePathVisitorConfig,
+            "resources/catalogs/local_file_visitor_ledger.yml"
+        );
+        let outputs: PipeOutputs<PathBuf> = run_source(pipe, config).await.unwrap();
+        outputs.get_records().to_owned()
+    }
+
+    #[tokio::test]
+    async fn test_list_folder_with_ledger() {
+        let _ = std::fs::remove_dir_all("resources/file_ledger");
+        let expected = vec![PathBuf::from("resources/test_file_folder/test_file_0.txt")];
+        // not completed downstream, sent again
+        assert_eq!(expected, visit_with_ledger().await);
+        assert_eq!(expected, visit_with_ledger().await);
+        let pipe = mapper!("file_recorder");
+        let config = config!(
+            FileArchiverConfig,
+            "resources/catalogs/file_archiver_ledger.yml"
+        );
+        let outputs = run_pipe_with_records(pipe, config, expected.to_owned())
+            .await
+            .unwrap();
+        assert_eq!(&expected, outputs.get_records());
+        assert!(visit_with_ledger().await.is_empty());
+        std::fs::remove_dir_all("resources/file_ledger").unwrap();
+    }
+
+    #[test]
+    fn test_compact_file_ledger() {
+        let directory = "resources/file_ledger_compact";
+        let _ = std::fs::remove_dir_all(directory);
+        std::fs::create_dir_all(directory).unwrap();
+        let path = format!("{}/ledger.jsonl", directory);
+        let exists = PathBuf::from("resources/test_file_folder/test_file_0.txt");
+        let metadata = std::fs::metadata(&exists).unwrap();
+        let mut ledger = FileLedger::load(&path).unwrap();
+        let entry = FileLedgerEntry::new(&exists, &metadata).unwrap();
+        ledger.append(entry.to_owned()).unwrap();
+        ledger.append(entry.to_owned()).unwrap();
+        let missing = PathBuf::from("resources/test_file_folder/missing.txt");
+        ledger
+            .append(FileLedgerEntry::new(&missing, &metadata).unwrap())
+            .unwrap();
+        ledger.compact().unwrap();
+        let lines = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(1, lines.lines().count());
+        let ledger = FileLedger::load(&path).unwrap();
+        assert!(ledger.contains(&entry));
+        std::fs::remove_dir_all(directory).unwrap();
+    }
 }
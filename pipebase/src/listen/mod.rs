@@ -1,7 +1,9 @@
 mod file;
+mod replay;
 mod runtime;
 
 pub use file::*;
+pub use replay::*;
 pub use runtime::*;
 
 use async_trait::async_trait;
@@ -0,0 +1,89 @@
+use super::Listen;
+use crate::common::{CapturedRecord, ConfigInto, FromConfig, FromPath};
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio::fs::File;
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::sync::mpsc::Sender;
+use tokio::time::sleep;
+
+#[derive(Deserialize)]
+pub struct RecordReplayConfig {
+    /// Capture file path written by `RecordCapture`
+    pub path: String,
+    /// Replay pace relative to capture, 1.0 by default, no delay if 0
+    pub speed: Option<f64>,
+}
+
+impl FromPath for RecordReplayConfig {}
+
+impl<U> ConfigInto<RecordReplay<U>> for RecordReplayConfig {}
+
+/// Replay captured records at original or accelerated pace
+pub struct RecordReplay<U> {
+    path: PathBuf,
+    speed: f64,
+    /// Sender to notify downstreams
+    tx: Option<Sender<U>>,
+}
+
+#[async_trait]
+impl<U> FromConfig<RecordReplayConfig> for RecordReplay<U> {
+    async fn from_config(config: RecordReplayConfig) -> anyhow::Result<Self> {
+        let speed = config.speed.unwrap_or(1.0);
+        anyhow::ensure!(speed >= 0.0, "invalid replay speed {}", speed);
+        Ok(RecordReplay {
+            path: PathBuf::from(config.path),
+            speed,
+            tx: None,
+        })
+    }
+}
+
+impl<U> RecordReplay<U> {
+    fn get_delay(&self, previous: Option<i64>, current: i64) -> Option<Duration> {
+        let previous = previous?;
+        if self.speed == 0.0 || current <= previous {
+            return None;
+        }
+        let millis = (current - previous) as f64 / self.speed;
+        Some(Duration::from_secs_f64(millis / 1000.0))
+    }
+}
+
+/// # Parameters
+/// * U: output
+#[async_trait]
+impl<U> Listen<U, RecordReplayConfig> for RecordReplay<U>
+where
+    U: DeserializeOwned + Send + Sync + 'static,
+{
+    async fn run(&mut self) -> anyhow::Result<()> {
+        let file = File::open(&self.path).await?;
+        let mut lines = BufReader::new(file).lines();
+        let mut previous: Option<i64> = None;
+        while let Some(line) = lines.next_line().await? {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let captured: CapturedRecord<U> = serde_json::from_str(&line)?;
+            if let Some(delay) = self.get_delay(previous, captured.timestamp) {
+                sleep(delay).await;
+            }
+            previous = Some(captured.timestamp);
+            self.tx
+                .as_ref()
+                .unwrap()
+                .send(captured.into_record())
+                .await?;
+        }
+        Ok(())
+    }
+
+    fn set_sender(&mut self, sender: Sender<U>) {
+        self.tx = Some(sender)
+    }
+}
@@ -63,6 +63,7 @@ where
 #[cfg(test)]
 mod sum_aggregator_tests {
     use crate::prelude::*;
+    use crate::testing::*;
 
     #[tokio::test]
     async fn test_sum_aggregator() {
@@ -115,6 +116,7 @@ mod sum_aggregator_tests {
 mod count32_tests {
 
     use crate::prelude::*;
+    use crate::testing::*;
 
     #[derive(Debug, Clone, AggregateAs)]
     #[agg(count32)]
@@ -140,6 +142,7 @@ mod count32_tests {
 mod test_avg {
 
     use crate::prelude::*;
+    use crate::testing::*;
 
     #[derive(Clone, Debug, AggregateAs)]
     struct Record {
@@ -243,6 +246,7 @@ where
 #[cfg(test)]
 mod test_group_sum_aggregator {
     use crate::prelude::*;
+    use crate::testing::*;
 
     #[tokio::test]
     async fn test_u32_group_sum_aggregator() {
@@ -315,6 +319,7 @@ mod test_group_sum_aggregator {
 mod unordered_group_avg_f32_tests {
 
     use crate::prelude::*;
+    use crate::testing::*;
 
     #[derive(Clone, Debug, AggregateAs, GroupAs)]
     struct Record {
@@ -374,6 +379,7 @@ mod unordered_group_avg_f32_tests {
 mod group_count32_tests {
 
     use crate::prelude::*;
+    use crate::testing::*;
 
     #[derive(Debug, Clone, GroupAs, AggregateAs)]
     #[agg(count32)]
@@ -518,6 +524,7 @@ where
 #[cfg(test)]
 mod test_ordered_group_aggregator {
     use crate::prelude::*;
+    use crate::testing::*;
 
     #[tokio::test]
     async fn test_word_group_count_aggregate() {
@@ -0,0 +1,99 @@
+use super::Map;
+use crate::common::{CapturedRecord, ConfigInto, FromConfig, FromPath};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+#[derive(Deserialize)]
+pub struct RecordCaptureConfig {
+    /// Capture file path
+    path: String,
+    /// Append to existing capture file, truncate by default
+    append: Option<bool>,
+}
+
+impl FromPath for RecordCaptureConfig {}
+
+impl ConfigInto<RecordCapture> for RecordCaptureConfig {}
+
+/// Tap pipe edge, write timestamped record as json line and pass record through
+pub struct RecordCapture {
+    writer: BufWriter<File>,
+}
+
+#[async_trait]
+impl FromConfig<RecordCaptureConfig> for RecordCapture {
+    async fn from_config(config: RecordCaptureConfig) -> anyhow::Result<Self> {
+        let path = Path::new(&config.path);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let append = config.append.unwrap_or(false);
+        let file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(append)
+            .truncate(!append)
+            .open(path)?;
+        Ok(RecordCapture {
+            writer: BufWriter::new(file),
+        })
+    }
+}
+
+/// # Parameters
+/// * T: input
+/// * T: output
+#[async_trait]
+impl<T> Map<T, T, RecordCaptureConfig> for RecordCapture
+where
+    T: Serialize + Send + Sync + 'static,
+{
+    async fn map(&mut self, data: T) -> anyhow::Result<T> {
+        let captured = CapturedRecord::new(data);
+        serde_json::to_writer(&mut self.writer, &captured)?;
+        self.writer.write_all(b"\n")?;
+        self.writer.flush()?;
+        Ok(captured.into_record())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::prelude::*;
+    use crate::testing::*;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+    struct Record {
+        key: String,
+        value: u32,
+    }
+
+    fn records() -> Vec<Record> {
+        (0..3)
+            .map(|i| Record {
+                key: format!("key{}", i),
+                value: i,
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_capture_and_replay() {
+        let pipe = mapper!("record_capture");
+        let config = config!(RecordCaptureConfig, "resources/catalogs/record_capture.yml");
+        let outputs = run_pipe_with_records(pipe, config, records())
+            .await
+            .expect("capture failed");
+        assert_eq!(&records(), outputs.get_records());
+        let pipe = listener!("record_replay");
+        let config = config!(RecordReplayConfig, "resources/catalogs/record_replay.yml");
+        let outputs: PipeOutputs<Record> = run_source(pipe, config).await.expect("replay failed");
+        assert_eq!(records(), outputs.into_records());
+        std::fs::remove_dir_all("resources/capture").unwrap();
+    }
+}
@@ -0,0 +1,180 @@
+use super::Map;
+use crate::common::{eval_object, to_expr_value, ConfigInto, Expression, FromConfig, FromPath};
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::iter::{FromIterator, IntoIterator};
+
+#[derive(Deserialize)]
+pub struct ExprFilterConfig {
+    /// Boolean expression, item kept if true, e.g. `price * qty > 100 && status == 'paid'`
+    pub predicate: String,
+}
+
+impl FromPath for ExprFilterConfig {}
+
+impl ConfigInto<ExprFilter> for ExprFilterConfig {}
+
+/// Filter items in iterator with runtime expression
+pub struct ExprFilter {
+    predicate: Expression,
+}
+
+#[async_trait]
+impl FromConfig<ExprFilterConfig> for ExprFilter {
+    async fn from_config(config: ExprFilterConfig) -> anyhow::Result<Self> {
+        Ok(ExprFilter {
+            predicate: config.predicate.parse()?,
+        })
+    }
+}
+
+/// # Parameters
+/// * U: input
+/// * V: output
+#[async_trait]
+impl<T, U, V> Map<U, V, ExprFilterConfig> for ExprFilter
+where
+    T: Serialize + Send,
+    U: IntoIterator<Item = T> + Send + 'static,
+    V: FromIterator<T> + Send,
+{
+    async fn map(&mut self, data: U) -> anyhow::Result<V> {
+        let mut filtered: Vec<T> = vec![];
+        for item in data {
+            if self.predicate.eval_bool(&to_expr_value(&item)?)? {
+                filtered.push(item);
+            }
+        }
+        Ok(filtered.into_iter().collect::<V>())
+    }
+}
+
+#[derive(Deserialize)]
+pub struct ExprProjectConfig {
+    /// Output field name and expression evaluated against input
+    pub fields: BTreeMap<String, String>,
+}
+
+impl FromPath for ExprProjectConfig {}
+
+impl ConfigInto<ExprProject> for ExprProjectConfig {}
+
+/// Project input into output with runtime expression per output field
+pub struct ExprProject {
+    fields: Vec<(String, Expression)>,
+}
+
+#[async_trait]
+impl FromConfig<ExprProjectConfig> for ExprProject {
+    async fn from_config(config: ExprProjectConfig) -> anyhow::Result<Self> {
+        let fields = config
+            .fields
+            .into_iter()
+            .map(|(name, expr)| Ok((name, expr.parse()?)))
+            .collect::<anyhow::Result<Vec<(String, Expression)>>>()?;
+        Ok(ExprProject { fields })
+    }
+}
+
+/// # Parameters
+/// * T: input
+/// * U: output
+#[async_trait]
+impl<T, U> Map<T, U, ExprProjectConfig> for ExprProject
+where
+    T: Serialize + Send + Sync + 'static,
+    U: DeserializeOwned + Send + 'static,
+{
+    async fn map(&mut self, data: T) -> anyhow::Result<U> {
+        let record = to_expr_value(&data)?;
+        let object = eval_object(&self.fields, &record)?;
+        Ok(serde_json::from_value(Value::Object(object))?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::prelude::*;
+    use crate::testing::*;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Clone, Debug, Serialize)]
+    struct Order {
+        id: u32,
+        price: f64,
+        qty: u32,
+        status: String,
+    }
+
+    #[derive(Clone, Debug, Deserialize, PartialEq)]
+    struct OrderTotal {
+        id: String,
+        total: f64,
+    }
+
+    fn orders() -> Vec<Order> {
+        vec![
+            Order {
+                id: 1,
+                price: 10.0,
+                qty: 20,
+                status: "paid".to_owned(),
+            },
+            Order {
+                id: 2,
+                price: 10.0,
+                qty: 1,
+                status: "paid".to_owned(),
+            },
+            Order {
+                id: 3,
+                price: 50.0,
+                qty: 3,
+                status: "open".to_owned(),
+            },
+        ]
+    }
+
+    #[tokio::test]
+    async fn test_expr_filter() {
+        let pipe = mapper!("expr_filter");
+        let config = config!(ExprFilterConfig, "resources/catalogs/expr_filter.yml");
+        let outputs: PipeOutputs<Vec<Order>> = run_pipe_with_records(pipe, config, vec![orders()])
+            .await
+            .expect("expr filter failed");
+        let filtered = outputs.into_records().pop().unwrap();
+        assert_eq!(1, filtered.len());
+        assert_eq!(1, filtered[0].id);
+    }
+
+    #[tokio::test]
+    async fn test_expr_project() {
+        let pipe = mapper!("expr_project");
+        let config = config!(ExprProjectConfig, "resources/catalogs/expr_project.yml");
+        let outputs: PipeOutputs<OrderTotal> = run_pipe_with_records(pipe, config, orders())
+            .await
+            .expect("expr project failed");
+        let totals = outputs.into_records();
+        assert_eq!(
+            vec![
+                OrderTotal {
+                    id: "order-1".to_owned(),
+                    total: 200.0
+                },
+                OrderTotal {
+                    id: "order-2".to_owned(),
+                    total: 10.0
+                },
+                OrderTotal {
+                    id: "order-3".to_owned(),
+                    total: 150.0
+                },
+            ],
+            totals
+        );
+    }
+}
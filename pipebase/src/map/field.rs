@@ -49,6 +49,7 @@ where
 mod tests {
 
     use crate::prelude::*;
+    use crate::testing::*;
 
     #[derive(FieldAccept)]
     struct Records {
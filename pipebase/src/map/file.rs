@@ -1,8 +1,9 @@
-use std::io::{BufReader, BufWriter, Read, Write};
+use std::io::{self, BufReader, BufWriter, Read, Write};
 use std::path::{Path, PathBuf};
 
 use super::Map;
 use crate::common::{ConfigInto, FromConfig, FromPath, Result};
+use crate::listen::{FileLedger, FileLedgerEntry};
 use async_trait::async_trait;
 use rand::distributions::Alphanumeric;
 use rand::{thread_rng, Rng};
@@ -129,6 +130,7 @@ mod file_rw_tests {
     use std::path::PathBuf;
 
     use crate::prelude::*;
+    use crate::testing::*;
 
     #[tokio::test]
     async fn test_file_rw() {
@@ -154,3 +156,146 @@ mod file_rw_tests {
         fs::remove_dir_all("resources/data").expect("clean data folder failed");
     }
 }
+
+#[derive(Clone, Deserialize)]
+pub enum FileArchiveMode {
+    /// Move file into archive directory
+    Move(String),
+    Delete,
+    /// Leave file in place, only record it in ledger
+    Keep,
+}
+
+#[derive(Deserialize)]
+pub struct FileArchiverConfig {
+    pub mode: FileArchiveMode,
+    /// Root of source files, keep path relative to root under archive directory
+    pub root: Option<String>,
+    /// Ledger of upstream `LocalFilePathVisitor`, file is recorded once archived
+    pub ledger: Option<String>,
+}
+
+impl FromPath for FileArchiverConfig {}
+
+impl ConfigInto<FileArchiver> for FileArchiverConfig {}
+
+/// Archive or delete file once downstream completed
+pub struct FileArchiver {
+    mode: FileArchiveMode,
+    root: Option<PathBuf>,
+    ledger: Option<FileLedger>,
+}
+
+#[async_trait]
+impl FromConfig<FileArchiverConfig> for FileArchiver {
+    async fn from_config(config: FileArchiverConfig) -> anyhow::Result<Self> {
+        let ledger = match config.ledger {
+            Some(path) => Some(FileLedger::load(path)?),
+            None => None,
+        };
+        Ok(FileArchiver {
+            mode: config.mode,
+            root: config.root.map(PathBuf::from),
+            ledger,
+        })
+    }
+}
+
+/// # Parameters
+/// * P, file path: input
+/// * PathBuf, archived or deleted file path: output
+#[async_trait]
+impl<P> Map<P, PathBuf, FileArchiverConfig> for FileArchiver
+where
+    P: AsRef<Path> + Send + 'static,
+{
+    async fn map(&mut self, path: P) -> anyhow::Result<PathBuf> {
+        let path = path.as_ref();
+        // fingerprint before file moved or deleted
+        let entry = match self.ledger {
+            Some(_) => Some(FileLedgerEntry::new(path, &fs::metadata(path)?)?),
+            None => None,
+        };
+        let archived = self.archive(path)?;
+        if let (Some(ledger), Some(entry)) = (self.ledger.as_mut(), entry) {
+            ledger.append(entry)?;
+        }
+        Ok(archived)
+    }
+}
+
+impl FileArchiver {
+    fn archive(&self, path: &Path) -> Result<PathBuf> {
+        let directory = match self.mode {
+            FileArchiveMode::Move(ref directory) => Path::new(directory),
+            FileArchiveMode::Keep => return Ok(path.to_path_buf()),
+            FileArchiveMode::Delete => {
+                fs::remove_file(path)?;
+                return Ok(path.to_path_buf());
+            }
+        };
+        let relative_path = match self.root {
+            Some(ref root) => path.strip_prefix(root).unwrap_or(path),
+            None => Path::new(path.file_name().unwrap_or_default()),
+        };
+        let archive_path = directory.join(relative_path);
+        if let Some(parent) = archive_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        Self::move_file(path, archive_path.as_path())?;
+        Ok(archive_path)
+    }
+
+    /// Rename, copy then remove if archive directory is on another filesystem
+    fn move_file(from: &Path, to: &Path) -> io::Result<()> {
+        match fs::rename(from, to) {
+            Err(err) if err.kind() == io::ErrorKind::CrossesDevices => {
+                fs::copy(from, to)?;
+                fs::remove_file(from)
+            }
+            result => result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod file_archiver_tests {
+
+    use std::fs;
+    use std::path::PathBuf;
+
+    use crate::prelude::*;
+    use crate::testing::*;
+
+    #[tokio::test]
+    async fn test_file_archiver() {
+        fs::create_dir_all("resources/archive_source/sub").unwrap();
+        fs::write("resources/archive_source/sub/foo.txt", "foo").unwrap();
+        fs::write("resources/archive_source/bar.txt", "bar").unwrap();
+        let pipe = mapper!("file_archiver");
+        let config = config!(FileArchiverConfig, "resources/catalogs/file_archiver.yml");
+        let outputs = run_pipe_with_records(
+            pipe,
+            config,
+            vec![PathBuf::from("resources/archive_source/sub/foo.txt")],
+        )
+        .await
+        .expect("archive failed");
+        let archived = PathBuf::from("resources/archive_target/sub/foo.txt");
+        assert_eq!(&vec![archived.to_owned()], outputs.get_records());
+        assert_eq!("foo", fs::read_to_string(archived).unwrap());
+        assert!(!PathBuf::from("resources/archive_source/sub/foo.txt").exists());
+        let pipe = mapper!("file_deleter");
+        let config = config!(FileArchiverConfig, "resources/catalogs/file_deleter.yml");
+        run_pipe_with_records(
+            pipe,
+            config,
+            vec![PathBuf::from("resources/archive_source/bar.txt")],
+        )
+        .await
+        .expect("delete failed");
+        assert!(!PathBuf::from("resources/archive_source/bar.txt").exists());
+        fs::remove_dir_all("resources/archive_source").unwrap();
+        fs::remove_dir_all("resources/archive_target").unwrap();
+    }
+}
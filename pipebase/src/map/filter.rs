@@ -55,6 +55,7 @@ where
 mod tests {
 
     use crate::prelude::*;
+    use crate::testing::*;
 
     #[derive(Clone, Debug, Filter)]
     #[filter(alias = "r", predicate = "r.r0 + r.r1 < 1")]
@@ -1,6 +1,8 @@
 mod add;
+mod capture;
 mod conversion;
 mod echo;
+mod expr;
 mod field;
 mod file;
 mod filter;
@@ -8,10 +10,13 @@ mod project;
 mod runtime;
 mod sort;
 mod split;
+mod value;
 
 pub use add::*;
+pub use capture::*;
 pub use conversion::*;
 pub use echo::*;
+pub use expr::*;
 pub use field::*;
 pub use file::*;
 pub use filter::*;
@@ -19,6 +24,7 @@ pub use project::*;
 pub use runtime::*;
 pub use sort::*;
 pub use split::*;
+pub use value::*;
 
 use async_trait::async_trait;
 
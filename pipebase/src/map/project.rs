@@ -48,6 +48,7 @@ where
 mod tests {
 
     use crate::prelude::*;
+    use crate::testing::*;
 
     #[derive(Debug)]
     struct Record {
@@ -115,6 +115,7 @@ where
 mod top_aggregator_tests {
 
     use crate::prelude::*;
+    use crate::testing::*;
 
     #[tokio::test]
     async fn test_top_aggregator() {
@@ -0,0 +1,270 @@
+use super::Map;
+use crate::common::{ConfigInto, FromConfig, FromPath, Value, ValuePath, ValueType};
+use async_trait::async_trait;
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct FieldRenamePair {
+    pub from: ValuePath,
+    pub to: ValuePath,
+}
+
+#[derive(Deserialize)]
+pub struct FieldRenamerConfig {
+    pub fields: Vec<FieldRenamePair>,
+}
+
+impl FromPath for FieldRenamerConfig {}
+
+impl ConfigInto<FieldRenamer> for FieldRenamerConfig {}
+
+/// Move field to new path, missing field skipped
+pub struct FieldRenamer {
+    fields: Vec<FieldRenamePair>,
+}
+
+#[async_trait]
+impl FromConfig<FieldRenamerConfig> for FieldRenamer {
+    async fn from_config(config: FieldRenamerConfig) -> anyhow::Result<Self> {
+        Ok(FieldRenamer {
+            fields: config.fields,
+        })
+    }
+}
+
+/// # Parameters
+/// * Value: input
+/// * Value: output
+#[async_trait]
+impl Map<Value, Value, FieldRenamerConfig> for FieldRenamer {
+    async fn map(&mut self, mut data: Value) -> anyhow::Result<Value> {
+        for field in &self.fields {
+            if let Some(value) = data.remove_path(&field.from) {
+                data.set_path(&field.to, value)?;
+            }
+        }
+        Ok(data)
+    }
+}
+
+#[derive(Deserialize)]
+pub struct FieldDropperConfig {
+    pub fields: Vec<ValuePath>,
+}
+
+impl FromPath for FieldDropperConfig {}
+
+impl ConfigInto<FieldDropper> for FieldDropperConfig {}
+
+/// Remove fields
+pub struct FieldDropper {
+    fields: Vec<ValuePath>,
+}
+
+#[async_trait]
+impl FromConfig<FieldDropperConfig> for FieldDropper {
+    async fn from_config(config: FieldDropperConfig) -> anyhow::Result<Self> {
+        Ok(FieldDropper {
+            fields: config.fields,
+        })
+    }
+}
+
+/// # Parameters
+/// * Value: input
+/// * Value: output
+#[async_trait]
+impl Map<Value, Value, FieldDropperConfig> for FieldDropper {
+    async fn map(&mut self, mut data: Value) -> anyhow::Result<Value> {
+        for field in &self.fields {
+            data.remove_path(field);
+        }
+        Ok(data)
+    }
+}
+
+#[derive(Deserialize)]
+pub struct FieldCast {
+    pub field: ValuePath,
+    pub ty: ValueType,
+}
+
+#[derive(Deserialize)]
+pub struct FieldCasterConfig {
+    pub fields: Vec<FieldCast>,
+}
+
+impl FromPath for FieldCasterConfig {}
+
+impl ConfigInto<FieldCaster> for FieldCasterConfig {}
+
+/// Cast fields into type, missing field skipped
+pub struct FieldCaster {
+    fields: Vec<FieldCast>,
+}
+
+#[async_trait]
+impl FromConfig<FieldCasterConfig> for FieldCaster {
+    async fn from_config(config: FieldCasterConfig) -> anyhow::Result<Self> {
+        Ok(FieldCaster {
+            fields: config.fields,
+        })
+    }
+}
+
+/// # Parameters
+/// * Value: input
+/// * Value: output
+#[async_trait]
+impl Map<Value, Value, FieldCasterConfig> for FieldCaster {
+    async fn map(&mut self, mut data: Value) -> anyhow::Result<Value> {
+        for field in &self.fields {
+            if let Some(value) = data.get_path_mut(&field.field) {
+                *value = value.cast(&field.ty)?;
+            }
+        }
+        Ok(data)
+    }
+}
+
+#[derive(Deserialize)]
+pub struct FieldDefault {
+    pub field: ValuePath,
+    pub value: Value,
+}
+
+#[derive(Deserialize)]
+pub struct FieldDefaulterConfig {
+    pub fields: Vec<FieldDefault>,
+}
+
+impl FromPath for FieldDefaulterConfig {}
+
+impl ConfigInto<FieldDefaulter> for FieldDefaulterConfig {}
+
+/// Set default value for missing or null fields
+pub struct FieldDefaulter {
+    fields: Vec<FieldDefault>,
+}
+
+#[async_trait]
+impl FromConfig<FieldDefaulterConfig> for FieldDefaulter {
+    async fn from_config(config: FieldDefaulterConfig) -> anyhow::Result<Self> {
+        Ok(FieldDefaulter {
+            fields: config.fields,
+        })
+    }
+}
+
+/// # Parameters
+/// * Value: input
+/// * Value: output
+#[async_trait]
+impl Map<Value, Value, FieldDefaulterConfig> for FieldDefaulter {
+    async fn map(&mut self, mut data: Value) -> anyhow::Result<Value> {
+        for field in &self.fields {
+            let is_null = match data.get_path(&field.field) {
+                Some(value) => value.is_null(),
+                None => true,
+            };
+            if is_null {
+                data.set_path(&field.field, field.value.to_owned())?;
+            }
+        }
+        Ok(data)
+    }
+}
+
+#[derive(Deserialize)]
+pub struct FieldFlattenerConfig {
+    /// Separator joins nested keys, `.` by default
+    pub separator: Option<String>,
+}
+
+impl FromPath for FieldFlattenerConfig {}
+
+impl ConfigInto<FieldFlattener> for FieldFlattenerConfig {}
+
+/// Flatten nested attributes into top level fields
+pub struct FieldFlattener {
+    separator: String,
+}
+
+#[async_trait]
+impl FromConfig<FieldFlattenerConfig> for FieldFlattener {
+    async fn from_config(config: FieldFlattenerConfig) -> anyhow::Result<Self> {
+        Ok(FieldFlattener {
+            separator: config.separator.unwrap_or_else(|| ".".to_owned()),
+        })
+    }
+}
+
+/// # Parameters
+/// * Value: input
+/// * Value: output
+#[async_trait]
+impl Map<Value, Value, FieldFlattenerConfig> for FieldFlattener {
+    async fn map(&mut self, data: Value) -> anyhow::Result<Value> {
+        Ok(data.flatten(&self.separator))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::prelude::*;
+    use crate::testing::*;
+
+    fn records() -> Vec<Value> {
+        let json = r#"
+        [
+            {"id": "1", "user": {"name": "foo", "age": "20"}, "debug": true},
+            {"id": "2", "user": {"name": "bar", "age": null}}
+        ]
+        "#;
+        serde_json::from_str(json).unwrap()
+    }
+
+    async fn map_records<M, C>(pipe: Mapper<'_>, config: C, records: Vec<Value>) -> Vec<Value>
+    where
+        M: Map<Value, Value, C> + 'static,
+        C: ConfigInto<M> + Send + Sync + 'static,
+    {
+        run_pipe_with_records(pipe, config, records)
+            .await
+            .expect("field mapper failed")
+            .into_records()
+    }
+
+    #[tokio::test]
+    async fn test_field_mappers() {
+        let records = records();
+        let config = config!(
+            FieldDefaulterConfig,
+            "resources/catalogs/field_defaulter.yml"
+        );
+        let records = map_records(mapper!("field_defaulter"), config, records).await;
+        let config = config!(FieldCasterConfig, "resources/catalogs/field_caster.yml");
+        let records = map_records(mapper!("field_caster"), config, records).await;
+        let config = config!(FieldRenamerConfig, "resources/catalogs/field_renamer.yml");
+        let records = map_records(mapper!("field_renamer"), config, records).await;
+        let config = config!(FieldDropperConfig, "resources/catalogs/field_dropper.yml");
+        let records = map_records(mapper!("field_dropper"), config, records).await;
+        let config = config!(
+            FieldFlattenerConfig,
+            "resources/catalogs/field_flattener.yml"
+        );
+        let records = map_records(mapper!("field_flattener"), config, records).await;
+        let records: Vec<String> = records
+            .iter()
+            .map(|record| serde_json::to_string(record).unwrap())
+            .collect();
+        assert_eq!(
+            vec![
+                r#"{"id":1,"name":"foo","user_age":20}"#,
+                r#"{"id":2,"name":"bar","user_age":0}"#,
+            ],
+            records
+        );
+    }
+}
@@ -0,0 +1,200 @@
+use super::PollResponse;
+use crate::common::Value;
+use serde::{de::DeserializeOwned, Deserialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Cursor of incremental query, rows are polled in order of cursor column
+#[derive(Clone, Deserialize)]
+pub struct CursorConfig {
+    /// Cursor column, value of last polled row is bound to query parameter in next poll
+    pub column: String,
+    /// Cursor value of first poll
+    pub initial: Value,
+    /// Checkpoint file path to persist last cursor value
+    pub checkpoint: Option<String>,
+}
+
+/// Last cursor value of incremental query, optionally persisted in local json file.
+/// Cursor is persisted once rows are polled and before they are delivered downstream,
+/// rows in flight when pipe stops are not polled again after restart (at-most-once)
+pub struct CursorCheckpoint {
+    column: String,
+    path: Option<PathBuf>,
+    cursor: Value,
+}
+
+impl CursorCheckpoint {
+    /// Load persisted cursor, initial cursor if checkpoint not configured or not exists
+    pub fn load(config: CursorConfig) -> anyhow::Result<Self> {
+        let path = config.checkpoint.map(PathBuf::from);
+        let cursor = match path {
+            Some(ref path) if path.exists() => serde_json::from_slice(&fs::read(path)?)?,
+            _ => config.initial,
+        };
+        Ok(CursorCheckpoint {
+            column: config.column,
+            path,
+            cursor,
+        })
+    }
+
+    pub fn get_column(&self) -> &str {
+        &self.column
+    }
+
+    pub fn get(&self) -> &Value {
+        &self.cursor
+    }
+
+    /// Advance cursor to cursor column value of last row
+    pub fn advance<'a, R>(&mut self, rows: R) -> anyhow::Result<()>
+    where
+        R: IntoIterator<Item = &'a Value>,
+        R::IntoIter: DoubleEndedIterator,
+    {
+        let last = match rows.into_iter().next_back() {
+            Some(last) => last,
+            None => return Ok(()),
+        };
+        let column = self.column.as_str();
+        let cursor = match last.as_attributes().and_then(|attrs| attrs.get(column)) {
+            Some(cursor) if !cursor.is_null() => cursor.to_owned(),
+            Some(_) => anyhow::bail!("cursor column '{}' is null", column),
+            None => anyhow::bail!("cursor column '{}' not found in row", column),
+        };
+        self.set(cursor)
+    }
+
+    /// Deserialize polled rows and advance cursor to last row, no result if no rows polled
+    pub fn poll_rows<U>(&mut self, rows: Vec<Value>) -> anyhow::Result<PollResponse<Vec<U>>>
+    where
+        U: DeserializeOwned,
+    {
+        if rows.is_empty() {
+            return Ok(PollResponse::PollResult(None));
+        }
+        let mut records: Vec<U> = Vec::with_capacity(rows.len());
+        for row in &rows {
+            records.push(serde_json::from_value(serde_json::to_value(row)?)?);
+        }
+        self.advance(&rows)?;
+        Ok(PollResponse::PollResult(Some(records)))
+    }
+
+    /// Set and persist cursor
+    pub fn set(&mut self, cursor: Value) -> anyhow::Result<()> {
+        self.cursor = cursor;
+        match self.path {
+            Some(ref path) => Self::save(path, &self.cursor),
+            None => Ok(()),
+        }
+    }
+
+    /// Write to temporary file and rename, checkpoint never left half written
+    fn save(path: &Path, cursor: &Value) -> anyhow::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut tmp_path = path.to_path_buf().into_os_string();
+        tmp_path.push(".tmp");
+        fs::write(&tmp_path, serde_json::to_vec(cursor)?)?;
+        fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use std::collections::HashMap;
+
+    const CURSOR_DIRECTORY: &str = "resources/test_cursor";
+
+    fn row(id: i64) -> Value {
+        let mut attributes = HashMap::new();
+        attributes.insert("id".to_owned(), Value::from(id));
+        Value::Attributes(attributes)
+    }
+
+    fn config(checkpoint: &Path) -> CursorConfig {
+        CursorConfig {
+            column: "id".to_owned(),
+            initial: Value::from(0_i64),
+            checkpoint: Some(checkpoint.to_string_lossy().into_owned()),
+        }
+    }
+
+    #[test]
+    fn test_cursor_checkpoint() {
+        let _ = fs::remove_dir_all(CURSOR_DIRECTORY);
+        let checkpoint_path = Path::new(CURSOR_DIRECTORY).join("cursor.json");
+        let mut checkpoint = CursorCheckpoint::load(config(&checkpoint_path)).unwrap();
+        assert_eq!(&Value::from(0_i64), checkpoint.get());
+        // no rows polled, cursor unchanged
+        checkpoint.advance(&[]).unwrap();
+        assert_eq!(&Value::from(0_i64), checkpoint.get());
+        checkpoint.advance(&[row(1), row(2)]).unwrap();
+        assert_eq!(&Value::from(2_i64), checkpoint.get());
+        // resume from checkpoint
+        let checkpoint = CursorCheckpoint::load(config(&checkpoint_path)).unwrap();
+        assert_eq!(&Value::from(2_i64), checkpoint.get());
+        fs::remove_dir_all(CURSOR_DIRECTORY).unwrap();
+    }
+
+    #[test]
+    fn test_cursor_poll_rows() {
+        let mut checkpoint = CursorCheckpoint::load(CursorConfig {
+            column: "id".to_owned(),
+            initial: Value::from(0_i64),
+            checkpoint: None,
+        })
+        .unwrap();
+        match checkpoint.poll_rows::<Value>(vec![]).unwrap() {
+            PollResponse::PollResult(None) => (),
+            _ => panic!("expect no result"),
+        }
+        let ids = match checkpoint
+            .poll_rows::<HashMap<String, i64>>(vec![row(1), row(2)])
+            .unwrap()
+        {
+            PollResponse::PollResult(Some(records)) => records
+                .into_iter()
+                .map(|record| record["id"])
+                .collect::<Vec<i64>>(),
+            _ => panic!("expect polled rows"),
+        };
+        assert_eq!(vec![1, 2], ids);
+        assert_eq!(&Value::from(2_i64), checkpoint.get());
+    }
+
+    #[test]
+    fn test_cursor_column_not_found() {
+        let mut checkpoint = CursorCheckpoint::load(CursorConfig {
+            column: "ts".to_owned(),
+            initial: Value::from(0_i64),
+            checkpoint: None,
+        })
+        .unwrap();
+        assert!(checkpoint.advance(&[row(1)]).is_err())
+    }
+
+    #[test]
+    fn test_cursor_column_null() {
+        let mut checkpoint = CursorCheckpoint::load(CursorConfig {
+            column: "id".to_owned(),
+            initial: Value::from(0_i64),
+            checkpoint: None,
+        })
+        .unwrap();
+        let mut attributes = HashMap::new();
+        attributes.insert("id".to_owned(), Value::Long(None));
+        assert!(checkpoint
+            .advance(&[Value::Attributes(attributes)])
+            .is_err());
+        assert_eq!(&Value::from(0_i64), checkpoint.get());
+    }
+}
@@ -1,6 +1,8 @@
+mod cursor;
 mod runtime;
 mod timer;
 
+pub use cursor::*;
 pub use runtime::*;
 pub use timer::*;
 
@@ -0,0 +1,118 @@
+use super::Select;
+use crate::common::{to_expr_value, ConfigInto, Expression, FromConfig, FromPath};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize)]
+pub struct ExprSelectorConfig {
+    /// Boolean expression per downstream in declaration order
+    pub routes: Vec<String>,
+    /// Downstream selected if no route matched, record dropped by default
+    pub default: Option<usize>,
+}
+
+impl FromPath for ExprSelectorConfig {}
+
+impl ConfigInto<ExprSelector> for ExprSelectorConfig {}
+
+/// Select every downstream whose route expression is true
+pub struct ExprSelector {
+    routes: Vec<Expression>,
+    default: Option<usize>,
+}
+
+#[async_trait]
+impl FromConfig<ExprSelectorConfig> for ExprSelector {
+    async fn from_config(config: ExprSelectorConfig) -> anyhow::Result<Self> {
+        let routes = config
+            .routes
+            .iter()
+            .map(|route| route.parse())
+            .collect::<anyhow::Result<Vec<Expression>>>()?;
+        Ok(ExprSelector {
+            routes,
+            default: config.default,
+        })
+    }
+}
+
+/// # Parameters
+/// * T: input
+#[async_trait]
+impl<T> Select<T, ExprSelectorConfig> for ExprSelector
+where
+    T: Serialize + Sync,
+{
+    /// `candidates`: index of downstreams
+    /// `t`: input data reference
+    async fn select(&mut self, t: &T, candidates: &[&usize]) -> anyhow::Result<Vec<usize>> {
+        let record = to_expr_value(t)?;
+        let mut selected: Vec<usize> = vec![];
+        for candidate in candidates {
+            let route = match self.routes.get(**candidate) {
+                Some(route) => route,
+                None => continue,
+            };
+            if route.eval_bool(&record)? {
+                selected.push(**candidate);
+            }
+        }
+        if !selected.is_empty() {
+            return Ok(selected);
+        }
+        match self.default {
+            Some(default) if candidates.contains(&&default) => Ok(vec![default]),
+            _ => Ok(selected),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::prelude::*;
+    use crate::testing::*;
+    use serde::Serialize;
+
+    #[derive(Clone, Debug, Serialize)]
+    struct Event {
+        level: String,
+        code: u32,
+    }
+
+    fn event(level: &str, code: u32) -> Event {
+        Event {
+            level: level.to_owned(),
+            code,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_expr_select() {
+        let (tx0, rx0) = channel!(Event, 1024);
+        let (tx1, rx1) = channel!(Event, 1024);
+        let (tx2, rx2) = channel!(Event, 1024);
+        let (tx3, rx3) = channel!(Event, 1024);
+        let channels = pipe_channels!(rx0, [tx1, tx2, tx3]);
+        let config = config!(ExprSelectorConfig, "resources/catalogs/expr_selector.yml");
+        let pipe = selector!("expr_select");
+        populate_records(
+            tx0,
+            vec![
+                event("error", 500),
+                event("warn", 404),
+                event("info", 200),
+                event("error", 200),
+            ],
+        )
+        .await;
+        join_pipes!([run_pipe!(pipe, config, channels)]);
+        let errors: Vec<Event> = receive_records(rx1).await;
+        let failures: Vec<Event> = receive_records(rx2).await;
+        let others: Vec<Event> = receive_records(rx3).await;
+        assert_eq!(2, errors.len());
+        assert_eq!(2, failures.len());
+        assert_eq!(1, others.len());
+        assert_eq!("info", others[0].level);
+    }
+}
@@ -1,8 +1,10 @@
+mod expr;
 mod hash;
 mod random;
 mod roundrobin;
 mod runtime;
 
+pub use expr::*;
 pub use hash::*;
 pub use random::*;
 pub use roundrobin::*;
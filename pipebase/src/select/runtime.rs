@@ -72,6 +72,11 @@ where
                     continue;
                 }
             };
+            if selected.is_empty() {
+                // no downstream selected, drop data
+                context.inc_total_run();
+                continue;
+            }
             let mut t_replicas = replicate(t, selected.len());
             let jhs: HashMap<usize, JoinHandle<core::result::Result<(), SendError<T>>>> = selected
                 .into_iter()
@@ -1,28 +1,45 @@
+use flate2::read::MultiGzDecoder;
 use std::{
     fs::File,
-    io::{BufRead, BufReader},
+    io::{BufRead, BufReader, Read},
     path::Path,
 };
 
-use super::Stream;
-use crate::common::{ConfigInto, FromConfig, FromPath};
+use super::{FileFollowConfig, FileFollowers, Stream};
+use crate::common::{ConfigFormat, ConfigInto, FromConfig, FromPath};
 use async_trait::async_trait;
 use serde::Deserialize;
 use tokio::sync::mpsc::Sender;
 
 #[async_trait]
 pub trait FileStreamReader {
-    fn new_reader<P: AsRef<Path>>(path: P) -> anyhow::Result<BufReader<File>> {
+    /// Decompress `.gz` and `.zst` file while streaming
+    fn new_reader<P: AsRef<Path>>(path: P) -> anyhow::Result<BufReader<Box<dyn Read + Send>>> {
+        let path = path.as_ref();
         let f = File::open(path)?;
-        Ok(BufReader::new(f))
+        let rdr: Box<dyn Read + Send> = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("gz") => Box::new(MultiGzDecoder::new(f)),
+            Some("zst") => Box::new(zstd::stream::read::Decoder::new(f)?),
+            _ => Box::new(f),
+        };
+        Ok(BufReader::new(rdr))
     }
 
     async fn stream_file<P: AsRef<Path> + Send>(&self, path: P) -> anyhow::Result<()>;
 }
 
+fn new_followers(follow: Option<FileFollowConfig>) -> anyhow::Result<Option<FileFollowers>> {
+    match follow {
+        Some(follow) => Ok(Some(FileFollowers::new(follow)?)),
+        None => Ok(None),
+    }
+}
+
 #[derive(Deserialize)]
 pub struct FileSplitReaderConfig {
     pub delimiter: u8,
+    /// Tail each file in its own task instead of reading to EOF once
+    pub follow: Option<FileFollowConfig>,
 }
 
 impl FromPath for FileSplitReaderConfig {}
@@ -34,6 +51,7 @@ impl ConfigInto<FileSplitReader> for FileSplitReaderConfig {}
 pub struct FileSplitReader {
     /// Delimite to split file
     delimiter: u8,
+    followers: Option<FileFollowers>,
     /// Sender to notify downstreams
     tx: Option<Sender<Vec<u8>>>,
 }
@@ -60,6 +78,7 @@ impl FromConfig<FileSplitReaderConfig> for FileSplitReader {
     async fn from_config(config: FileSplitReaderConfig) -> anyhow::Result<Self> {
         Ok(FileSplitReader {
             delimiter: config.delimiter,
+            followers: new_followers(config.follow)?,
             tx: None,
         })
     }
@@ -74,12 +93,24 @@ where
     P: AsRef<Path> + Send + 'static,
 {
     async fn stream(&mut self, path: P) -> anyhow::Result<()> {
-        self.stream_file(path).await
+        let tx = self.tx.as_ref().unwrap().to_owned();
+        match self.followers {
+            Some(ref mut followers) => followers.follow(path, self.delimiter, tx, Ok).await,
+            None => self.stream_file(path).await,
+        }
     }
 
     fn set_sender(&mut self, sender: Sender<Vec<u8>>) {
         self.tx = Some(sender)
     }
+
+    /// Wait followers if any
+    async fn close(&mut self) -> anyhow::Result<()> {
+        match self.followers {
+            Some(ref mut followers) => followers.join().await,
+            None => Ok(()),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -88,6 +119,7 @@ mod file_split_streamer_tests {
     use std::path::PathBuf;
 
     use crate::prelude::*;
+    use crate::testing::*;
 
     #[tokio::test]
     async fn test_file_split_streamer() {
@@ -112,16 +144,25 @@ mod file_split_streamer_tests {
     }
 }
 
-#[derive(Deserialize)]
-pub struct FileLineReaderConfig {}
+#[derive(Default, Deserialize)]
+pub struct FileLineReaderConfig {
+    /// Tail each file in its own task instead of reading to EOF once
+    pub follow: Option<FileFollowConfig>,
+}
 
 #[async_trait]
 impl FromPath for FileLineReaderConfig {
-    async fn from_path<P>(_path: P) -> anyhow::Result<Self>
+    /// Empty path for default config
+    async fn from_path<P>(path: P) -> anyhow::Result<Self>
     where
         P: AsRef<std::path::Path> + Send,
     {
-        Ok(FileLineReaderConfig {})
+        if path.as_ref().as_os_str().is_empty() {
+            return Ok(FileLineReaderConfig::default());
+        }
+        let format = ConfigFormat::from_path(&path);
+        let bytes = std::fs::read(path)?;
+        format.deserialize(&bytes)
     }
 }
 
@@ -130,17 +171,28 @@ impl ConfigInto<FileLineReader> for FileLineReaderConfig {}
 
 /// Stream file lines
 pub struct FileLineReader {
+    followers: Option<FileFollowers>,
     /// Sender to notify downstreams
     tx: Option<Sender<String>>,
 }
 
 #[async_trait]
 impl FromConfig<FileLineReaderConfig> for FileLineReader {
-    async fn from_config(_config: FileLineReaderConfig) -> anyhow::Result<Self> {
-        Ok(FileLineReader { tx: None })
+    async fn from_config(config: FileLineReaderConfig) -> anyhow::Result<Self> {
+        Ok(FileLineReader {
+            followers: new_followers(config.follow)?,
+            tx: None,
+        })
     }
 }
 
+fn into_line(mut bin: Vec<u8>) -> anyhow::Result<String> {
+    if bin.last() == Some(&b'\r') {
+        bin.pop();
+    }
+    Ok(String::from_utf8(bin)?)
+}
+
 #[async_trait]
 impl FileStreamReader for FileLineReader {
     async fn stream_file<P>(&self, path: P) -> anyhow::Result<()>
@@ -171,12 +223,24 @@ where
     /// Input: P, file path
     /// Output: String, file line
     async fn stream(&mut self, path: P) -> anyhow::Result<()> {
-        self.stream_file(path).await
+        let tx = self.tx.as_ref().unwrap().to_owned();
+        match self.followers {
+            Some(ref mut followers) => followers.follow(path, b'\n', tx, into_line).await,
+            None => self.stream_file(path).await,
+        }
     }
 
     fn set_sender(&mut self, sender: Sender<String>) {
         self.tx = Some(sender)
     }
+
+    /// Wait followers if any
+    async fn close(&mut self) -> anyhow::Result<()> {
+        match self.followers {
+            Some(ref mut followers) => followers.join().await,
+            None => Ok(()),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -185,6 +249,7 @@ mod file_line_streamer_tests {
     use std::path::PathBuf;
 
     use crate::prelude::*;
+    use crate::testing::*;
 
     #[tokio::test]
     async fn test_file_line_streamer() {
@@ -204,4 +269,92 @@ mod file_line_streamer_tests {
         let line = rx1.recv().await.unwrap();
         assert_eq!("foo2 bar2", &line);
     }
+
+    #[tokio::test]
+    async fn test_compressed_file_line_streamer() {
+        use flate2::{write::GzEncoder, Compression};
+        use std::io::Write;
+        let gz_path = "resources/test_file_stream/test_file_2.txt.gz";
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(b"foo1 bar1\nfoo2 bar2\n").unwrap();
+        std::fs::write(gz_path, encoder.finish().unwrap()).unwrap();
+        let zst_path = "resources/test_file_stream/test_file_2.txt.zst";
+        let zst = zstd::stream::encode_all(&b"foo3 bar3\n"[..], 3).unwrap();
+        std::fs::write(zst_path, zst).unwrap();
+        let pipe = streamer!("file_line_streamer");
+        let config = config!(FileLineReaderConfig);
+        let outputs = run_pipe_with_records(
+            pipe,
+            config,
+            vec![PathBuf::from(gz_path), PathBuf::from(zst_path)],
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            &vec!["foo1 bar1", "foo2 bar2", "foo3 bar3"],
+            outputs.get_records()
+        );
+        std::fs::remove_file(gz_path).unwrap();
+        std::fs::remove_file(zst_path).unwrap();
+    }
+
+    const FOLLOW_STREAM_DIRECTORY: &str = "resources/test_file_follow_stream";
+
+    async fn recv_line(rx: &mut tokio::sync::mpsc::Receiver<String>) -> String {
+        tokio::time::timeout(std::time::Duration::from_secs(5), rx.recv())
+            .await
+            .expect("no line followed")
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_follow_file_line_streamer() {
+        use std::io::Write;
+        let _ = std::fs::remove_dir_all(FOLLOW_STREAM_DIRECTORY);
+        std::fs::create_dir_all(FOLLOW_STREAM_DIRECTORY).unwrap();
+        let path0 = PathBuf::from(FOLLOW_STREAM_DIRECTORY).join("app0.log");
+        let path1 = PathBuf::from(FOLLOW_STREAM_DIRECTORY).join("app1.log");
+        std::fs::write(&path0, "foo1\n").unwrap();
+        std::fs::write(&path1, "bar1\n").unwrap();
+        let (tx0, rx0) = channel!(PathBuf, 1024);
+        let (tx1, mut rx1) = channel!(String, 1024);
+        let channels = pipe_channels!(rx0, [tx1]);
+        let pipe = streamer!("file_follow_line_streamer");
+        let config = config!(
+            FileLineReaderConfig,
+            "resources/catalogs/file_follow_line_streamer.yml"
+        );
+        let join = run_pipe!(pipe, config, channels);
+        // both files followed without idle timeout
+        populate_records(tx0, vec![path0.to_owned(), path1.to_owned()]).await;
+        let mut lines = vec![recv_line(&mut rx1).await, recv_line(&mut rx1).await];
+        lines.sort();
+        assert_eq!(vec!["bar1", "foo1"], lines);
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path0)
+            .unwrap();
+        file.write_all(b"foo2\n").unwrap();
+        assert_eq!("foo2", recv_line(&mut rx1).await);
+        join.abort();
+        std::fs::remove_dir_all(FOLLOW_STREAM_DIRECTORY).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_follow_compressed_file_rejected() {
+        let pipe = streamer!("file_follow_line_streamer");
+        let config = config!(
+            FileLineReaderConfig,
+            "resources/catalogs/file_follow_line_streamer.yml"
+        );
+        let outputs = run_pipe_with_records(
+            pipe,
+            config,
+            vec![PathBuf::from("resources/test_file_stream/app.log.gz")],
+        )
+        .await
+        .unwrap();
+        assert!(outputs.get_records().is_empty());
+        assert_context(&outputs.get_context(), State::Done, 1, 1);
+    }
 }
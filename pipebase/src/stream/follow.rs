@@ -0,0 +1,526 @@
+use crate::common::Period;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, File, Metadata};
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+use tokio::sync::mpsc::Sender;
+use tokio::task::JoinHandle;
+use tokio::time::sleep;
+
+const DEFAULT_FOLLOW_INTERVAL_MILLIS: i64 = 1000;
+/// Bytes read last compared with file content to detect truncation
+const FINGERPRINT_LEN: usize = 64;
+
+#[derive(Clone, Deserialize)]
+pub struct FileFollowConfig {
+    /// Interval to check file growth at EOF, 1 second by default
+    pub interval: Option<Period>,
+    /// Stop following once no data for the period, follow forever by default
+    pub idle_timeout: Option<Period>,
+    /// Checkpoint file path to persist per-file offset
+    pub checkpoint: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct FileOffset {
+    /// Identity of file, inode on unix
+    pub file_id: u64,
+    /// Bytes consumed
+    pub offset: u64,
+}
+
+/// Per-file offsets persisted in local json file
+pub struct FileCheckpoint {
+    path: PathBuf,
+    offsets: HashMap<String, FileOffset>,
+}
+
+impl FileCheckpoint {
+    pub fn load<P: AsRef<Path>>(path: P) -> anyhow::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let offsets = match path.exists() {
+            true => serde_json::from_slice(&fs::read(&path)?)?,
+            false => HashMap::new(),
+        };
+        Ok(FileCheckpoint { path, offsets })
+    }
+
+    pub fn get(&self, file_path: &Path) -> Option<&FileOffset> {
+        self.offsets.get(file_path.to_string_lossy().as_ref())
+    }
+
+    pub fn set(&mut self, file_path: &Path, offset: FileOffset) {
+        self.offsets
+            .insert(file_path.to_string_lossy().into_owned(), offset);
+    }
+
+    /// Write to temporary file and rename, checkpoint never left half written
+    pub fn save(&self) -> anyhow::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut tmp_path = self.path.clone().into_os_string();
+        tmp_path.push(".tmp");
+        fs::write(&tmp_path, serde_json::to_vec(&self.offsets)?)?;
+        fs::rename(&tmp_path, &self.path)?;
+        Ok(())
+    }
+}
+
+#[cfg(unix)]
+fn get_file_id(metadata: &Metadata) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+    metadata.ino()
+}
+
+#[cfg(not(unix))]
+fn get_file_id(_metadata: &Metadata) -> u64 {
+    0
+}
+
+/// Checkpoint shared by followers of all paths
+pub type SharedFileCheckpoint = Arc<Mutex<FileCheckpoint>>;
+
+/// Tail growing file split by delimiter, reopen file once rotated and
+/// rewind once truncated
+pub struct FileFollower {
+    path: PathBuf,
+    delimiter: u8,
+    interval: Duration,
+    idle_timeout: Option<Duration>,
+    checkpoint: Option<SharedFileCheckpoint>,
+    reader: Option<BufReader<File>>,
+    file_id: u64,
+    /// Creation time of current file if supported by platform
+    created: Option<SystemTime>,
+    /// Bytes consumed in current file
+    offset: u64,
+    /// Last bytes read, file rewritten in place if content differs
+    fingerprint: Vec<u8>,
+    /// Partial split not yet delimited
+    buffer: Vec<u8>,
+    idle: Duration,
+}
+
+impl FileFollower {
+    pub fn new<P: AsRef<Path>>(
+        path: P,
+        delimiter: u8,
+        config: &FileFollowConfig,
+        checkpoint: Option<SharedFileCheckpoint>,
+    ) -> Self {
+        let interval = config
+            .interval
+            .to_owned()
+            .unwrap_or(Period::Millis(DEFAULT_FOLLOW_INTERVAL_MILLIS));
+        FileFollower {
+            path: path.as_ref().to_path_buf(),
+            delimiter,
+            interval: interval.into(),
+            idle_timeout: config.idle_timeout.to_owned().map(Duration::from),
+            checkpoint,
+            reader: None,
+            file_id: 0,
+            created: None,
+            offset: 0,
+            fingerprint: vec![],
+            buffer: vec![],
+            idle: Duration::from_millis(0),
+        }
+    }
+
+    /// Next split without delimiter, None once idle timeout elapsed
+    pub async fn next_split(&mut self) -> anyhow::Result<Option<Vec<u8>>> {
+        loop {
+            if self.reader.is_none() && !self.open()? {
+                match self.wait().await {
+                    true => continue,
+                    false => return Ok(None),
+                }
+            }
+            let reader = self.reader.as_mut().unwrap();
+            let n = reader.read_until(self.delimiter, &mut self.buffer)?;
+            if n > 0 {
+                self.update_fingerprint(n);
+                self.idle = Duration::from_millis(0);
+                if self.buffer.last() == Some(&self.delimiter) {
+                    self.offset += self.buffer.len() as u64;
+                    let mut split = std::mem::take(&mut self.buffer);
+                    split.pop();
+                    return Ok(Some(split));
+                }
+                continue;
+            }
+            // EOF
+            self.save_checkpoint()?;
+            if self.is_rotated()? {
+                self.reader = None;
+                if !self.buffer.is_empty() {
+                    return Ok(Some(std::mem::take(&mut self.buffer)));
+                }
+                continue;
+            }
+            if self.is_truncated()? {
+                self.rewind()?;
+                continue;
+            }
+            if self.wait().await {
+                // file may be rewritten in place while waiting
+                if self.is_truncated()? {
+                    self.rewind()?;
+                }
+                continue;
+            }
+            if self.buffer.is_empty() {
+                return Ok(None);
+            }
+            self.offset += self.buffer.len() as u64;
+            self.save_checkpoint()?;
+            return Ok(Some(std::mem::take(&mut self.buffer)));
+        }
+    }
+
+    /// Open file and seek to checkpoint offset, false if file not exists
+    fn open(&mut self) -> anyhow::Result<bool> {
+        let file = match File::open(&self.path) {
+            Ok(file) => file,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(false),
+            Err(err) => return Err(err.into()),
+        };
+        let metadata = file.metadata()?;
+        let file_id = get_file_id(&metadata);
+        let checkpoint_offset = self.checkpoint.as_ref().and_then(|checkpoint| {
+            let checkpoint = checkpoint.lock().expect("file checkpoint");
+            checkpoint.get(&self.path).cloned()
+        });
+        let offset = match checkpoint_offset {
+            Some(offset) if offset.file_id == file_id && offset.offset <= metadata.len() => {
+                offset.offset
+            }
+            _ => 0,
+        };
+        let mut reader = BufReader::new(file);
+        reader.seek(SeekFrom::Start(offset))?;
+        self.reader = Some(reader);
+        self.file_id = file_id;
+        self.created = metadata.created().ok();
+        self.offset = offset;
+        self.buffer.clear();
+        self.fingerprint = self.read_fingerprint(offset)?;
+        Ok(true)
+    }
+
+    fn rewind(&mut self) -> anyhow::Result<()> {
+        let reader = self.reader.as_mut().unwrap();
+        reader.seek(SeekFrom::Start(0))?;
+        self.offset = 0;
+        self.buffer.clear();
+        self.fingerprint.clear();
+        Ok(())
+    }
+
+    /// Keep last bytes of `n` bytes just read into buffer
+    fn update_fingerprint(&mut self, n: usize) {
+        let read = &self.buffer[self.buffer.len() - n..];
+        self.fingerprint.extend_from_slice(read);
+        let excess = self.fingerprint.len().saturating_sub(FINGERPRINT_LEN);
+        self.fingerprint.drain(..excess);
+    }
+
+    /// Bytes of file ending at position, at most fingerprint length
+    fn read_fingerprint(&self, end: u64) -> io::Result<Vec<u8>> {
+        let start = end.saturating_sub(FINGERPRINT_LEN as u64);
+        let mut file = File::open(&self.path)?;
+        file.seek(SeekFrom::Start(start))?;
+        let mut fingerprint = vec![];
+        file.take(end - start).read_to_end(&mut fingerprint)?;
+        Ok(fingerprint)
+    }
+
+    /// Path removed or replaced by new file
+    fn is_rotated(&self) -> anyhow::Result<bool> {
+        let metadata = match fs::metadata(&self.path) {
+            Ok(metadata) => metadata,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(true),
+            Err(err) => return Err(err.into()),
+        };
+        if get_file_id(&metadata) != self.file_id {
+            return Ok(true);
+        }
+        match (metadata.created().ok(), self.created) {
+            (Some(created), Some(last_created)) => Ok(created != last_created),
+            _ => Ok(false),
+        }
+    }
+
+    /// File shorter than bytes read, or rewritten in place with different content
+    /// even if it regrows beyond bytes read before next check
+    fn is_truncated(&self) -> anyhow::Result<bool> {
+        let position = self.offset + self.buffer.len() as u64;
+        let len = match fs::metadata(&self.path) {
+            Ok(metadata) => metadata.len(),
+            // removed, handled as rotation
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(false),
+            Err(err) => return Err(err.into()),
+        };
+        if len < position {
+            return Ok(true);
+        }
+        match self.read_fingerprint(position) {
+            Ok(fingerprint) => Ok(fingerprint != self.fingerprint),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(false),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Sleep an interval, false if idle timeout elapsed
+    async fn wait(&mut self) -> bool {
+        if let Some(idle_timeout) = self.idle_timeout {
+            if self.idle >= idle_timeout {
+                return false;
+            }
+        }
+        sleep(self.interval).await;
+        self.idle += self.interval;
+        true
+    }
+
+    fn save_checkpoint(&mut self) -> anyhow::Result<()> {
+        let mut checkpoint = match self.checkpoint {
+            Some(ref checkpoint) => checkpoint.lock().expect("file checkpoint"),
+            None => return Ok(()),
+        };
+        let offset = FileOffset {
+            file_id: self.file_id,
+            offset: self.offset,
+        };
+        if checkpoint.get(&self.path) == Some(&offset) {
+            return Ok(());
+        }
+        checkpoint.set(&self.path, offset);
+        checkpoint.save()
+    }
+}
+
+/// Follow each path in its own task, so a path followed forever does not block others.
+/// Followers are aborted once dropped
+pub struct FileFollowers {
+    config: FileFollowConfig,
+    checkpoint: Option<SharedFileCheckpoint>,
+    followers: HashMap<PathBuf, JoinHandle<anyhow::Result<()>>>,
+}
+
+impl FileFollowers {
+    pub fn new(config: FileFollowConfig) -> anyhow::Result<Self> {
+        let checkpoint = match config.checkpoint {
+            Some(ref path) => Some(Arc::new(Mutex::new(FileCheckpoint::load(path)?))),
+            None => None,
+        };
+        Ok(FileFollowers {
+            config,
+            checkpoint,
+            followers: HashMap::new(),
+        })
+    }
+
+    /// Spawn follower sending mapped splits, path still followed is skipped.
+    /// Error of finished follower is returned
+    pub async fn follow<P, U, F>(
+        &mut self,
+        path: P,
+        delimiter: u8,
+        tx: Sender<U>,
+        map: F,
+    ) -> anyhow::Result<()>
+    where
+        P: AsRef<Path>,
+        U: Send + 'static,
+        F: Fn(Vec<u8>) -> anyhow::Result<U> + Send + Sync + 'static,
+    {
+        let path = path.as_ref().to_path_buf();
+        if is_compressed(&path) {
+            anyhow::bail!("can not follow compressed file '{}'", path.display())
+        }
+        let finished = self.take_followers(|follower| follower.is_finished());
+        // follow path again if its follower finished
+        let result = Self::join_followers(finished).await;
+        if !self.followers.contains_key(&path) {
+            let mut follower =
+                FileFollower::new(&path, delimiter, &self.config, self.checkpoint.clone());
+            let join = tokio::spawn(async move {
+                while let Some(split) = follower.next_split().await? {
+                    if tx.send(map(split)?).await.is_err() {
+                        // downstream dropped, stop following
+                        break;
+                    }
+                }
+                Ok(())
+            });
+            self.followers.insert(path, join);
+        }
+        result
+    }
+
+    /// Wait all followers until idle timeout or downstream dropped, first error returned
+    pub async fn join(&mut self) -> anyhow::Result<()> {
+        let followers = self.take_followers(|_| true);
+        Self::join_followers(followers).await
+    }
+
+    fn take_followers<F>(&mut self, predicate: F) -> Vec<(PathBuf, JoinHandle<anyhow::Result<()>>)>
+    where
+        F: Fn(&JoinHandle<anyhow::Result<()>>) -> bool,
+    {
+        let paths: Vec<PathBuf> = self
+            .followers
+            .iter()
+            .filter(|(_, follower)| predicate(follower))
+            .map(|(path, _)| path.to_owned())
+            .collect();
+        paths
+            .into_iter()
+            .filter_map(|path| self.followers.remove_entry(&path))
+            .collect()
+    }
+
+    async fn join_followers(
+        followers: Vec<(PathBuf, JoinHandle<anyhow::Result<()>>)>,
+    ) -> anyhow::Result<()> {
+        let mut result = Ok(());
+        for (path, follower) in followers {
+            let follower_result = match follower.await {
+                Ok(follower_result) => follower_result,
+                Err(err) => Err(err.into()),
+            };
+            if let (Ok(()), Err(err)) = (&result, follower_result) {
+                result = Err(err.context(format!("follow file '{}'", path.display())));
+            }
+        }
+        result
+    }
+}
+
+impl Drop for FileFollowers {
+    fn drop(&mut self) {
+        for follower in self.followers.values() {
+            follower.abort();
+        }
+    }
+}
+
+/// Compressed file is read once, appended bytes can not be decompressed
+fn is_compressed(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(|ext| ext.to_str()),
+        Some("gz") | Some("zst")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use std::fs::OpenOptions;
+    use std::io::Write;
+
+    const FOLLOW_DIRECTORY: &str = "resources/test_file_follow";
+
+    fn append(path: &Path, data: &str) {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .unwrap();
+        file.write_all(data.as_bytes()).unwrap();
+    }
+
+    async fn next_line(follower: &mut FileFollower) -> Option<String> {
+        follower
+            .next_split()
+            .await
+            .unwrap()
+            .map(|split| String::from_utf8(split).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_file_follower() {
+        let _ = fs::remove_dir_all(FOLLOW_DIRECTORY);
+        fs::create_dir_all(FOLLOW_DIRECTORY).unwrap();
+        let path = Path::new(FOLLOW_DIRECTORY).join("app.log");
+        let checkpoint_path = Path::new(FOLLOW_DIRECTORY).join("checkpoint.json");
+        let config = FileFollowConfig {
+            interval: Some(Period::Millis(10)),
+            idle_timeout: Some(Period::Millis(50)),
+            checkpoint: None,
+        };
+        let checkpoint = Arc::new(Mutex::new(FileCheckpoint::load(&checkpoint_path).unwrap()));
+        append(&path, "foo1\nfoo2\n");
+        {
+            let mut follower = FileFollower::new(&path, b'\n', &config, Some(checkpoint));
+            assert_eq!(Some("foo1".to_owned()), next_line(&mut follower).await);
+            assert_eq!(Some("foo2".to_owned()), next_line(&mut follower).await);
+            // growth with partial line
+            append(&path, "foo3\nfoo");
+            assert_eq!(Some("foo3".to_owned()), next_line(&mut follower).await);
+            append(&path, "4\n");
+            assert_eq!(Some("foo4".to_owned()), next_line(&mut follower).await);
+            // rotation
+            fs::rename(&path, Path::new(FOLLOW_DIRECTORY).join("app.log.1")).unwrap();
+            append(&path, "bar1\n");
+            assert_eq!(Some("bar1".to_owned()), next_line(&mut follower).await);
+            // truncation
+            fs::write(&path, "").unwrap();
+            append(&path, "baz\n");
+            assert_eq!(Some("baz".to_owned()), next_line(&mut follower).await);
+            // rewritten in place while waiting at EOF and regrown beyond offset
+            let rewrite = async {
+                sleep(Duration::from_millis(20)).await;
+                fs::write(&path, "quux\nquux\n").unwrap();
+            };
+            let (line, _) = tokio::join!(next_line(&mut follower), rewrite);
+            assert_eq!(Some("quux".to_owned()), line);
+            assert_eq!(Some("quux".to_owned()), next_line(&mut follower).await);
+            assert_eq!(None, next_line(&mut follower).await);
+        }
+        // resume from checkpoint
+        append(&path, "qux\n");
+        let checkpoint = FileCheckpoint::load(&checkpoint_path).unwrap();
+        assert_eq!(10, checkpoint.get(&path).unwrap().offset);
+        let checkpoint = Arc::new(Mutex::new(checkpoint));
+        let mut follower = FileFollower::new(&path, b'\n', &config, Some(checkpoint));
+        assert_eq!(Some("qux".to_owned()), next_line(&mut follower).await);
+        assert_eq!(None, next_line(&mut follower).await);
+        fs::remove_dir_all(FOLLOW_DIRECTORY).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_file_followers_error() {
+        let directory = Path::new("resources/test_file_followers");
+        let _ = fs::remove_dir_all(directory);
+        fs::create_dir_all(directory).unwrap();
+        let path = directory.join("app.log");
+        append(&path, "foo\nbar\n");
+        let config = FileFollowConfig {
+            interval: Some(Period::Millis(10)),
+            idle_timeout: Some(Period::Millis(50)),
+            checkpoint: None,
+        };
+        let mut followers = FileFollowers::new(config).unwrap();
+        let (tx, mut rx) = tokio::sync::mpsc::channel::<String>(16);
+        followers
+            .follow(&path, b'\n', tx, |split| match split.as_slice() {
+                b"bar" => anyhow::bail!("bar not allowed"),
+                _ => Ok(String::from_utf8(split)?),
+            })
+            .await
+            .unwrap();
+        assert_eq!("foo", rx.recv().await.unwrap());
+        // error returned to caller instead of only logged
+        let err = followers.join().await.unwrap_err();
+        assert!(format!("{:#}", err).contains("bar not allowed"));
+        fs::remove_dir_all(directory).unwrap();
+    }
+}
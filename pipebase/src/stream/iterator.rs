@@ -63,6 +63,7 @@ where
 mod tests {
 
     use crate::prelude::*;
+    use crate::testing::*;
 
     use std::collections::HashMap;
 
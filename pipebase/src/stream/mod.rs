@@ -1,8 +1,10 @@
 mod file;
+mod follow;
 mod iterator;
 mod runtime;
 
 pub use file::*;
+pub use follow::*;
 pub use iterator::*;
 pub use runtime::*;
 
@@ -18,4 +20,9 @@ where
 {
     async fn stream(&mut self, t: T) -> anyhow::Result<()>;
     fn set_sender(&mut self, sender: Sender<U>);
+
+    /// Called once upstream closed, e.g wait background tasks spawned by stream
+    async fn close(&mut self) -> anyhow::Result<()> {
+        Ok(())
+    }
 }
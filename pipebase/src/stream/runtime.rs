@@ -75,6 +75,17 @@ where
                 }
                 context.inc_total_run();
             }
+            if let Err(err) = streamer.close().await {
+                error!(
+                    name = pipe_name.as_str(),
+                    ty = "streamer",
+                    thread = "stream",
+                    "close error '{:#?}'",
+                    err
+                );
+                send_pipe_error(etx.as_ref(), PipeError::new(pipe_name.clone(), err)).await;
+                context.inc_failure_run();
+            }
             info!(
                 name = pipe_name.as_str(),
                 ty = "streamer",
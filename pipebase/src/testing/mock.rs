@@ -0,0 +1,152 @@
+use crate::common::{ConfigInto, FromConfig, FromPath};
+use crate::export::Export;
+use crate::listen::Listen;
+use async_trait::async_trait;
+use lazy_static::lazy_static;
+use serde::Deserialize;
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tokio::sync::mpsc::Sender;
+
+type Registry = Mutex<HashMap<String, Box<dyn Any + Send>>>;
+
+lazy_static! {
+    static ref MOCK_INPUTS: Registry = Mutex::new(HashMap::new());
+    static ref MOCK_OUTPUTS: Registry = Mutex::new(HashMap::new());
+}
+
+/// Register records emitted by `MockListener` configured with key
+pub fn set_mock_inputs<T, I>(key: &str, records: I)
+where
+    T: Send + 'static,
+    I: IntoIterator<Item = T>,
+{
+    let records: Vec<T> = records.into_iter().collect();
+    let mut inputs = MOCK_INPUTS.lock().unwrap();
+    inputs.insert(key.to_owned(), Box::new(records));
+}
+
+/// Take records exported by `MockExporter` configured with key
+pub fn take_mock_outputs<T>(key: &str) -> Vec<T>
+where
+    T: Send + 'static,
+{
+    let mut outputs = MOCK_OUTPUTS.lock().unwrap();
+    match outputs.remove(key) {
+        Some(records) => *records
+            .downcast::<Vec<T>>()
+            .unwrap_or_else(|_| panic!("mock outputs '{}' type mismatch", key)),
+        None => vec![],
+    }
+}
+
+fn take_mock_inputs<T>(key: &str) -> Vec<T>
+where
+    T: Send + 'static,
+{
+    let mut inputs = MOCK_INPUTS.lock().unwrap();
+    match inputs.remove(key) {
+        Some(records) => *records
+            .downcast::<Vec<T>>()
+            .unwrap_or_else(|_| panic!("mock inputs '{}' type mismatch", key)),
+        None => vec![],
+    }
+}
+
+fn append_mock_output<T>(key: &str, record: T)
+where
+    T: Send + 'static,
+{
+    let mut outputs = MOCK_OUTPUTS.lock().unwrap();
+    let records = outputs
+        .entry(key.to_owned())
+        .or_insert_with(|| Box::new(Vec::<T>::new()));
+    records
+        .downcast_mut::<Vec<T>>()
+        .unwrap_or_else(|| panic!("mock outputs '{}' type mismatch", key))
+        .push(record);
+}
+
+#[derive(Deserialize)]
+pub struct MockListenerConfig {
+    pub key: String,
+}
+
+impl FromPath for MockListenerConfig {}
+
+#[async_trait]
+impl<U> ConfigInto<MockListener<U>> for MockListenerConfig {}
+
+/// Emit records registered with `set_mock_inputs` and exit
+pub struct MockListener<U> {
+    /// Key of registered records
+    key: String,
+    /// Sender to notify downstreams
+    tx: Option<Sender<U>>,
+}
+
+#[async_trait]
+impl<U> FromConfig<MockListenerConfig> for MockListener<U> {
+    async fn from_config(config: MockListenerConfig) -> anyhow::Result<Self> {
+        Ok(MockListener {
+            key: config.key,
+            tx: None,
+        })
+    }
+}
+
+/// # Parameters
+/// * U: output
+#[async_trait]
+impl<U> Listen<U, MockListenerConfig> for MockListener<U>
+where
+    U: Send + Sync + 'static,
+{
+    async fn run(&mut self) -> anyhow::Result<()> {
+        let tx = self.tx.as_ref().unwrap();
+        for record in take_mock_inputs::<U>(&self.key) {
+            tx.send(record).await?;
+        }
+        Ok(())
+    }
+
+    fn set_sender(&mut self, sender: Sender<U>) {
+        self.tx = Some(sender)
+    }
+}
+
+#[derive(Deserialize)]
+pub struct MockExporterConfig {
+    pub key: String,
+}
+
+impl FromPath for MockExporterConfig {}
+
+impl ConfigInto<MockExporter> for MockExporterConfig {}
+
+/// Keep exported records, read with `take_mock_outputs`
+pub struct MockExporter {
+    /// Key of exported records
+    key: String,
+}
+
+#[async_trait]
+impl FromConfig<MockExporterConfig> for MockExporter {
+    async fn from_config(config: MockExporterConfig) -> anyhow::Result<Self> {
+        Ok(MockExporter { key: config.key })
+    }
+}
+
+/// # Parameters
+/// * T: input
+#[async_trait]
+impl<T> Export<T, MockExporterConfig> for MockExporter
+where
+    T: Send + Sync + 'static,
+{
+    async fn export(&mut self, t: T) -> anyhow::Result<()> {
+        append_mock_output(&self.key, t);
+        Ok(())
+    }
+}
@@ -0,0 +1,164 @@
+mod mock;
+mod run;
+
+pub use mock::*;
+pub use run::*;
+
+use crate::common::{Context, State};
+use std::fmt::Debug;
+use tokio::sync::mpsc::{Receiver, Sender};
+
+/// Send records to pipe, sender dropped once all records sent
+pub async fn populate_records<T, U>(tx: Sender<T>, records: U)
+where
+    U: IntoIterator<Item = T>,
+{
+    for record in records {
+        let _ = tx.send(record).await;
+    }
+}
+
+/// Receive records until all senders dropped
+pub async fn receive_records<T>(mut rx: Receiver<T>) -> Vec<T> {
+    let mut records: Vec<T> = vec![];
+    while let Some(record) = rx.recv().await {
+        records.push(record)
+    }
+    records
+}
+
+/// Assert pipe state, total run and failure run
+pub fn assert_context(context: &Context, state: State, total_run: u64, failure_run: u64) {
+    assert_eq!(state, context.get_state(), "unexpected pipe state");
+    assert_eq!(total_run, context.get_total_run(), "unexpected total run");
+    assert_eq!(
+        failure_run,
+        context.get_failure_run(),
+        "unexpected failure run"
+    );
+}
+
+/// Assert records equal regardless of order
+pub fn assert_records_unordered<T>(actual: &[T], expected: &[T])
+where
+    T: Debug + PartialEq,
+{
+    assert_eq!(
+        actual.len(),
+        expected.len(),
+        "records {:?} not match {:?}",
+        actual,
+        expected
+    );
+    let mut matched = vec![false; expected.len()];
+    for record in actual {
+        let position = expected
+            .iter()
+            .enumerate()
+            .position(|(i, e)| !matched[i] && e == record);
+        match position {
+            Some(i) => matched[i] = true,
+            None => panic!("record {:?} not found in {:?}", record, expected),
+        }
+    }
+}
+
+/// Freeze tokio clock of current runtime, require `test-util` feature
+#[cfg(any(test, feature = "test-util"))]
+pub fn pause_time() {
+    tokio::time::pause()
+}
+
+/// Move paused tokio clock forward, timers due fire immediately
+#[cfg(any(test, feature = "test-util"))]
+pub async fn advance_time(duration: std::time::Duration) {
+    tokio::time::advance(duration).await
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::prelude::*;
+    use crate::testing::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_run_pipe_with_records() {
+        let pipe = mapper!("echo");
+        let config = config!(EchoConfig);
+        let outputs = run_pipe_with_records(pipe, config, vec![1, 2, 3])
+            .await
+            .expect("echo failed");
+        assert_records_unordered(outputs.get_records(), &[3, 1, 2]);
+        assert_context(&outputs.get_context(), State::Done, 3, 0);
+    }
+
+    #[tokio::test]
+    async fn test_run_source_and_sink() {
+        set_mock_inputs("test_run_source_and_sink", vec!["a", "b"]);
+        let pipe = listener!("mock_listener");
+        let config = config!(MockListenerConfig, "resources/catalogs/mock_listener.yml");
+        let outputs: PipeOutputs<&str> = run_source(pipe, config).await.expect("listen failed");
+        assert_eq!(&vec!["a", "b"], outputs.get_records());
+        let pipe = exporter!("mock_exporter");
+        let config = config!(MockExporterConfig, "resources/catalogs/mock_exporter.yml");
+        let context = run_sink(pipe, config, outputs.into_records())
+            .await
+            .expect("export failed");
+        assert_context(&context, State::Done, 2, 0);
+        let exported: Vec<&str> = take_mock_outputs("test_run_source_and_sink");
+        assert_eq!(vec!["a", "b"], exported);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_collector_with_paused_time() {
+        let pipe = collector!("bag_collector");
+        let config = config!(
+            InMemoryBagCollectorConfig,
+            "resources/catalogs/bag_collector.yml"
+        );
+        let run = tokio::spawn(run_pipe_with_records(pipe, config, vec![1, 2, 3]));
+        advance_time(Duration::from_secs(60)).await;
+        let outputs = run.await.unwrap().expect("collect failed");
+        let records: Vec<i32> = outputs.into_records().into_iter().flatten().collect();
+        assert_records_unordered(&records, &[1, 2, 3]);
+    }
+
+    #[derive(Bootstrap)]
+    #[pipe(
+        name = "source",
+        ty = "listener",
+        config(
+            ty = "MockListenerConfig",
+            path = "resources/catalogs/mock_app_listener.yml"
+        ),
+        output = "u32"
+    )]
+    #[pipe(
+        name = "echo",
+        ty = "mapper",
+        upstream = "source",
+        config(ty = "EchoConfig"),
+        output = "u32"
+    )]
+    #[pipe(
+        name = "sink",
+        ty = "exporter",
+        upstream = "echo",
+        config(
+            ty = "MockExporterConfig",
+            path = "resources/catalogs/mock_app_exporter.yml"
+        )
+    )]
+    struct App {}
+
+    #[tokio::test]
+    async fn test_run_app() {
+        set_mock_inputs("test_run_app_inputs", vec![1_u32, 2, 3]);
+        run_app(App {}, Duration::from_secs(10))
+            .await
+            .expect("app not exit");
+        let exported: Vec<u32> = take_mock_outputs("test_run_app_outputs");
+        assert_records_unordered(&exported, &[1, 2, 3]);
+    }
+}
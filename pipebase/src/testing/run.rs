@@ -0,0 +1,98 @@
+use super::{populate_records, receive_records};
+use crate::common::{Bootstrap, ConfigInto, Context, FromConfig, Pipe, PipeChannels, Result};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc::channel;
+
+const TEST_CHANNEL_SIZE: usize = 1024;
+
+/// Records sent to downstream and pipe context after run
+pub struct PipeOutputs<U> {
+    records: Vec<U>,
+    context: Arc<Context>,
+}
+
+impl<U> PipeOutputs<U> {
+    pub fn new(records: Vec<U>, context: Arc<Context>) -> Self {
+        PipeOutputs { records, context }
+    }
+
+    pub fn get_records(&self) -> &Vec<U> {
+        &self.records
+    }
+
+    pub fn get_context(&self) -> Arc<Context> {
+        self.context.to_owned()
+    }
+
+    pub fn into_records(self) -> Vec<U> {
+        self.records
+    }
+}
+
+/// Run listener or poller until exit and collect outputs
+pub async fn run_source<P, U, R, C>(pipe: P, config: C) -> Result<PipeOutputs<U>>
+where
+    P: Pipe<(), U, R, C>,
+    R: FromConfig<C>,
+    C: ConfigInto<R>,
+{
+    let context = pipe.get_context();
+    let (tx, rx) = channel::<U>(TEST_CHANNEL_SIZE);
+    let channels = PipeChannels::default().tx(tx);
+    let (result, records) = tokio::join!(pipe.run(config, channels), receive_records(rx));
+    result?;
+    Ok(PipeOutputs::new(records, context))
+}
+
+/// Feed records into mapper, streamer, selector or collector and collect outputs
+pub async fn run_pipe_with_records<P, T, U, R, C, I>(
+    pipe: P,
+    config: C,
+    records: I,
+) -> Result<PipeOutputs<U>>
+where
+    P: Pipe<T, U, R, C>,
+    R: FromConfig<C>,
+    C: ConfigInto<R>,
+    I: IntoIterator<Item = T>,
+{
+    let context = pipe.get_context();
+    let (tx0, rx0) = channel::<T>(TEST_CHANNEL_SIZE);
+    let (tx1, rx1) = channel::<U>(TEST_CHANNEL_SIZE);
+    let channels = PipeChannels::default().rx(rx0).tx(tx1);
+    let (result, _, records) = tokio::join!(
+        pipe.run(config, channels),
+        populate_records(tx0, records),
+        receive_records(rx1)
+    );
+    result?;
+    Ok(PipeOutputs::new(records, context))
+}
+
+/// Feed records into exporter
+pub async fn run_sink<P, T, R, C, I>(pipe: P, config: C, records: I) -> Result<Arc<Context>>
+where
+    P: Pipe<T, (), R, C>,
+    R: FromConfig<C>,
+    C: ConfigInto<R>,
+    I: IntoIterator<Item = T>,
+{
+    let context = pipe.get_context();
+    let (tx, rx) = channel::<T>(TEST_CHANNEL_SIZE);
+    let channels = PipeChannels::default().rx(rx);
+    let (result, _) = tokio::join!(pipe.run(config, channels), populate_records(tx, records));
+    result?;
+    Ok(context)
+}
+
+/// Bootstrap app and wait until all pipes exit, error if timeout elapsed
+pub async fn run_app<A>(mut app: A, timeout: Duration) -> anyhow::Result<()>
+where
+    A: Bootstrap,
+{
+    match tokio::time::timeout(timeout, app.bootstrap()).await {
+        Ok(_) => Ok(()),
+        Err(_) => Err(anyhow::anyhow!("app not exit in {:?}", timeout)),
+    }
+}
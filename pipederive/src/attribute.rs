@@ -26,6 +26,19 @@ pub fn impl_into_attributes(ident: &Ident, data: &Data, generics: &Generics) ->
     expanded
 }
 
+pub fn impl_schema(ident: &Ident, data: &Data, generics: &Generics) -> TokenStream {
+    let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+    let resolved_data = resolve_data(data, &quote! {}, "", &resolve_field_column);
+    let expanded = quote! {
+        impl #impl_generics Schema for #ident #type_generics #where_clause {
+            fn schema() -> std::vec::Vec<Column> {
+                vec![#resolved_data]
+            }
+        }
+    };
+    expanded
+}
+
 fn resolve_field_named(
     field: &Field,
     _input_type_ident: &TokenStream,
@@ -39,6 +52,22 @@ fn resolve_field_named(
     }
 }
 
+fn resolve_field_column(
+    field: &Field,
+    _input_type_ident: &TokenStream,
+    _ident_location: &str,
+) -> TokenStream {
+    let field_name = field.ident.as_ref().unwrap().to_string();
+    let column_name = get_attribute_field_alias(&field.attrs).unwrap_or(field_name);
+    let field_ty = &field.ty;
+    quote_spanned! {field.span() =>
+         Column {
+            name: String::from(#column_name),
+            ty: <#field_ty as ColumnTyped>::column_type(),
+         }
+    }
+}
+
 fn get_attribute_field_alias(attributes: &[Attribute]) -> Option<String> {
     let attribute = match get_any_attribute_by_meta_prefix(ATTRIBUTE_ALIAS, attributes, false, "") {
         Some(attribute) => attribute,
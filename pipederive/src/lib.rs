@@ -172,3 +172,13 @@ pub fn derive_into_attributes(_tokens: proc_macro::TokenStream) -> proc_macro::T
     let expanded = attribute::impl_into_attributes(ident, data, generics);
     proc_macro::TokenStream::from(expanded)
 }
+
+#[proc_macro_derive(Schema, attributes(attribute))]
+pub fn derive_schema(_tokens: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let tokens = &parse_macro_input!(_tokens as DeriveInput);
+    let ident = &tokens.ident;
+    let data = &tokens.data;
+    let generics = &tokens.generics;
+    let expanded = attribute::impl_schema(ident, data, generics);
+    proc_macro::TokenStream::from(expanded)
+}
@@ -27,6 +27,8 @@ impl Debug for Error {
 pub enum ErrorImpl {
     Api(String),
     IO(std::io::Error),
+    Json(serde_json::Error),
+    Toml(toml::de::Error),
     Yaml(serde_yaml::Error),
 }
 
@@ -34,6 +36,8 @@ impl ErrorImpl {
     fn source(&self) -> Option<&(dyn error::Error + 'static)> {
         match self {
             ErrorImpl::IO(err) => Some(err),
+            ErrorImpl::Json(err) => Some(err),
+            ErrorImpl::Toml(err) => Some(err),
             ErrorImpl::Yaml(err) => Some(err),
             _ => None,
         }
@@ -43,6 +47,8 @@ impl ErrorImpl {
         match self {
             ErrorImpl::Api(msg) => Display::fmt(msg, f),
             ErrorImpl::IO(err) => Display::fmt(err, f),
+            ErrorImpl::Json(err) => Display::fmt(err, f),
+            ErrorImpl::Toml(err) => Display::fmt(err, f),
             ErrorImpl::Yaml(err) => Display::fmt(err, f),
         }
     }
@@ -51,6 +57,8 @@ impl ErrorImpl {
         match self {
             ErrorImpl::Api(msg) => f.debug_tuple("Api").field(msg).finish(),
             ErrorImpl::IO(err) => f.debug_tuple("Io").field(err).finish(),
+            ErrorImpl::Json(err) => f.debug_tuple("Json").field(err).finish(),
+            ErrorImpl::Toml(err) => f.debug_tuple("Toml").field(err).finish(),
             ErrorImpl::Yaml(err) => f.debug_tuple("Yaml").field(err).finish(),
         }
     }
@@ -64,6 +72,14 @@ pub(crate) fn io_error(err: std::io::Error) -> Error {
     Error(Box::new(ErrorImpl::IO(err)))
 }
 
+pub(crate) fn json_error(err: serde_json::Error) -> Error {
+    Error(Box::new(ErrorImpl::Json(err)))
+}
+
+pub(crate) fn toml_error(err: toml::de::Error) -> Error {
+    Error(Box::new(ErrorImpl::Toml(err)))
+}
+
 pub(crate) fn yaml_error(err: serde_yaml::Error) -> Error {
     Error(Box::new(ErrorImpl::Yaml(err)))
 }
@@ -5,6 +5,7 @@ use super::constants::{
 use super::context::ContextStore;
 use super::dependency::{CrateVisitor, Dependency, UseCrate};
 use super::error::ErrorHandler;
+use super::format::ManifestFormat;
 use super::meta::{metas_to_literal, Meta, MetaValue};
 use super::pipe::Pipe;
 use super::utils::indent_literal;
@@ -58,14 +59,21 @@ impl App {
     where
         P: AsRef<std::path::Path>,
     {
-        let file = match std::fs::File::open(manifest_path) {
-            Ok(file) => file,
+        let format = ManifestFormat::from_path(&manifest_path);
+        let manifest_buffer = match std::fs::read(manifest_path) {
+            Ok(manifest_buffer) => manifest_buffer,
             Err(err) => return Err(io_error(err)),
         };
-        let mut app = match serde_yaml::from_reader::<std::fs::File, Self>(file) {
-            Ok(app) => app,
-            Err(err) => return Err(yaml_error(err)),
+        let app = match format {
+            ManifestFormat::Yaml => {
+                serde_yaml::from_slice::<Self>(&manifest_buffer).map_err(yaml_error)
+            }
+            ManifestFormat::Json => {
+                serde_json::from_slice::<Self>(&manifest_buffer).map_err(json_error)
+            }
+            ManifestFormat::Toml => toml::from_slice::<Self>(&manifest_buffer).map_err(toml_error),
         };
+        let mut app = app?;
         app.init();
         Ok(app)
     }
@@ -148,6 +156,11 @@ impl App {
         if let Some(error_handler) = self.error.as_ref() {
             error_handler.accept_crate_visitor(&mut visitor)
         }
+        if let Some(objects) = self.objects.as_ref() {
+            for object in objects {
+                object.accept_crate_visitor(&mut visitor)
+            }
+        }
         let mut all_dependencies: Vec<Dependency> = visitor.into_iter().collect();
         all_dependencies.extend(Self::default_dependencies());
         all_dependencies
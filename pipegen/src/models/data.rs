@@ -6,6 +6,7 @@ use crate::models::{Entity, EntityAccept, VisitEntity};
 use serde::Deserialize;
 use std::fmt::Display;
 
+use super::dependency::{default_prost_dependency, Dependency, UseCrate};
 use super::meta::{metas_to_literal, Meta};
 
 #[derive(Clone, Debug, Deserialize)]
@@ -37,6 +38,7 @@ pub enum DataType {
     UtcTime,
     Period,
     Timestamp,
+    Value,
     Object(String),
     ExternalObject(String),
     Booleans,
@@ -59,6 +61,7 @@ pub enum DataType {
     PathBufs,
     Count32s,
     Averagef32s,
+    Values,
     Objects(String),
     ExternalObjects(String),
     Box {
@@ -126,6 +129,7 @@ pub fn data_ty_to_literal(ty: &DataType) -> String {
         DataType::UtcTime => "chrono::DateTime<chrono::Utc>".to_owned(),
         DataType::Period => "pipebase::common::Period".to_owned(),
         DataType::Timestamp => "pipebase::common::Timestamp".to_owned(),
+        DataType::Value => "pipebase::common::Value".to_owned(),
         DataType::Object(object) => object.to_owned(),
         DataType::ExternalObject(object) => object.to_owned(),
         DataType::Booleans => {
@@ -208,6 +212,10 @@ pub fn data_ty_to_literal(ty: &DataType) -> String {
             let ty_lit = data_ty_to_literal(&DataType::Averagef32);
             format!("std::vec::Vec<{}>", ty_lit)
         }
+        DataType::Values => {
+            let ty_lit = data_ty_to_literal(&DataType::Value);
+            format!("std::vec::Vec<{}>", ty_lit)
+        }
         DataType::Objects(object) => {
             let ty_lit = data_ty_to_literal(&DataType::Object(object.to_owned()));
             format!("std::vec::Vec<{}>", ty_lit)
@@ -438,6 +446,16 @@ impl Entity for Object {
 
 impl<V: VisitEntity<Object>> EntityAccept<V> for Object {}
 
+impl UseCrate for Object {
+    fn get_crate(&self) -> Option<Dependency> {
+        let metas = self.metas.as_ref()?;
+        match metas.iter().any(|meta| meta.has_derive_message()) {
+            true => Some(default_prost_dependency()),
+            false => None,
+        }
+    }
+}
+
 impl Display for Object {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         writeln!(f, "Type: {}", self.ty)?;
@@ -162,6 +162,13 @@ pub(crate) fn default_tracing_dependency() -> Dependency {
         .build()
 }
 
+pub(crate) fn default_prost_dependency() -> Dependency {
+    DependencyBuilder::new()
+        .name("prost".to_owned())
+        .version("0.12".to_owned())
+        .build()
+}
+
 pub(crate) fn default_avro_dependency() -> Dependency {
     DependencyBuilder::new()
         .name("pipeavro".to_owned())
@@ -170,6 +177,22 @@ pub(crate) fn default_avro_dependency() -> Dependency {
         .build()
 }
 
+pub(crate) fn default_cbor_dependency() -> Dependency {
+    DependencyBuilder::new()
+        .name("pipecbor".to_owned())
+        .version("0.1.0".to_owned())
+        .modules(vec!["pipecbor::*".to_owned()])
+        .build()
+}
+
+pub(crate) fn default_compress_dependency() -> Dependency {
+    DependencyBuilder::new()
+        .name("pipecompress".to_owned())
+        .version("0.1.0".to_owned())
+        .modules(vec!["pipecompress::*".to_owned()])
+        .build()
+}
+
 pub(crate) fn default_cql_dependency() -> Dependency {
     DependencyBuilder::new()
         .name("pipecql".to_owned())
@@ -210,6 +233,14 @@ pub(crate) fn default_kube_dependency() -> Dependency {
         .build()
 }
 
+pub(crate) fn default_msgpack_dependency() -> Dependency {
+    DependencyBuilder::new()
+        .name("pipemsgpack".to_owned())
+        .version("0.1.0".to_owned())
+        .modules(vec!["pipemsgpack::*".to_owned()])
+        .build()
+}
+
 pub(crate) fn default_mysql_dependency() -> Dependency {
     DependencyBuilder::new()
         .name("pipemysql".to_owned())
@@ -218,6 +249,22 @@ pub(crate) fn default_mysql_dependency() -> Dependency {
         .build()
 }
 
+pub(crate) fn default_parquet_dependency() -> Dependency {
+    DependencyBuilder::new()
+        .name("pipeparquet".to_owned())
+        .version("0.1.0".to_owned())
+        .modules(vec!["pipeparquet::*".to_owned()])
+        .build()
+}
+
+pub(crate) fn default_protobuf_dependency() -> Dependency {
+    DependencyBuilder::new()
+        .name("pipeprotobuf".to_owned())
+        .version("0.1.0".to_owned())
+        .modules(vec!["pipeprotobuf::*".to_owned()])
+        .build()
+}
+
 pub(crate) fn default_psql_dependency() -> Dependency {
     DependencyBuilder::new()
         .name("pipepsql".to_owned())
@@ -282,6 +329,14 @@ pub(crate) fn default_sns_dependency() -> Dependency {
         .build()
 }
 
+pub(crate) fn default_sqlite_dependency() -> Dependency {
+    DependencyBuilder::new()
+        .name("pipesqlite".to_owned())
+        .version("0.1.0".to_owned())
+        .modules(vec!["pipesqlite::*".to_owned()])
+        .build()
+}
+
 pub(crate) fn default_sqs_dependency() -> Dependency {
     DependencyBuilder::new()
         .name("pipesqs".to_owned())
@@ -0,0 +1,28 @@
+use std::path::Path;
+
+/// Manifest file format, resolved by file extension
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ManifestFormat {
+    Yaml,
+    Json,
+    Toml,
+}
+
+impl ManifestFormat {
+    /// `.json` and `.toml` files are parsed accordingly, yaml otherwise
+    pub fn from_path<P>(path: P) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        let extension = path
+            .as_ref()
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(|extension| extension.to_lowercase());
+        match extension.as_deref() {
+            Some("json") => ManifestFormat::Json,
+            Some("toml") => ManifestFormat::Toml,
+            _ => ManifestFormat::Yaml,
+        }
+    }
+}
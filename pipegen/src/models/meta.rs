@@ -49,6 +49,9 @@ pub enum DeriveMeta {
     LeftRight,
     Render,
     IntoAttributes,
+    Schema,
+    // prost message
+    Message,
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -81,6 +84,20 @@ pub struct SerdeMeta {
     with: String,
 }
 
+#[derive(Clone, Debug, Deserialize)]
+pub enum ProstLabel {
+    Optional,
+    Repeated,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct ProstMeta {
+    // protobuf scalar type such as `string`, `int64`, or `message`
+    ty: String,
+    tag: u32,
+    label: Option<ProstLabel>,
+}
+
 #[derive(Clone, Debug, Deserialize)]
 #[serde(untagged)]
 pub enum MetaValue {
@@ -107,6 +124,18 @@ pub enum Meta {
     Convert { convert: ConvertMeta },
     IntoAttributes { attribute: IntoAttributesMeta },
     Serde { serde: SerdeMeta },
+    Prost { prost: ProstMeta },
+}
+
+impl Meta {
+    pub(crate) fn has_derive_message(&self) -> bool {
+        match self {
+            Meta::Derive { derives } => derives
+                .iter()
+                .any(|derive| matches!(derive, DeriveMeta::Message)),
+            _ => false,
+        }
+    }
 }
 
 pub(crate) fn meta_value_str(name: &str, value: &str, raw: bool) -> Meta {
@@ -162,6 +191,8 @@ fn expand_derive(derive: &DeriveMeta) -> Meta {
         DeriveMeta::LeftRight => "LeftRight",
         DeriveMeta::Render => "Render",
         DeriveMeta::IntoAttributes => "IntoAttributes",
+        DeriveMeta::Schema => "Schema",
+        DeriveMeta::Message => "prost::Message",
     };
     new_path(name.to_owned())
 }
@@ -296,6 +327,20 @@ fn expand_serde(serde: &SerdeMeta) -> Meta {
     }
 }
 
+fn expand_prost(prost: &ProstMeta) -> Meta {
+    let mut metas: Vec<Meta> = vec![new_path(prost.ty.to_owned())];
+    match prost.label {
+        Some(ProstLabel::Optional) => metas.push(new_path("optional".to_owned())),
+        Some(ProstLabel::Repeated) => metas.push(new_path("repeated".to_owned())),
+        None => (),
+    };
+    metas.push(meta_value_str("tag", &prost.tag.to_string(), false));
+    Meta::List {
+        name: "prost".to_owned(),
+        metas,
+    }
+}
+
 fn meta_path_to_lit(name: &str, indent: usize, compact: bool) -> String {
     let lit = name.to_owned();
     if compact {
@@ -385,6 +430,10 @@ fn expand_meta_lit(meta: &Meta, indent: usize, compact: bool) -> String {
             let meta = expand_serde(serde);
             return expand_meta_lit(&meta, indent, compact);
         }
+        Meta::Prost { prost } => {
+            let meta = expand_prost(prost);
+            return expand_meta_lit(&meta, indent, compact);
+        }
         Meta::List { name, metas } => (name, metas),
     };
     let nested_metas_lits: Vec<String> = metas
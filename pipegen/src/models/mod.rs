@@ -4,6 +4,7 @@ mod context;
 mod data;
 mod dependency;
 mod error;
+mod format;
 mod function;
 mod meta;
 mod pipe;
@@ -15,6 +16,7 @@ pub(crate) use context::*;
 pub(crate) use data::*;
 pub use dependency::*;
 pub(crate) use error::*;
+pub use format::*;
 pub(crate) use function::*;
 pub(crate) use pipe::*;
 
@@ -8,11 +8,13 @@ use strum::{Display, EnumString};
 use super::data::{data_ty_to_literal, DataType};
 use super::default_mqtt_dependency;
 use super::dependency::{
-    default_amqp_dependency, default_avro_dependency, default_cql_dependency,
-    default_csv_dependency, default_dynamodb_dependency, default_json_dependency,
-    default_kafka_dependency, default_kube_dependency, default_mysql_dependency,
-    default_psql_dependency, default_redis_dependency, default_reqwest_dependency,
-    default_rocksdb_dependency, default_s3_dependency, default_sns_dependency,
+    default_amqp_dependency, default_avro_dependency, default_cbor_dependency,
+    default_compress_dependency, default_cql_dependency, default_csv_dependency,
+    default_dynamodb_dependency, default_json_dependency, default_kafka_dependency,
+    default_kube_dependency, default_msgpack_dependency, default_mysql_dependency,
+    default_parquet_dependency, default_protobuf_dependency, default_psql_dependency,
+    default_redis_dependency, default_reqwest_dependency, default_rocksdb_dependency,
+    default_s3_dependency, default_sns_dependency, default_sqlite_dependency,
     default_sqs_dependency, default_warp_dependency, Dependency, UseCrate,
 };
 use super::meta::{meta_to_literal, meta_value_str, meta_value_usize, Meta};
@@ -53,8 +55,13 @@ impl PipeConfig {
             "InMemoryBagCollectorConfig"
             | "InMemorySetCollectorConfig"
             | "InMemoryWindowCollectorConfig"
+            | "RollingFileWriterConfig"
             | "TextCollectorConfig" => Some(PipeType::Collector),
-            "TimerConfig" | "SqsMessageReceiverConfig" => Some(PipeType::Poller),
+            "TimerConfig"
+            | "SqsMessageReceiverConfig"
+            | "SqliteQueryPollerConfig"
+            | "PsqlQueryPollerConfig"
+            | "MySQLQueryPollerConfig" => Some(PipeType::Poller),
             "LocalFilePathVisitorConfig"
             | "WarpIngestionServerConfig"
             | "KubeLogReaderConfig"
@@ -62,13 +69,18 @@ impl PipeConfig {
             | "RedisSubscriberConfig"
             | "KafkaConsumerConfig"
             | "MqttSubscriberConfig"
-            | "AmqpConsumerConfig" => Some(PipeType::Listener),
+            | "AmqpConsumerConfig"
+            | "RecordReplayConfig" => Some(PipeType::Listener),
             "PrinterConfig"
             | "ReqwestPosterConfig"
             | "CqlWriterConfig"
             | "CqlPreparedWriterConfig"
+            | "CqlSchemaPreparedWriterConfig"
             | "PsqlWriterConfig"
             | "PsqlPreparedWriterConfig"
+            | "PsqlSchemaPreparedWriterConfig"
+            | "PsqlUpsertWriterConfig"
+            | "PsqlCopyWriterConfig"
             | "RedisStringWriterConfig"
             | "RedisStringBatchWriterConfig"
             | "RedisPublisherConfig"
@@ -77,6 +89,10 @@ impl PipeConfig {
             | "S3WriterConfig"
             | "MySQLWriterConfig"
             | "MySQLPreparedWriterConfig"
+            | "MySQLSchemaPreparedWriterConfig"
+            | "MySQLUpsertWriterConfig"
+            | "SqliteWriterConfig"
+            | "SqlitePreparedWriterConfig"
             | "DynamoDBWriterConfig"
             | "SnsPublisherConfig"
             | "MqttPublisherConfig"
@@ -84,32 +100,66 @@ impl PipeConfig {
             "AddAggregatorConfig"
             | "ConversionConfig"
             | "EchoConfig"
+            | "ExprFilterConfig"
+            | "ExprProjectConfig"
+            | "FieldCasterConfig"
+            | "FieldDefaulterConfig"
+            | "FieldDropperConfig"
+            | "FieldFlattenerConfig"
+            | "FieldRenamerConfig"
             | "FieldVisitConfig"
+            | "FileArchiverConfig"
             | "FileReaderConfig"
             | "FileWriterConfig"
             | "FilterMapConfig"
             | "OrderedGroupAddAggregatorConfig"
             | "ProjectionConfig"
+            | "RecordCaptureConfig"
             | "StringSplitterConfig"
             | "TopAggregatorConfig"
             | "UnorderedGroupAddAggregatorConfig"
             | "AvroSerConfig"
             | "AvroDeserConfig"
+            | "AvroRegistryDeserConfig"
+            | "AvroRegistrySerConfig"
             | "JsonSerConfig"
             | "JsonDeserConfig"
+            | "JsonPathExtractorConfig"
             | "JsonRecordSerConfig"
+            | "NdjsonDeserConfig"
+            | "NdjsonSerConfig"
+            | "ParquetDeserConfig"
+            | "ParquetSerConfig"
+            | "ProtobufDeserConfig"
+            | "ProtobufDynamicDeserConfig"
+            | "ProtobufDynamicSerConfig"
+            | "ProtobufSerConfig"
             | "CsvSerConfig"
             | "CsvDeserConfig"
+            | "CborDeserConfig"
+            | "CborRecordSerConfig"
+            | "CborSerConfig"
+            | "MsgpackDeserConfig"
+            | "MsgpackRecordSerConfig"
+            | "MsgpackSerConfig"
+            | "CompressorConfig"
+            | "DecompressorConfig"
             | "RedisUnorderedGroupAddAggregatorConfig"
             | "RocksDBUnorderedGroupAddAggregatorConfig"
             | "ReqwestGetterConfig"
             | "ReqwestQueryConfig" => Some(PipeType::Mapper),
-            "DefaultHashSelectorConfig" | "RandomSelectorConfig" | "RoundRobinSelectorConfig" => {
-                Some(PipeType::Selector)
-            }
-            "FileSplitReaderConfig" | "FileLineReaderConfig" | "IteratorReaderConfig" => {
-                Some(PipeType::Streamer)
-            }
+            "DefaultHashSelectorConfig"
+            | "ExprSelectorConfig"
+            | "RandomSelectorConfig"
+            | "RoundRobinSelectorConfig" => Some(PipeType::Selector),
+            "FileSplitReaderConfig"
+            | "AvroFileReaderConfig"
+            | "CsvFileReaderConfig"
+            | "FileLineReaderConfig"
+            | "IteratorReaderConfig"
+            | "NdjsonFileReaderConfig"
+            | "NdjsonReaderConfig"
+            | "ParquetFileReaderConfig" => Some(PipeType::Streamer),
             _ => None,
         }
     }
@@ -285,18 +335,54 @@ impl UseCrate for Pipe {
     fn get_crate(&self) -> Option<Dependency> {
         let config_ty = self.config.get_config_type().as_str();
         match config_ty {
-            "AvroDeserConfig" | "AvroSerConfig" => Some(default_avro_dependency()),
-            "CqlPreparedWriterConfig" | "CqlWriterConfig" => Some(default_cql_dependency()),
-            "CsvDeserConfig" | "CsvSerConfig" => Some(default_csv_dependency()),
-            "JsonDeserConfig" | "JsonRecordSerConfig" | "JsonSerConfig" => {
-                Some(default_json_dependency())
+            "AvroDeserConfig"
+            | "AvroFileReaderConfig"
+            | "AvroRegistryDeserConfig"
+            | "AvroRegistrySerConfig"
+            | "AvroSerConfig" => Some(default_avro_dependency()),
+            "CborDeserConfig" | "CborRecordSerConfig" | "CborSerConfig" => {
+                Some(default_cbor_dependency())
+            }
+            "CompressorConfig" | "DecompressorConfig" => Some(default_compress_dependency()),
+            "CqlPreparedWriterConfig" | "CqlSchemaPreparedWriterConfig" | "CqlWriterConfig" => {
+                Some(default_cql_dependency())
             }
+            "CsvDeserConfig" | "CsvFileReaderConfig" | "CsvSerConfig" => {
+                Some(default_csv_dependency())
+            }
+            "JsonDeserConfig"
+            | "JsonPathExtractorConfig"
+            | "JsonRecordSerConfig"
+            | "JsonSerConfig"
+            | "NdjsonDeserConfig"
+            | "NdjsonFileReaderConfig"
+            | "NdjsonReaderConfig"
+            | "NdjsonSerConfig" => Some(default_json_dependency()),
             "KafkaConsumerConfig" | "KafkaPartitionedProducerConfig" | "KafkaProducerConfig" => {
                 Some(default_kafka_dependency())
             }
             "KubeEventReaderConfig" | "KubeLogReaderConfig" => Some(default_kube_dependency()),
-            "MySQLPreparedWriterConfig" | "MySQLWriterConfig" => Some(default_mysql_dependency()),
-            "PsqlPreparedWriterConfig" | "PsqlWriterConfig" => Some(default_psql_dependency()),
+            "MsgpackDeserConfig" | "MsgpackRecordSerConfig" | "MsgpackSerConfig" => {
+                Some(default_msgpack_dependency())
+            }
+            "MySQLPreparedWriterConfig"
+            | "MySQLQueryPollerConfig"
+            | "MySQLSchemaPreparedWriterConfig"
+            | "MySQLUpsertWriterConfig"
+            | "MySQLWriterConfig" => Some(default_mysql_dependency()),
+            "ParquetDeserConfig" | "ParquetFileReaderConfig" | "ParquetSerConfig" => {
+                Some(default_parquet_dependency())
+            }
+            "ProtobufDeserConfig"
+            | "ProtobufDynamicDeserConfig"
+            | "ProtobufDynamicSerConfig"
+            | "ProtobufSerConfig" => Some(default_protobuf_dependency()),
+            "PsqlCopyWriterConfig"
+            | "PsqlPreparedWriterConfig"
+            | "PsqlQueryPollerConfig"
+            | "PsqlSchemaPreparedWriterConfig"
+            | "PsqlUpsertWriterConfig"
+            | "PsqlWriterConfig" => Some(default_psql_dependency()),
             "RedisPublisherConfig"
             | "RedisStringBatchWriterConfig"
             | "RedisStringWriterConfig"
@@ -306,6 +392,9 @@ impl UseCrate for Pipe {
                 Some(default_reqwest_dependency())
             }
             "RocksDBUnorderedGroupAddAggregatorConfig" => Some(default_rocksdb_dependency()),
+            "SqlitePreparedWriterConfig" | "SqliteQueryPollerConfig" | "SqliteWriterConfig" => {
+                Some(default_sqlite_dependency())
+            }
             "WarpIngestionServerConfig" => Some(default_warp_dependency()),
             "DynamoDBWriterConfig" => Some(default_dynamodb_dependency()),
             "S3WriterConfig" => Some(default_s3_dependency()),
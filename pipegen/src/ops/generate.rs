@@ -268,6 +268,20 @@ mod tests {
         app.print()
     }
 
+    #[test]
+    fn test_print_timer_tick_pipe_json_toml() {
+        let yaml_manifest_path = Path::new("resources/manifest/print_timer_tick_pipe.yml");
+        let expected = App::from_path(yaml_manifest_path).unwrap().generate();
+        for manifest_path in [
+            "resources/manifest/print_timer_tick_pipe.json",
+            "resources/manifest/print_timer_tick_pipe.toml",
+        ] {
+            let app = App::from_path(Path::new(manifest_path)).unwrap();
+            app.validate().expect("expect valid");
+            assert_eq!(expected, app.generate())
+        }
+    }
+
     #[test]
     fn test_projection_pipe() {
         let manifest_path = Path::new("resources/manifest/projection_pipe.yml");
@@ -283,4 +297,15 @@ mod tests {
         app.validate().expect("expect valid");
         app.print()
     }
+
+    #[test]
+    fn test_protobuf_object() {
+        let manifest_path = Path::new("resources/manifest/protobuf_object.yml");
+        let app = App::from_path(manifest_path).unwrap();
+        app.validate().expect("expect valid");
+        let dependencies = app.get_dependencies();
+        assert!(dependencies.iter().any(|d| d.get_name() == "prost"));
+        assert!(dependencies.iter().any(|d| d.get_name() == "pipeprotobuf"));
+        app.print()
+    }
 }
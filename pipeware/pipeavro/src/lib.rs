@@ -1,2 +1,7 @@
 mod map;
+mod registry;
+mod stream;
+
 pub use map::*;
+pub use registry::*;
+pub use stream::*;
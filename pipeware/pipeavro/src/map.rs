@@ -1,7 +1,10 @@
+use crate::{decode_wire_format, encode_wire_format, SchemaRegistryClient, SchemaRegistryConfig};
 use async_trait::async_trait;
-use avro_rs::{from_value, Codec, Reader, Schema, Writer};
+use avro_rs::{
+    from_avro_datum, from_value, to_avro_datum, to_value, Codec, Reader, Schema, Writer,
+};
 use pipebase::{
-    common::{ConfigInto, FromConfig, FromPath},
+    common::{ConfigFormat, ConfigInto, FromConfig, FromPath},
     map::Map,
 };
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
@@ -72,33 +75,61 @@ where
     }
 }
 
-#[derive(Deserialize)]
-pub struct AvroDeserConfig {}
+/// Parse optional reader schema
+pub(crate) fn parse_reader_schema(
+    reader_schema: Option<&String>,
+) -> anyhow::Result<Option<Schema>> {
+    match reader_schema {
+        Some(reader_schema) => Ok(Some(Schema::parse_str(reader_schema)?)),
+        None => Ok(None),
+    }
+}
+
+#[derive(Default, Deserialize)]
+pub struct AvroDeserConfig {
+    /// Reader schema, data resolved from writer schema into reader schema if present
+    reader_schema: Option<String>,
+}
 
 #[async_trait]
 impl FromPath for AvroDeserConfig {
-    async fn from_path<P>(_path: P) -> anyhow::Result<Self>
+    async fn from_path<P>(path: P) -> anyhow::Result<Self>
     where
         P: AsRef<std::path::Path> + Send,
     {
-        Ok(AvroDeserConfig {})
+        if path.as_ref().as_os_str().is_empty() {
+            return Ok(AvroDeserConfig::default());
+        }
+        let format = ConfigFormat::from_path(&path);
+        let bytes = std::fs::read(path)?;
+        format.deserialize(&bytes)
     }
 }
 
 impl ConfigInto<AvroDeser> for AvroDeserConfig {}
 
-pub struct AvroDeser {}
+pub struct AvroDeser {
+    reader_schema: Option<Schema>,
+}
 
 #[async_trait]
 impl FromConfig<AvroDeserConfig> for AvroDeser {
-    async fn from_config(_config: AvroDeserConfig) -> anyhow::Result<Self> {
-        Ok(AvroDeser {})
+    async fn from_config(config: AvroDeserConfig) -> anyhow::Result<Self> {
+        Ok(AvroDeser {
+            reader_schema: parse_reader_schema(config.reader_schema.as_ref())?,
+        })
     }
 }
 
 impl AvroDeser {
-    fn deserialize<T: DeserializeOwned>(bytes: &[u8]) -> anyhow::Result<Vec<T>> {
-        let reader = Reader::new(bytes)?;
+    fn deserialize<T: DeserializeOwned>(
+        bytes: &[u8],
+        reader_schema: Option<&Schema>,
+    ) -> anyhow::Result<Vec<T>> {
+        let reader = match reader_schema {
+            Some(reader_schema) => Reader::with_schema(reader_schema, bytes)?,
+            None => Reader::new(bytes)?,
+        };
         let mut items: Vec<T> = vec![];
         for value in reader {
             items.push(from_value::<T>(&value?)?);
@@ -113,6 +144,228 @@ where
     T: DeserializeOwned,
 {
     async fn map(&mut self, data: Vec<u8>) -> anyhow::Result<Vec<T>> {
-        Self::deserialize(&data)
+        Self::deserialize(&data, self.reader_schema.as_ref())
+    }
+}
+
+#[derive(Deserialize)]
+pub struct AvroRegistrySerConfig {
+    registry: SchemaRegistryConfig,
+    /// Registry subject, e.g `<topic>-value`
+    subject: String,
+    schema: String,
+    /// Register schema under subject on start, lookup registered schema id otherwise
+    auto_register: Option<bool>,
+}
+
+impl FromPath for AvroRegistrySerConfig {}
+
+impl ConfigInto<AvroRegistrySer> for AvroRegistrySerConfig {}
+
+/// Serialize record into single avro datum in Confluent wire format
+pub struct AvroRegistrySer {
+    schema: Schema,
+    schema_id: u32,
+}
+
+#[async_trait]
+impl FromConfig<AvroRegistrySerConfig> for AvroRegistrySer {
+    async fn from_config(config: AvroRegistrySerConfig) -> anyhow::Result<Self> {
+        let schema = Schema::parse_str(&config.schema)?;
+        let mut client = SchemaRegistryClient::new(config.registry);
+        let schema_id = match config.auto_register.unwrap_or(true) {
+            true => client.register(&config.subject, &config.schema).await?,
+            false => client.lookup(&config.subject, &config.schema).await?,
+        };
+        Ok(AvroRegistrySer { schema, schema_id })
+    }
+}
+
+impl AvroRegistrySer {
+    fn serialize<T: Serialize>(
+        item: T,
+        schema: &Schema,
+        schema_id: u32,
+    ) -> anyhow::Result<Vec<u8>> {
+        let value = to_value(item)?.resolve(schema)?;
+        let datum = to_avro_datum(schema, value)?;
+        Ok(encode_wire_format(schema_id, &datum))
+    }
+}
+
+/// # Parameters
+/// * T: input
+/// * Vec<u8>: output
+#[async_trait]
+impl<T> Map<T, Vec<u8>, AvroRegistrySerConfig> for AvroRegistrySer
+where
+    T: Serialize + Send + 'static,
+{
+    async fn map(&mut self, data: T) -> anyhow::Result<Vec<u8>> {
+        Self::serialize(data, &self.schema, self.schema_id)
+    }
+}
+
+#[derive(Deserialize)]
+pub struct AvroRegistryDeserConfig {
+    registry: SchemaRegistryConfig,
+    /// Reader schema, data resolved from writer schema into reader schema if present
+    reader_schema: Option<String>,
+}
+
+impl FromPath for AvroRegistryDeserConfig {}
+
+impl ConfigInto<AvroRegistryDeser> for AvroRegistryDeserConfig {}
+
+/// Deserialize avro datum in Confluent wire format, writer schema fetched by id and cached
+pub struct AvroRegistryDeser {
+    client: SchemaRegistryClient,
+    reader_schema: Option<Schema>,
+}
+
+#[async_trait]
+impl FromConfig<AvroRegistryDeserConfig> for AvroRegistryDeser {
+    async fn from_config(config: AvroRegistryDeserConfig) -> anyhow::Result<Self> {
+        Ok(AvroRegistryDeser {
+            client: SchemaRegistryClient::new(config.registry),
+            reader_schema: parse_reader_schema(config.reader_schema.as_ref())?,
+        })
+    }
+}
+
+/// # Parameters
+/// * Vec<u8>: input
+/// * T: output
+#[async_trait]
+impl<T> Map<Vec<u8>, T, AvroRegistryDeserConfig> for AvroRegistryDeser
+where
+    T: DeserializeOwned,
+{
+    async fn map(&mut self, data: Vec<u8>) -> anyhow::Result<T> {
+        let (schema_id, mut datum) = decode_wire_format(&data)?;
+        let schema = self.client.get_schema(schema_id).await?;
+        let value = from_avro_datum(schema, &mut datum, self.reader_schema.as_ref())?;
+        Ok(from_value::<T>(&value)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use pipebase::common::FromConfig;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    const SCHEMA: &str = r#"{"type":"record","name":"Record","fields":[{"name":"key","type":"string"},{"name":"value","type":"int"}]}"#;
+
+    #[derive(Debug, Deserialize, PartialEq, Serialize)]
+    struct Record {
+        key: String,
+        value: i32,
+    }
+
+    /// Stub registry with single schema of id 7
+    async fn run_stub_registry(listener: TcpListener) {
+        loop {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buffer = vec![0; 4096];
+            let n = socket.read(&mut buffer).await.unwrap();
+            let request = String::from_utf8_lossy(&buffer[..n]).to_string();
+            let body = match request.split_whitespace().nth(1) {
+                Some("/subjects/records-value/versions") => r#"{"id":7}"#.to_owned(),
+                Some("/schemas/ids/7") => serde_json::json!({ "schema": SCHEMA }).to_string(),
+                _ => r#"{"error_code":40401}"#.to_owned(),
+            };
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+        }
+    }
+
+    fn registry(port: u16) -> SchemaRegistryConfig {
+        SchemaRegistryConfig {
+            url: format!("http://127.0.0.1:{}", port),
+            username: None,
+            password: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_avro_registry_ser_deser() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(run_stub_registry(listener));
+        let mut ser = AvroRegistrySer::from_config(AvroRegistrySerConfig {
+            registry: registry(port),
+            subject: "records-value".to_owned(),
+            schema: SCHEMA.to_owned(),
+            auto_register: None,
+        })
+        .await
+        .unwrap();
+        let record = Record {
+            key: "foo".to_owned(),
+            value: 1,
+        };
+        let bytes = ser.map(record).await.unwrap();
+        assert_eq!(&[0, 0, 0, 0, 7], &bytes[..5]);
+        let mut deser = AvroRegistryDeser::from_config(AvroRegistryDeserConfig {
+            registry: registry(port),
+            reader_schema: None,
+        })
+        .await
+        .unwrap();
+        let record: Record = deser.map(bytes).await.unwrap();
+        assert_eq!(
+            Record {
+                key: "foo".to_owned(),
+                value: 1
+            },
+            record
+        );
+    }
+
+    const EVOLVED_SCHEMA: &str = r#"{"type":"record","name":"Record","fields":[{"name":"key","type":"string"},{"name":"value","type":"long"},{"name":"tag","type":["null","string"],"default":null}]}"#;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct EvolvedRecord {
+        key: String,
+        value: i64,
+        tag: Option<String>,
+    }
+
+    #[tokio::test]
+    async fn test_avro_deser_with_reader_schema() {
+        let mut ser = AvroSer::from_config(AvroSerConfig {
+            compression: Compression::Null,
+            schema: SCHEMA.to_owned(),
+        })
+        .await
+        .unwrap();
+        let bytes = ser
+            .map(vec![Record {
+                key: "foo".to_owned(),
+                value: 1,
+            }])
+            .await
+            .unwrap();
+        let mut deser = AvroDeser::from_config(AvroDeserConfig {
+            reader_schema: Some(EVOLVED_SCHEMA.to_owned()),
+        })
+        .await
+        .unwrap();
+        let records: Vec<EvolvedRecord> = deser.map(bytes).await.unwrap();
+        assert_eq!(
+            vec![EvolvedRecord {
+                key: "foo".to_owned(),
+                value: 1,
+                tag: None
+            }],
+            records
+        );
     }
 }
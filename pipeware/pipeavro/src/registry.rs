@@ -0,0 +1,132 @@
+use avro_rs::Schema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::convert::TryInto;
+
+/// First byte of Confluent wire format
+const MAGIC_BYTE: u8 = 0;
+const WIRE_HEADER_LEN: usize = 5;
+const CONTENT_TYPE: &str = "application/vnd.schemaregistry.v1+json";
+
+#[derive(Clone, Deserialize)]
+pub struct SchemaRegistryConfig {
+    /// Base url, e.g `http://localhost:8081`
+    pub url: String,
+    pub username: Option<String>,
+    pub password: Option<String>,
+}
+
+#[derive(Serialize)]
+struct SchemaRequest<'a> {
+    schema: &'a str,
+}
+
+#[derive(Deserialize)]
+struct SchemaResponse {
+    schema: String,
+}
+
+#[derive(Deserialize)]
+struct SchemaIdResponse {
+    id: u32,
+}
+
+/// Schema registry http client, schemas are cached by id
+pub struct SchemaRegistryClient {
+    client: reqwest::Client,
+    config: SchemaRegistryConfig,
+    schemas: HashMap<u32, Schema>,
+}
+
+impl SchemaRegistryClient {
+    pub fn new(config: SchemaRegistryConfig) -> Self {
+        SchemaRegistryClient {
+            client: reqwest::Client::new(),
+            config,
+            schemas: HashMap::new(),
+        }
+    }
+
+    fn request(&self, method: reqwest::Method, path: &str) -> reqwest::RequestBuilder {
+        let url = format!("{}{}", self.config.url.trim_end_matches('/'), path);
+        let req = self
+            .client
+            .request(method, url)
+            .header(reqwest::header::ACCEPT, CONTENT_TYPE);
+        match self.config.username {
+            Some(ref username) => req.basic_auth(username, self.config.password.as_ref()),
+            None => req,
+        }
+    }
+
+    async fn send<T>(req: reqwest::RequestBuilder) -> anyhow::Result<T>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let resp = req.send().await?;
+        let status = resp.status();
+        if !status.is_success() {
+            let body = resp.text().await.unwrap_or_default();
+            anyhow::bail!("schema registry error, status: {}, body: {}", status, body);
+        }
+        Ok(resp.json::<T>().await?)
+    }
+
+    /// Schema sent as configured, canonical form drops attributes e.g `default` and `doc`
+    fn schema_request(req: reqwest::RequestBuilder, schema: &str) -> reqwest::RequestBuilder {
+        let body = serde_json::to_vec(&SchemaRequest { schema }).expect("serialize schema request");
+        req.header(reqwest::header::CONTENT_TYPE, CONTENT_TYPE)
+            .body(body)
+    }
+
+    /// Get schema by id, fetch from registry at first time
+    pub async fn get_schema(&mut self, id: u32) -> anyhow::Result<&Schema> {
+        if !self.schemas.contains_key(&id) {
+            let req = self.request(reqwest::Method::GET, &format!("/schemas/ids/{}", id));
+            let resp: SchemaResponse = Self::send(req).await?;
+            let schema = Schema::parse_str(&resp.schema)?;
+            self.schemas.insert(id, schema);
+        }
+        Ok(self.schemas.get(&id).unwrap())
+    }
+
+    /// Register schema json under subject and return schema id
+    pub async fn register(&mut self, subject: &str, schema: &str) -> anyhow::Result<u32> {
+        let parsed = Schema::parse_str(schema)?;
+        let req = self.request(
+            reqwest::Method::POST,
+            &format!("/subjects/{}/versions", subject),
+        );
+        let resp: SchemaIdResponse = Self::send(Self::schema_request(req, schema)).await?;
+        self.schemas.insert(resp.id, parsed);
+        Ok(resp.id)
+    }
+
+    /// Lookup id of schema json already registered under subject
+    pub async fn lookup(&mut self, subject: &str, schema: &str) -> anyhow::Result<u32> {
+        let parsed = Schema::parse_str(schema)?;
+        let req = self.request(reqwest::Method::POST, &format!("/subjects/{}", subject));
+        let resp: SchemaIdResponse = Self::send(Self::schema_request(req, schema)).await?;
+        self.schemas.insert(resp.id, parsed);
+        Ok(resp.id)
+    }
+}
+
+/// Prefix magic byte and big endian schema id
+pub fn encode_wire_format(schema_id: u32, datum: &[u8]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(WIRE_HEADER_LEN + datum.len());
+    bytes.push(MAGIC_BYTE);
+    bytes.extend_from_slice(&schema_id.to_be_bytes());
+    bytes.extend_from_slice(datum);
+    bytes
+}
+
+/// Split schema id and datum
+pub fn decode_wire_format(bytes: &[u8]) -> anyhow::Result<(u32, &[u8])> {
+    anyhow::ensure!(
+        bytes.len() >= WIRE_HEADER_LEN && bytes[0] == MAGIC_BYTE,
+        "invalid confluent wire format, expect magic byte and schema id"
+    );
+    let schema_id = u32::from_be_bytes(bytes[1..WIRE_HEADER_LEN].try_into()?);
+    Ok((schema_id, &bytes[WIRE_HEADER_LEN..]))
+}
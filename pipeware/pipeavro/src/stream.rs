@@ -0,0 +1,146 @@
+use crate::parse_reader_schema;
+use async_trait::async_trait;
+use avro_rs::{from_value, Reader, Schema};
+use pipebase::{
+    common::{ConfigFormat, ConfigInto, FromConfig, FromPath},
+    stream::{FileStreamReader, Stream},
+};
+use serde::{de::DeserializeOwned, Deserialize};
+use std::path::Path;
+use tokio::sync::mpsc::Sender;
+
+#[derive(Default, Deserialize)]
+pub struct AvroFileReaderConfig {
+    /// Reader schema, records resolved from writer schema into reader schema if present
+    reader_schema: Option<String>,
+}
+
+#[async_trait]
+impl FromPath for AvroFileReaderConfig {
+    async fn from_path<P>(path: P) -> anyhow::Result<Self>
+    where
+        P: AsRef<Path> + Send,
+    {
+        if path.as_ref().as_os_str().is_empty() {
+            return Ok(AvroFileReaderConfig::default());
+        }
+        let format = ConfigFormat::from_path(&path);
+        let bytes = std::fs::read(path)?;
+        format.deserialize(&bytes)
+    }
+}
+
+impl<U> ConfigInto<AvroFileReader<U>> for AvroFileReaderConfig {}
+
+/// Stream records of avro object container file block by block
+pub struct AvroFileReader<U> {
+    reader_schema: Option<Schema>,
+    tx: Option<Sender<U>>,
+}
+
+#[async_trait]
+impl<U> FromConfig<AvroFileReaderConfig> for AvroFileReader<U> {
+    async fn from_config(config: AvroFileReaderConfig) -> anyhow::Result<Self> {
+        Ok(AvroFileReader {
+            reader_schema: parse_reader_schema(config.reader_schema.as_ref())?,
+            tx: None,
+        })
+    }
+}
+
+#[async_trait]
+impl<U> FileStreamReader for AvroFileReader<U>
+where
+    U: DeserializeOwned + Send + Sync + 'static,
+{
+    async fn stream_file<P>(&self, path: P) -> anyhow::Result<()>
+    where
+        P: AsRef<Path> + Send,
+    {
+        let file = Self::new_reader(path)?;
+        let reader = match self.reader_schema {
+            Some(ref reader_schema) => Reader::with_schema(reader_schema, file)?,
+            None => Reader::new(file)?,
+        };
+        let tx = self.tx.as_ref().unwrap();
+        for value in reader {
+            let u: U = from_value(&value?)?;
+            tx.send(u).await?;
+        }
+        Ok(())
+    }
+}
+
+/// # Parameters
+/// * P: input, file path
+/// * U: output, record
+#[async_trait]
+impl<P, U> Stream<P, U, AvroFileReaderConfig> for AvroFileReader<U>
+where
+    P: AsRef<Path> + Send + 'static,
+    U: DeserializeOwned + Send + Sync + 'static,
+{
+    async fn stream(&mut self, path: P) -> anyhow::Result<()> {
+        self.stream_file(path).await
+    }
+
+    fn set_sender(&mut self, sender: Sender<U>) {
+        self.tx = Some(sender)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::*;
+    use avro_rs::{Schema, Writer};
+    use pipebase::prelude::*;
+    use pipebase::testing::*;
+    use serde::{Deserialize, Serialize};
+    use std::fs::{self, File};
+    use std::path::PathBuf;
+
+    const SCHEMA: &str = r#"{"type":"record","name":"Record","fields":[{"name":"key","type":"string"},{"name":"value","type":"int"}]}"#;
+
+    #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+    struct Record {
+        key: String,
+        value: i32,
+    }
+
+    fn records() -> Vec<Record> {
+        (0..3)
+            .map(|i| Record {
+                key: format!("key{}", i),
+                value: i,
+            })
+            .collect()
+    }
+
+    fn write_avro_file(path: &str, records: &[Record]) {
+        let schema = Schema::parse_str(SCHEMA).unwrap();
+        let mut writer = Writer::new(&schema, File::create(path).unwrap());
+        // flush per record so file contains multiple blocks
+        for record in records {
+            writer.append_ser(record).unwrap();
+            writer.flush().unwrap();
+        }
+        writer.into_inner().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_avro_file_reader() {
+        let directory = "resources/avro_stream";
+        fs::create_dir_all(directory).unwrap();
+        let path = format!("{}/records.avro", directory);
+        write_avro_file(&path, &records());
+        let config = config!(AvroFileReaderConfig);
+        let pipe = streamer!("avro_file_reader");
+        let outputs: PipeOutputs<Record> =
+            run_pipe_with_records(pipe, config, vec![PathBuf::from(&path)])
+                .await
+                .unwrap();
+        assert_eq!(&records(), outputs.get_records());
+        fs::remove_dir_all(directory).unwrap();
+    }
+}
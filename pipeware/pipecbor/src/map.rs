@@ -0,0 +1,195 @@
+use async_trait::async_trait;
+use pipebase::{
+    common::{ConfigInto, FromConfig, FromPath, GroupAs, Pair},
+    map::Map,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::path::Path;
+
+#[derive(Deserialize)]
+pub struct CborSerConfig {}
+
+#[async_trait]
+impl FromPath for CborSerConfig {
+    async fn from_path<P>(_path: P) -> anyhow::Result<Self>
+    where
+        P: AsRef<Path> + Send,
+    {
+        Ok(CborSerConfig {})
+    }
+}
+
+impl ConfigInto<CborSer> for CborSerConfig {}
+
+pub struct CborSer {}
+
+#[async_trait]
+impl FromConfig<CborSerConfig> for CborSer {
+    async fn from_config(_config: CborSerConfig) -> anyhow::Result<Self> {
+        Ok(CborSer {})
+    }
+}
+
+impl CborSer {
+    fn serialize<T: Serialize>(t: &T) -> anyhow::Result<Vec<u8>> {
+        let mut bytes: Vec<u8> = Vec::new();
+        ciborium::ser::into_writer(t, &mut bytes)?;
+        Ok(bytes)
+    }
+}
+
+/// # Parameters
+/// * T: input
+/// * Vec<u8>: output
+#[async_trait]
+impl<T> Map<T, Vec<u8>, CborSerConfig> for CborSer
+where
+    T: Serialize + Send + Sync + 'static,
+{
+    async fn map(&mut self, t: T) -> anyhow::Result<Vec<u8>> {
+        CborSer::serialize(&t)
+    }
+}
+
+#[derive(Deserialize)]
+pub struct CborRecordSerConfig {}
+
+#[async_trait]
+impl FromPath for CborRecordSerConfig {
+    async fn from_path<P>(_path: P) -> anyhow::Result<Self>
+    where
+        P: AsRef<Path> + Send,
+    {
+        Ok(CborRecordSerConfig {})
+    }
+}
+
+impl ConfigInto<CborRecordSer> for CborRecordSerConfig {}
+
+pub struct CborRecordSer {}
+
+#[async_trait]
+impl FromConfig<CborRecordSerConfig> for CborRecordSer {
+    async fn from_config(_config: CborRecordSerConfig) -> anyhow::Result<Self> {
+        Ok(CborRecordSer {})
+    }
+}
+
+impl CborRecordSer {
+    fn serialize<K, R>(record: &R) -> anyhow::Result<Pair<K, Vec<u8>>>
+    where
+        R: GroupAs<K> + Serialize,
+    {
+        let bytes = CborSer::serialize(record)?;
+        let key = record.group();
+        Ok(Pair::new(key, bytes))
+    }
+}
+
+/// # Parameters
+/// * R: input, record
+/// * Pair<K, Vec<u8>>: output, record key and bytes
+#[async_trait]
+impl<K, R> Map<R, Pair<K, Vec<u8>>, CborRecordSerConfig> for CborRecordSer
+where
+    R: GroupAs<K> + Serialize + Send + 'static,
+{
+    async fn map(&mut self, data: R) -> anyhow::Result<Pair<K, Vec<u8>>> {
+        Self::serialize(&data)
+    }
+}
+
+#[derive(Deserialize)]
+pub struct CborDeserConfig {}
+
+#[async_trait]
+impl FromPath for CborDeserConfig {
+    async fn from_path<P>(_path: P) -> anyhow::Result<Self>
+    where
+        P: AsRef<Path> + Send,
+    {
+        Ok(CborDeserConfig {})
+    }
+}
+
+impl ConfigInto<CborDeser> for CborDeserConfig {}
+
+pub struct CborDeser {}
+
+#[async_trait]
+impl FromConfig<CborDeserConfig> for CborDeser {
+    async fn from_config(_config: CborDeserConfig) -> anyhow::Result<Self> {
+        Ok(CborDeser {})
+    }
+}
+
+impl CborDeser {
+    fn deserialize<T: DeserializeOwned>(bytes: &[u8]) -> anyhow::Result<T> {
+        let t: T = ciborium::de::from_reader(bytes)?;
+        Ok(t)
+    }
+}
+
+/// # Parameters
+/// * Vec<u8>: input
+/// * T: output
+#[async_trait]
+impl<T> Map<Vec<u8>, T, CborDeserConfig> for CborDeser
+where
+    T: DeserializeOwned + Sync,
+{
+    async fn map(&mut self, bytes: Vec<u8>) -> anyhow::Result<T> {
+        CborDeser::deserialize(bytes.as_slice())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::*;
+    use pipebase::common::{FromConfig, GroupAs, LeftRight, Pair, Value, ValuePath};
+    use pipebase::map::Map;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+    struct Reading {
+        device: String,
+        value: f64,
+    }
+
+    impl GroupAs<String> for Reading {
+        fn group(&self) -> String {
+            self.device.to_owned()
+        }
+    }
+
+    fn reading() -> Reading {
+        Reading {
+            device: "sensor-1".to_owned(),
+            value: 21.5,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cbor_ser_deser() {
+        let mut ser = CborSer::from_config(CborSerConfig {}).await.unwrap();
+        let bytes = ser.map(reading()).await.unwrap();
+        let mut deser = CborDeser::from_config(CborDeserConfig {}).await.unwrap();
+        let actual: Reading = deser.map(bytes.to_owned()).await.unwrap();
+        assert_eq!(reading(), actual);
+        let value: Value = deser.map(bytes).await.unwrap();
+        let device: ValuePath = "device".parse().unwrap();
+        assert_eq!(Some(&Value::from("sensor-1")), value.get_path(&device))
+    }
+
+    #[tokio::test]
+    async fn test_cbor_record_ser() {
+        let mut ser = CborRecordSer::from_config(CborRecordSerConfig {})
+            .await
+            .unwrap();
+        let pair: Pair<String, Vec<u8>> = ser.map(reading()).await.unwrap();
+        assert_eq!("sensor-1", pair.left().as_str());
+        let actual: Reading = ciborium::de::from_reader(pair.right().as_slice()).unwrap();
+        assert_eq!(reading(), actual)
+    }
+}
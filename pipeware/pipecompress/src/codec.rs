@@ -0,0 +1,101 @@
+use flate2::read::{GzEncoder, MultiGzDecoder};
+use serde::Deserialize;
+use std::io::{Read, Write};
+
+#[derive(Clone, Deserialize)]
+pub enum Codec {
+    Gzip,
+    Zstd,
+    /// Snappy frame format
+    Snappy,
+    /// Lz4 frame format
+    Lz4,
+}
+
+const DEFAULT_GZIP_LEVEL: u32 = 6;
+const DEFAULT_ZSTD_LEVEL: u32 = 3;
+
+impl Codec {
+    pub fn compress(&self, bytes: &[u8], level: Option<u32>) -> anyhow::Result<Vec<u8>> {
+        let mut buffer: Vec<u8> = Vec::new();
+        match self {
+            Codec::Gzip => {
+                let level = flate2::Compression::new(level.unwrap_or(DEFAULT_GZIP_LEVEL));
+                GzEncoder::new(bytes, level).read_to_end(&mut buffer)?;
+            }
+            Codec::Zstd => {
+                let level = level.unwrap_or(DEFAULT_ZSTD_LEVEL) as i32;
+                buffer = zstd::stream::encode_all(bytes, level)?;
+            }
+            Codec::Snappy => {
+                let mut wtr = snap::write::FrameEncoder::new(&mut buffer);
+                wtr.write_all(bytes)?;
+                wtr.flush()?;
+            }
+            Codec::Lz4 => {
+                let mut wtr = lz4_flex::frame::FrameEncoder::new(&mut buffer);
+                wtr.write_all(bytes)?;
+                wtr.finish()?;
+            }
+        };
+        Ok(buffer)
+    }
+
+    pub fn decompress(&self, bytes: &[u8]) -> anyhow::Result<Vec<u8>> {
+        let mut buffer: Vec<u8> = Vec::new();
+        match self {
+            Codec::Gzip => {
+                MultiGzDecoder::new(bytes).read_to_end(&mut buffer)?;
+            }
+            Codec::Zstd => {
+                buffer = zstd::stream::decode_all(bytes)?;
+            }
+            Codec::Snappy => {
+                snap::read::FrameDecoder::new(bytes).read_to_end(&mut buffer)?;
+            }
+            Codec::Lz4 => {
+                lz4_flex::frame::FrameDecoder::new(bytes).read_to_end(&mut buffer)?;
+            }
+        };
+        Ok(buffer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    const CODECS: [Codec; 4] = [Codec::Gzip, Codec::Zstd, Codec::Snappy, Codec::Lz4];
+
+    fn bytes() -> Vec<u8> {
+        "pipebase ".repeat(1024).into_bytes()
+    }
+
+    #[test]
+    fn test_codec_round_trip() {
+        let bytes = bytes();
+        for codec in CODECS.iter() {
+            let compressed = codec.compress(&bytes, None).unwrap();
+            assert!(compressed.len() < bytes.len());
+            assert_eq!(bytes, codec.decompress(&compressed).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_codec_round_trip_with_level() {
+        let bytes = bytes();
+        for (codec, level) in [(Codec::Gzip, 9), (Codec::Zstd, 19)].iter() {
+            let compressed = codec.compress(&bytes, Some(*level)).unwrap();
+            assert_eq!(bytes, codec.decompress(&compressed).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_codec_decompress_corrupt() {
+        let corrupt = b"not compressed".to_vec();
+        for codec in CODECS.iter() {
+            assert!(codec.decompress(&corrupt).is_err());
+        }
+    }
+}
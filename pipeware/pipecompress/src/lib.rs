@@ -0,0 +1,5 @@
+mod codec;
+mod map;
+
+pub use codec::*;
+pub use map::*;
@@ -0,0 +1,100 @@
+use crate::Codec;
+use async_trait::async_trait;
+use pipebase::{
+    common::{ConfigInto, FromConfig, FromPath},
+    map::Map,
+};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct CompressorConfig {
+    pub codec: Codec,
+    /// Compression level of gzip (0-9) or zstd (1-21)
+    pub level: Option<u32>,
+}
+
+impl FromPath for CompressorConfig {}
+
+impl ConfigInto<Compressor> for CompressorConfig {}
+
+/// Compress bytes
+pub struct Compressor {
+    codec: Codec,
+    level: Option<u32>,
+}
+
+#[async_trait]
+impl FromConfig<CompressorConfig> for Compressor {
+    async fn from_config(config: CompressorConfig) -> anyhow::Result<Self> {
+        Ok(Compressor {
+            codec: config.codec,
+            level: config.level,
+        })
+    }
+}
+
+/// # Parameters
+/// * Vec<u8>: input
+/// * Vec<u8>: output
+#[async_trait]
+impl Map<Vec<u8>, Vec<u8>, CompressorConfig> for Compressor {
+    async fn map(&mut self, bytes: Vec<u8>) -> anyhow::Result<Vec<u8>> {
+        self.codec.compress(bytes.as_slice(), self.level)
+    }
+}
+
+#[derive(Deserialize)]
+pub struct DecompressorConfig {
+    pub codec: Codec,
+}
+
+impl FromPath for DecompressorConfig {}
+
+impl ConfigInto<Decompressor> for DecompressorConfig {}
+
+/// Decompress bytes
+pub struct Decompressor {
+    codec: Codec,
+}
+
+#[async_trait]
+impl FromConfig<DecompressorConfig> for Decompressor {
+    async fn from_config(config: DecompressorConfig) -> anyhow::Result<Self> {
+        Ok(Decompressor {
+            codec: config.codec,
+        })
+    }
+}
+
+/// # Parameters
+/// * Vec<u8>: input
+/// * Vec<u8>: output
+#[async_trait]
+impl Map<Vec<u8>, Vec<u8>, DecompressorConfig> for Decompressor {
+    async fn map(&mut self, bytes: Vec<u8>) -> anyhow::Result<Vec<u8>> {
+        self.codec.decompress(bytes.as_slice())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::*;
+    use pipebase::{common::FromConfig, map::Map};
+
+    #[tokio::test]
+    async fn test_compress_decompress() {
+        let bytes = b"pipebase pipebase pipebase".to_vec();
+        let mut compressor =
+            Compressor::from_config(serde_yaml::from_str("codec: Zstd\nlevel: 5\n").unwrap())
+                .await
+                .unwrap();
+        let mut decompressor =
+            Decompressor::from_config(serde_yaml::from_str("codec: Zstd\n").unwrap())
+                .await
+                .unwrap();
+        let compressed = compressor.map(bytes.to_owned()).await.unwrap();
+        assert_eq!(bytes, decompressor.map(compressed).await.unwrap());
+        assert!(decompressor.map(bytes).await.is_err());
+    }
+}
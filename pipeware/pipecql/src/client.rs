@@ -1,44 +1,152 @@
+use chrono::{Duration, TimeZone, Utc};
 use openssl::ssl::{SslContext, SslContextBuilder, SslMethod, SslVerifyMode};
-use pipebase::common::{IntoAttributes, Render, Value};
+use pipebase::common::{Backoff, BackoffConfig, Column, ColumnType, IntoAttributes, Render, Value};
 use scylla::{
-    frame::value::{SerializedValues, Timestamp},
+    batch::{Batch, BatchType},
+    frame::value::{SerializedValues, Timestamp, Value as ScyllaValue, ValueTooBig},
     prepared_statement::PreparedStatement,
+    retry_policy::{DefaultRetryPolicy, FallthroughRetryPolicy, RetryPolicy},
     statement::Consistency,
     transport::session::Session,
-    SessionBuilder,
+    IntoTypedRows, SessionBuilder,
 };
 use serde::Deserialize;
-use std::{fs, path::PathBuf};
+use std::{collections::HashMap, convert::TryFrom, fs, path::PathBuf};
+
+const TABLE_COLUMNS_QUERY: &str =
+    "SELECT column_name FROM system_schema.columns WHERE keyspace_name = ? AND table_name = ?";
 
 #[derive(Deserialize)]
 pub struct SslConfig {
     root_cert_path: String,
 }
 
+#[derive(Clone, Copy, Deserialize)]
+pub enum CqlConsistency {
+    Any,
+    One,
+    Two,
+    Three,
+    Quorum,
+    All,
+    LocalQuorum,
+    EachQuorum,
+    LocalOne,
+}
+
+impl From<CqlConsistency> for Consistency {
+    fn from(consistency: CqlConsistency) -> Self {
+        match consistency {
+            CqlConsistency::Any => Consistency::Any,
+            CqlConsistency::One => Consistency::One,
+            CqlConsistency::Two => Consistency::Two,
+            CqlConsistency::Three => Consistency::Three,
+            CqlConsistency::Quorum => Consistency::Quorum,
+            CqlConsistency::All => Consistency::All,
+            CqlConsistency::LocalQuorum => Consistency::LocalQuorum,
+            CqlConsistency::EachQuorum => Consistency::EachQuorum,
+            CqlConsistency::LocalOne => Consistency::LocalOne,
+        }
+    }
+}
+
+/// Consistency of paxos phase in lightweight transaction
+#[derive(Clone, Copy, Deserialize)]
+pub enum CqlSerialConsistency {
+    Serial,
+    LocalSerial,
+}
+
+impl From<CqlSerialConsistency> for Consistency {
+    fn from(consistency: CqlSerialConsistency) -> Self {
+        match consistency {
+            CqlSerialConsistency::Serial => Consistency::Serial,
+            CqlSerialConsistency::LocalSerial => Consistency::LocalSerial,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Deserialize)]
+pub enum CqlRetryPolicy {
+    /// Retry on timeout, unavailable or overloaded node, write retried if idempotent
+    Default,
+    /// Never retry
+    Fallthrough,
+}
+
+impl CqlRetryPolicy {
+    fn new_policy(&self) -> Box<dyn RetryPolicy + Send + Sync> {
+        match self {
+            CqlRetryPolicy::Default => Box::new(DefaultRetryPolicy::new()),
+            CqlRetryPolicy::Fallthrough => Box::new(FallthroughRetryPolicy::new()),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Deserialize)]
+pub enum CqlBatchType {
+    Logged,
+    Unlogged,
+    Counter,
+}
+
+impl From<CqlBatchType> for BatchType {
+    fn from(ty: CqlBatchType) -> Self {
+        match ty {
+            CqlBatchType::Logged => BatchType::Logged,
+            CqlBatchType::Unlogged => BatchType::Unlogged,
+            CqlBatchType::Counter => BatchType::Counter,
+        }
+    }
+}
+
 #[derive(Deserialize)]
 pub struct CqlClientConfig {
     hostname: String,
     ssl: Option<SslConfig>,
+    /// Backoff of initial connect, session reconnects broken connection itself
+    backoff: Option<BackoffConfig>,
+    /// Consistency of statements, `One` by default
+    consistency: Option<CqlConsistency>,
+    serial_consistency: Option<CqlSerialConsistency>,
+    /// Retry policy of session, `Default` by default
+    retry_policy: Option<CqlRetryPolicy>,
+    /// Statements safe to retry, false by default
+    idempotent: Option<bool>,
 }
 
 pub struct CqlClient {
     session: Session,
+    consistency: Consistency,
+    serial_consistency: Option<Consistency>,
+    idempotent: bool,
 }
 
 impl CqlClient {
     pub async fn new(config: CqlClientConfig) -> anyhow::Result<Self> {
-        let hostname = config.hostname;
-        let ssl = config.ssl;
-        let ssl_context = match ssl {
+        let ssl_context = match config.ssl {
             Some(ssl) => Some(Self::new_ssl_context(ssl)?),
             None => None,
         };
+        let retry_policy = config.retry_policy.unwrap_or(CqlRetryPolicy::Default);
+        let backoff: Backoff = config.backoff.unwrap_or_default().into();
+        let hostname = config.hostname;
+        let session = backoff
+            .retry(|| async {
+                let session = SessionBuilder::new()
+                    .known_node(&hostname)
+                    .ssl_context(ssl_context.to_owned())
+                    .retry_policy(retry_policy.new_policy())
+                    .build()
+                    .await?;
+                Ok(session)
+            })
+            .await?;
         Ok(CqlClient {
-            session: SessionBuilder::new()
-                .known_node(hostname)
-                .ssl_context(ssl_context)
-                .build()
-                .await?,
+            session,
+            consistency: config.consistency.unwrap_or(CqlConsistency::One).into(),
+            serial_consistency: config.serial_consistency.map(Consistency::from),
+            idempotent: config.idempotent.unwrap_or_default(),
         })
     }
 
@@ -52,8 +160,10 @@ impl CqlClient {
     }
 
     pub async fn execute<R: Render>(&self, r: R) -> anyhow::Result<()> {
-        let prepared = self.session.prepare(r.render()).await?;
-        self.session.execute(&prepared, ()).await?;
+        let session = &self.session;
+        let mut prepared = session.prepare(r.render()).await?;
+        self.configure_statement(&mut prepared);
+        session.execute(&prepared, ()).await?;
         Ok(())
     }
 
@@ -65,11 +175,108 @@ impl CqlClient {
     where
         A: IntoAttributes,
     {
-        let mut statement: PreparedStatement = self.session.prepare(statement).await?;
-        statement.set_consistency(Consistency::One);
+        let session = &self.session;
+        let mut statement: PreparedStatement = session.prepare(statement).await?;
+        self.configure_statement(&mut statement);
         for item in items {
             let values = Self::serialize_values(item)?;
-            self.session.execute(&statement, values).await?;
+            session.execute(&statement, values).await?;
+        }
+        Ok(())
+    }
+
+    /// Execute prepared statement per item in `BATCH` of at most `size` statements
+    pub async fn prepare_batch_execute<A>(
+        &self,
+        statement: String,
+        items: Vec<A>,
+        ty: CqlBatchType,
+        size: usize,
+    ) -> anyhow::Result<()>
+    where
+        A: IntoAttributes,
+    {
+        let session = &self.session;
+        let statement: PreparedStatement = session.prepare(statement).await?;
+        let values = items
+            .into_iter()
+            .map(Self::serialize_values)
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        for values in values.chunks(size.max(1)) {
+            let mut batch = Batch::new(ty.into());
+            for _ in values {
+                batch.append_statement(statement.to_owned());
+            }
+            self.configure_batch(&mut batch);
+            session.batch(&batch, values).await?;
+        }
+        Ok(())
+    }
+
+    fn configure_statement(&self, statement: &mut PreparedStatement) {
+        statement.set_consistency(self.consistency);
+        statement.set_serial_consistency(self.serial_consistency);
+        statement.set_is_idempotent(self.idempotent);
+    }
+
+    fn configure_batch(&self, batch: &mut Batch) {
+        batch.set_consistency(self.consistency);
+        batch.set_serial_consistency(self.serial_consistency);
+        batch.set_is_idempotent(self.idempotent);
+    }
+
+    /// Validate statement against record columns bound in order, fail at startup instead
+    /// of first write if statement can not be prepared or params mismatch columns
+    pub async fn validate_params(&self, statement: &str, columns: &[Column]) -> anyhow::Result<()> {
+        self.session.prepare(statement).await?;
+        let params = bind_markers(statement);
+        if params != columns.len() {
+            anyhow::bail!(
+                "statement expects {} params but record has {} columns",
+                params,
+                columns.len()
+            )
+        }
+        Ok(())
+    }
+
+    /// Create table if not exists and add missing columns, table qualified by keyspace
+    pub async fn create_table(
+        &self,
+        table: &str,
+        columns: &[Column],
+        keys: &[String],
+    ) -> anyhow::Result<()> {
+        let (keyspace, name) = match table.split_once('.') {
+            Some(names) => names,
+            None => anyhow::bail!("keyspace of table '{}' not specified", table),
+        };
+        if keys.is_empty() {
+            anyhow::bail!("primary key of table '{}' not configured", table)
+        }
+        let session = &self.session;
+        session
+            .query(create_table_statement(table, columns, keys), ())
+            .await?;
+        // unquoted names are case insensitive and stored in lower case
+        let existing = session
+            .query(
+                TABLE_COLUMNS_QUERY,
+                (keyspace.to_lowercase(), name.to_lowercase()),
+            )
+            .await?
+            .rows
+            .unwrap_or_default()
+            .into_typed::<(String,)>()
+            .map(|row| row.map(|(name,)| name))
+            .collect::<Result<Vec<_>, _>>()?;
+        for column in columns {
+            if existing.contains(&column.name.to_lowercase()) {
+                continue;
+            }
+            session
+                .query(add_column_statement(table, column), ())
+                .await?;
         }
         Ok(())
     }
@@ -87,21 +294,254 @@ impl CqlClient {
     }
 
     fn serialize_value(value: &Value, values: &mut SerializedValues) -> anyhow::Result<()> {
-        match value {
-            Value::Bool(value) => values.add_value(value)?,
-            Value::Integer(value) => values.add_value(value)?,
-            Value::Long(value) => values.add_value(value)?,
-            Value::Float(value) => values.add_value(value)?,
-            Value::Double(value) => values.add_value(value)?,
-            Value::String(value) => values.add_value(value)?,
-            Value::UnsignedBytes(value) => values.add_value(value)?,
-            Value::Date(value) => values.add_value(value)?,
-            Value::Duration(value) => {
-                let ts = value.as_ref().map(|value| Timestamp(*value));
-                values.add_value(&ts)?
-            }
-            _ => unimplemented!(),
-        };
+        values.add_value(&CqlValue(value))?;
         Ok(())
     }
 }
+
+/// Serialize `Value` as cql value, array as list and attributes as map
+struct CqlValue<'a>(&'a Value);
+
+impl<'a> ScyllaValue for CqlValue<'a> {
+    fn serialize(&self, buf: &mut Vec<u8>) -> Result<(), ValueTooBig> {
+        match self.0 {
+            Value::Null => None::<i32>.serialize(buf),
+            Value::Bool(value) => value.serialize(buf),
+            // cql has no unsigned type
+            Value::UnsignedInteger(value) => value.map(i64::from).serialize(buf),
+            Value::UnsignedLong(value) => match value {
+                Some(value) => match i64::try_from(*value) {
+                    Ok(value) => value.serialize(buf),
+                    Err(_) => Err(ValueTooBig),
+                },
+                None => None::<i64>.serialize(buf),
+            },
+            Value::Integer(value) => value.serialize(buf),
+            Value::Long(value) => value.serialize(buf),
+            Value::Float(value) => value.serialize(buf),
+            Value::Double(value) => value.serialize(buf),
+            Value::String(value) => value.serialize(buf),
+            Value::UnsignedBytes(value) => value.serialize(buf),
+            Value::Date(value) => value.serialize(buf),
+            Value::DateTime(value) => value
+                .map(|value| {
+                    let value = Utc.from_utc_datetime(&value);
+                    Timestamp(Duration::milliseconds(value.timestamp_millis()))
+                })
+                .serialize(buf),
+            Value::Duration(value) => value.map(Timestamp).serialize(buf),
+            Value::LocalTime(value) => value
+                .map(|value| Timestamp(Duration::milliseconds(value.timestamp_millis())))
+                .serialize(buf),
+            Value::UtcTime(value) => value
+                .map(|value| Timestamp(Duration::milliseconds(value.timestamp_millis())))
+                .serialize(buf),
+            Value::Array(values) => values
+                .iter()
+                .map(CqlValue)
+                .collect::<Vec<_>>()
+                .serialize(buf),
+            Value::Attributes(attributes) => attributes
+                .iter()
+                .map(|(name, value)| (name.as_str(), CqlValue(value)))
+                .collect::<HashMap<_, _>>()
+                .serialize(buf),
+        }
+    }
+}
+
+/// Value bound to `USING TIMESTAMP ?` as micros since epoch, null is rejected by Cassandra
+pub fn cql_timestamp(value: Value) -> anyhow::Result<Value> {
+    let micros = match value {
+        Value::Null => None,
+        Value::Integer(value) => value.map(i64::from),
+        Value::UnsignedInteger(value) => value.map(i64::from),
+        Value::Long(value) => value,
+        Value::UnsignedLong(value) => value.map(i64::try_from).transpose()?,
+        Value::DateTime(value) => {
+            value.map(|value| Utc.from_utc_datetime(&value).timestamp_micros())
+        }
+        Value::LocalTime(value) => value.map(|value| value.timestamp_micros()),
+        Value::UtcTime(value) => value.map(|value| value.timestamp_micros()),
+        value => anyhow::bail!("can not bind {:?} as timestamp", value),
+    };
+    match micros {
+        Some(micros) => Ok(Value::Long(Some(micros))),
+        None => anyhow::bail!("can not bind null as timestamp"),
+    }
+}
+
+/// Value bound to `USING TTL ?` as seconds, null is rejected by Cassandra
+pub fn cql_ttl(value: Value) -> anyhow::Result<Value> {
+    let seconds = match value {
+        Value::Null => None,
+        Value::Integer(value) => value,
+        Value::UnsignedInteger(value) => value.map(i32::try_from).transpose()?,
+        Value::Long(value) => value.map(i32::try_from).transpose()?,
+        Value::UnsignedLong(value) => value.map(i32::try_from).transpose()?,
+        Value::Duration(value) => value
+            .map(|value| i32::try_from(value.num_seconds()))
+            .transpose()?,
+        value => anyhow::bail!("can not bind {:?} as ttl", value),
+    };
+    match seconds {
+        Some(seconds) => Ok(Value::Integer(Some(seconds))),
+        None => anyhow::bail!("can not bind null as ttl"),
+    }
+}
+
+fn column_type(ty: &ColumnType) -> String {
+    let ty = match ty {
+        ColumnType::Bool => "boolean",
+        // cql has no unsigned type
+        ColumnType::UnsignedInteger | ColumnType::UnsignedLong | ColumnType::Long => "bigint",
+        ColumnType::Integer => "int",
+        ColumnType::Float => "float",
+        ColumnType::Double => "double",
+        ColumnType::String => "text",
+        ColumnType::Date => "date",
+        ColumnType::DateTime
+        | ColumnType::Duration
+        | ColumnType::LocalTime
+        | ColumnType::UtcTime => "timestamp",
+        ColumnType::UnsignedBytes => "blob",
+        ColumnType::Array(ty) => return format!("list<{}>", element_type(ty)),
+        ColumnType::Attributes(ty) => return format!("map<text, {}>", element_type(ty)),
+    };
+    ty.to_owned()
+}
+
+/// Nested collection frozen
+fn element_type(ty: &ColumnType) -> String {
+    match ty.is_collection() {
+        true => format!("frozen<{}>", column_type(ty)),
+        false => column_type(ty),
+    }
+}
+
+/// Number of `?` bind markers, quoted text skipped
+fn bind_markers(statement: &str) -> usize {
+    let mut markers = 0;
+    let mut quote: Option<char> = None;
+    for c in statement.chars() {
+        match (quote, c) {
+            (Some(q), c) if q == c => quote = None,
+            (Some(_), _) => (),
+            (None, '\'') | (None, '"') => quote = Some(c),
+            (None, '?') => markers += 1,
+            _ => (),
+        }
+    }
+    markers
+}
+
+fn create_table_statement(table: &str, columns: &[Column], keys: &[String]) -> String {
+    let definitions: Vec<String> = columns
+        .iter()
+        .map(|column| format!("{} {}", column.name, column_type(&column.ty)))
+        .collect();
+    format!(
+        "CREATE TABLE IF NOT EXISTS {} ({}, PRIMARY KEY ({}))",
+        table,
+        definitions.join(", "),
+        keys.join(", ")
+    )
+}
+
+fn add_column_statement(table: &str, column: &Column) -> String {
+    format!(
+        "ALTER TABLE {} ADD {} {}",
+        table,
+        column.name,
+        column_type(&column.ty)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn serialize<T: ScyllaValue>(t: T) -> Vec<u8> {
+        let mut buf = Vec::new();
+        t.serialize(&mut buf).unwrap();
+        buf
+    }
+
+    #[test]
+    fn test_cql_value() {
+        let tags = Value::Array(vec![Value::from("foo"), Value::from("bar")]);
+        assert_eq!(serialize(vec!["foo", "bar"]), serialize(CqlValue(&tags)));
+        assert_eq!(serialize(None::<i32>), serialize(CqlValue(&Value::Null)));
+        let mut attributes = HashMap::new();
+        attributes.insert("count".to_owned(), Value::from(1_i64));
+        let mut expected = HashMap::new();
+        expected.insert("count", 1_i64);
+        assert_eq!(
+            serialize(expected),
+            serialize(CqlValue(&Value::Attributes(attributes)))
+        );
+        let mut buf = Vec::new();
+        assert!(CqlValue(&Value::from(u64::MAX))
+            .serialize(&mut buf)
+            .is_err());
+    }
+
+    #[test]
+    fn test_create_table_statement() {
+        let columns = vec![
+            Column {
+                name: "id".to_owned(),
+                ty: ColumnType::UnsignedInteger,
+            },
+            Column {
+                name: "scores".to_owned(),
+                ty: ColumnType::Attributes(Box::new(ColumnType::Array(Box::new(
+                    ColumnType::Double,
+                )))),
+            },
+        ];
+        assert_eq!(
+            "CREATE TABLE IF NOT EXISTS test.records (id bigint, scores map<text, frozen<list<double>>>, PRIMARY KEY (id))",
+            create_table_statement("test.records", &columns, &["id".to_owned()])
+        );
+        assert_eq!(
+            "ALTER TABLE test.records ADD scores map<text, frozen<list<double>>>",
+            add_column_statement("test.records", &columns[1])
+        );
+    }
+
+    #[test]
+    fn test_bind_markers() {
+        assert_eq!(
+            3,
+            bind_markers(
+                "INSERT INTO test.records (id, note) VALUES (?, '?') USING TTL ? AND TIMESTAMP ?"
+            )
+        );
+        assert_eq!(
+            1,
+            bind_markers("UPDATE test.records SET \"a?\" = ? WHERE id = 1")
+        );
+    }
+
+    #[test]
+    fn test_cql_timestamp_ttl() {
+        let time = chrono::NaiveDate::from_ymd_opt(2021, 8, 21)
+            .unwrap()
+            .and_hms_opt(0, 0, 1)
+            .unwrap();
+        assert_eq!(
+            Value::Long(Some(1_629_504_001_000_000)),
+            cql_timestamp(Value::from(time)).unwrap()
+        );
+        assert_eq!(
+            Value::Integer(Some(3600)),
+            cql_ttl(Value::from(Duration::hours(1))).unwrap()
+        );
+        assert!(cql_ttl(Value::Long(None)).is_err());
+        assert!(cql_timestamp(Value::Null).is_err());
+        assert!(cql_ttl(Value::from(i64::MAX)).is_err());
+        assert!(cql_timestamp(Value::from("foo")).is_err());
+    }
+}
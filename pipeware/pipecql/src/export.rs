@@ -1,10 +1,14 @@
-use crate::client::{CqlClient, CqlClientConfig};
+use crate::client::{cql_timestamp, cql_ttl, CqlBatchType, CqlClient, CqlClientConfig};
 use async_trait::async_trait;
 use pipebase::{
-    common::{ConfigInto, FromConfig, FromPath, IntoAttributes, Render},
+    common::{
+        Column, ConfigInto, CreateTableConfig, FromConfig, FromPath, IntoAttributes, Render,
+        Schema, Value,
+    },
     export::Export,
 };
 use serde::Deserialize;
+use std::marker::PhantomData;
 
 #[derive(Deserialize)]
 pub struct CqlWriterConfig {
@@ -38,10 +42,28 @@ where
     }
 }
 
+const DEFAULT_BATCH_SIZE: usize = 20;
+
+#[derive(Deserialize)]
+pub struct CqlBatchConfig {
+    /// `Unlogged` by default, `Logged` guarantees all statements eventually apply
+    /// at the cost of a batchlog write on coordinator
+    ty: Option<CqlBatchType>,
+    /// Max number of statements per batch, 20 by default, large multi-partition batches
+    /// load coordinator and may exceed `batch_size_fail_threshold_in_kb`
+    size: Option<usize>,
+}
+
 #[derive(Deserialize)]
 pub struct CqlPreparedWriterConfig {
     client: CqlClientConfig,
     statement: String,
+    /// Execute items in batches, one by one if not configured
+    batch: Option<CqlBatchConfig>,
+    /// Field bound to `USING TIMESTAMP ?` as micros
+    timestamp: Option<String>,
+    /// Field bound to `USING TTL ?` as seconds
+    ttl: Option<String>,
 }
 
 impl FromPath for CqlPreparedWriterConfig {}
@@ -51,25 +73,254 @@ impl ConfigInto<CqlPreparedWriter> for CqlPreparedWriterConfig {}
 pub struct CqlPreparedWriter {
     client: CqlClient,
     statement: String,
+    batch: Option<(CqlBatchType, usize)>,
+    timestamp: Option<String>,
+    ttl: Option<String>,
+    using: UsingOrder,
 }
 
 #[async_trait]
 impl FromConfig<CqlPreparedWriterConfig> for CqlPreparedWriter {
     async fn from_config(config: CqlPreparedWriterConfig) -> anyhow::Result<Self> {
+        let client = CqlClient::new(config.client).await?;
+        let using = UsingOrder::parse(&config.statement);
         Ok(CqlPreparedWriter {
-            client: CqlClient::new(config.client).await?,
+            client,
             statement: config.statement,
+            batch: config.batch.map(|batch| {
+                (
+                    batch.ty.unwrap_or(CqlBatchType::Unlogged),
+                    batch.size.unwrap_or(DEFAULT_BATCH_SIZE),
+                )
+            }),
+            timestamp: config.timestamp,
+            ttl: config.ttl,
+            using,
         })
     }
 }
 
+/// # Parameters
+/// * Vec<T>: input, records with attributes bound as statement params
 #[async_trait]
 impl<T> Export<Vec<T>, CqlPreparedWriterConfig> for CqlPreparedWriter
 where
     T: IntoAttributes + Send + Sync + 'static,
 {
     async fn export(&mut self, items: Vec<T>) -> anyhow::Result<()> {
+        self.write(items).await
+    }
+}
+
+impl CqlPreparedWriter {
+    async fn write<T>(&mut self, items: Vec<T>) -> anyhow::Result<()>
+    where
+        T: IntoAttributes,
+    {
+        let rows = items
+            .into_iter()
+            .map(|item| {
+                bind_using(
+                    item.into_attribute_tuples(),
+                    self.timestamp.as_deref(),
+                    self.ttl.as_deref(),
+                    self.using,
+                )
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
         let statement = self.statement.to_owned();
-        self.client.prepare_execute(statement, items).await
+        match self.batch {
+            Some((ty, size)) => {
+                self.client
+                    .prepare_batch_execute(statement, rows, ty, size)
+                    .await
+            }
+            None => self.client.prepare_execute(statement, rows).await,
+        }
+    }
+
+    /// Whether field is bound to `USING` clause instead of column
+    fn is_using(&self, name: &str) -> bool {
+        self.timestamp.as_deref() == Some(name) || self.ttl.as_deref() == Some(name)
+    }
+}
+
+/// Position of `USING` clause params among statement params
+#[derive(Clone, Copy)]
+struct UsingOrder {
+    /// `USING` precedes column params, e.g `UPDATE ... USING TTL ? SET ...`
+    leading: bool,
+    /// `TTL ?` precedes `TIMESTAMP ?`
+    ttl_first: bool,
+}
+
+impl UsingOrder {
+    fn parse(statement: &str) -> Self {
+        let statement = statement.to_uppercase();
+        // `INSERT ... VALUES (...) USING ...`, other statements start with `USING`
+        let leading = !statement.trim_start().starts_with("INSERT");
+        let using = match statement.rfind("USING") {
+            Some(i) => &statement[i..],
+            None => "",
+        };
+        let ttl_first = match (using.find("TTL"), using.find("TIMESTAMP")) {
+            (Some(ttl), Some(timestamp)) => ttl < timestamp,
+            _ => false,
+        };
+        UsingOrder { leading, ttl_first }
+    }
+}
+
+/// Convert timestamp and ttl fields and move them to position of `USING` params,
+/// other fields bound to column params in order
+fn bind_using(
+    row: Vec<(String, Value)>,
+    timestamp: Option<&str>,
+    ttl: Option<&str>,
+    order: UsingOrder,
+) -> anyhow::Result<Vec<(String, Value)>> {
+    let mut columns = Vec::with_capacity(row.len());
+    let mut timestamp_value = None;
+    let mut ttl_value = None;
+    for (name, value) in row {
+        if timestamp == Some(name.as_str()) {
+            timestamp_value = Some((name, cql_timestamp(value)?));
+        } else if ttl == Some(name.as_str()) {
+            ttl_value = Some((name, cql_ttl(value)?));
+        } else {
+            columns.push((name, value));
+        }
+    }
+    let using: Vec<(String, Value)> = match order.ttl_first {
+        true => vec![ttl_value, timestamp_value],
+        false => vec![timestamp_value, ttl_value],
+    }
+    .into_iter()
+    .flatten()
+    .collect();
+    Ok(match order.leading {
+        true => using.into_iter().chain(columns).collect(),
+        false => columns.into_iter().chain(using).collect(),
+    })
+}
+
+#[derive(Deserialize)]
+pub struct CqlSchemaPreparedWriterConfig {
+    #[serde(flatten)]
+    writer: CqlPreparedWriterConfig,
+    /// Create table from record schema if not exists
+    create_table: Option<CreateTableConfig>,
+    /// Validate record columns against statement params at startup, true if table created
+    validate: Option<bool>,
+}
+
+impl FromPath for CqlSchemaPreparedWriterConfig {}
+
+impl<T> ConfigInto<CqlSchemaPreparedWriter<T>> for CqlSchemaPreparedWriterConfig where T: Schema {}
+
+/// Prepared writer creating table from schema of record type at startup
+pub struct CqlSchemaPreparedWriter<T> {
+    writer: CqlPreparedWriter,
+    _record: PhantomData<T>,
+}
+
+#[async_trait]
+impl<T> FromConfig<CqlSchemaPreparedWriterConfig> for CqlSchemaPreparedWriter<T>
+where
+    T: Schema,
+{
+    async fn from_config(config: CqlSchemaPreparedWriterConfig) -> anyhow::Result<Self> {
+        let writer = CqlPreparedWriter::from_config(config.writer).await?;
+        let created = match config.create_table {
+            Some(create_table) => {
+                // timestamp and ttl are not columns
+                let columns: Vec<Column> = T::schema()
+                    .into_iter()
+                    .filter(|column| !writer.is_using(&column.name))
+                    .collect();
+                writer
+                    .client
+                    .create_table(&create_table.table, &columns, &create_table.get_keys())
+                    .await?;
+                true
+            }
+            None => false,
+        };
+        // table may be created by upstream pipe after startup
+        if config.validate.unwrap_or(created) {
+            writer
+                .client
+                .validate_params(&writer.statement, &T::schema())
+                .await?;
+        }
+        Ok(CqlSchemaPreparedWriter {
+            writer,
+            _record: PhantomData,
+        })
+    }
+}
+
+/// # Parameters
+/// * Vec<T>: input, records with attributes bound as statement params
+#[async_trait]
+impl<T> Export<Vec<T>, CqlSchemaPreparedWriterConfig> for CqlSchemaPreparedWriter<T>
+where
+    T: IntoAttributes + Schema + Send + Sync + 'static,
+{
+    async fn export(&mut self, items: Vec<T>) -> anyhow::Result<()> {
+        self.writer.write(items).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::{bind_using, UsingOrder};
+    use chrono::{Duration, NaiveDate};
+    use pipebase::common::Value;
+
+    fn record() -> Vec<(String, Value)> {
+        let time = NaiveDate::from_ymd_opt(2021, 8, 21)
+            .unwrap()
+            .and_hms_opt(0, 0, 1)
+            .unwrap();
+        vec![
+            ("expire".to_owned(), Value::from(Duration::hours(1))),
+            ("id".to_owned(), Value::from("foo")),
+            ("updated".to_owned(), Value::from(time)),
+            ("count".to_owned(), Value::from(1_i64)),
+        ]
+    }
+
+    fn names(row: &[(String, Value)]) -> Vec<&str> {
+        row.iter().map(|(name, _)| name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_bind_using_after_values() {
+        let order = UsingOrder::parse(
+            "INSERT INTO test.records (id, count) VALUES (?, ?) USING TIMESTAMP ? AND TTL ?",
+        );
+        let row = bind_using(record(), Some("updated"), Some("expire"), order).unwrap();
+        assert_eq!(vec!["id", "count", "updated", "expire"], names(&row));
+        assert_eq!(Value::Long(Some(1_629_504_001_000_000)), row[2].1);
+        assert_eq!(Value::Integer(Some(3600)), row[3].1);
+        let order = UsingOrder::parse(
+            "insert into test.records (id, count) values (?, ?) using ttl ? and timestamp ?",
+        );
+        let row = bind_using(record(), Some("updated"), Some("expire"), order).unwrap();
+        assert_eq!(vec!["id", "count", "expire", "updated"], names(&row));
+    }
+
+    #[test]
+    fn test_bind_using_before_set() {
+        let order = UsingOrder::parse("UPDATE test.records USING TTL ? SET count = ? WHERE id = ?");
+        let row = vec![
+            ("count".to_owned(), Value::from(1_i64)),
+            ("id".to_owned(), Value::from("foo")),
+            ("expire".to_owned(), Value::from(Duration::hours(1))),
+        ];
+        let row = bind_using(row, None, Some("expire"), order).unwrap();
+        assert_eq!(vec!["expire", "count", "id"], names(&row));
     }
 }
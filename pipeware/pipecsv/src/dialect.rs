@@ -0,0 +1,113 @@
+use csv::{ReaderBuilder, StringRecord, Trim, WriterBuilder};
+use serde::Deserialize;
+
+#[derive(Clone, Copy, Deserialize)]
+pub enum CsvTrim {
+    None,
+    Headers,
+    Fields,
+    All,
+}
+
+impl From<CsvTrim> for Trim {
+    fn from(trim: CsvTrim) -> Self {
+        match trim {
+            CsvTrim::None => Trim::None,
+            CsvTrim::Headers => Trim::Headers,
+            CsvTrim::Fields => Trim::Fields,
+            CsvTrim::All => Trim::All,
+        }
+    }
+}
+
+/// Csv format, `csv` crate defaults if not specified
+#[derive(Clone, Default, Deserialize)]
+pub struct CsvDialect {
+    /// Field delimiter, `,` by default
+    pub delimiter: Option<char>,
+    /// Quote character, `"` by default
+    pub quote: Option<char>,
+    /// Escape character for quote in quoted field, quote is escaped by doubling by default
+    pub escape: Option<char>,
+    /// Whether first row is header, true by default
+    pub has_headers: Option<bool>,
+    /// Header override, replace header row if any
+    pub headers: Option<Vec<String>>,
+    /// Trim whitespace in headers and fields while reading
+    pub trim: Option<CsvTrim>,
+    /// Allow records with different number of fields
+    pub flexible: Option<bool>,
+}
+
+fn to_byte(c: char, name: &str) -> anyhow::Result<u8> {
+    match c.is_ascii() {
+        true => Ok(c as u8),
+        false => anyhow::bail!("csv {} '{}' is not single byte character", name, c),
+    }
+}
+
+impl CsvDialect {
+    pub fn has_headers(&self) -> bool {
+        self.has_headers.unwrap_or(true)
+    }
+
+    pub fn get_headers(&self) -> Option<StringRecord> {
+        self.headers
+            .as_ref()
+            .map(|headers| StringRecord::from(headers.to_owned()))
+    }
+
+    pub fn reader_builder(&self) -> anyhow::Result<ReaderBuilder> {
+        let mut builder = ReaderBuilder::new();
+        builder.has_headers(self.has_headers());
+        if let Some(delimiter) = self.delimiter {
+            builder.delimiter(to_byte(delimiter, "delimiter")?);
+        }
+        if let Some(quote) = self.quote {
+            builder.quote(to_byte(quote, "quote")?);
+        }
+        if let Some(escape) = self.escape {
+            builder.escape(Some(to_byte(escape, "escape")?));
+            builder.double_quote(false);
+        }
+        if let Some(trim) = self.trim {
+            builder.trim(trim.into());
+        }
+        builder.flexible(self.flexible.unwrap_or(false));
+        Ok(builder)
+    }
+
+    /// Header row is written by writer only if headers not overridden
+    pub fn writer_builder(&self) -> anyhow::Result<WriterBuilder> {
+        let mut builder = WriterBuilder::new();
+        builder.has_headers(self.has_headers() && self.headers.is_none());
+        if let Some(delimiter) = self.delimiter {
+            builder.delimiter(to_byte(delimiter, "delimiter")?);
+        }
+        if let Some(quote) = self.quote {
+            builder.quote(to_byte(quote, "quote")?);
+        }
+        if let Some(escape) = self.escape {
+            builder.escape(to_byte(escape, "escape")?);
+            builder.double_quote(false);
+        }
+        builder.flexible(self.flexible.unwrap_or(false));
+        Ok(builder)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_non_ascii_delimiter() {
+        let dialect = CsvDialect {
+            delimiter: Some('§'),
+            ..Default::default()
+        };
+        assert!(dialect.reader_builder().is_err());
+        assert!(dialect.writer_builder().is_err());
+    }
+}
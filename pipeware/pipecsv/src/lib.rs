@@ -1,3 +1,7 @@
+mod dialect;
 mod map;
+mod stream;
 
+pub use dialect::*;
 pub use map::*;
+pub use stream::*;
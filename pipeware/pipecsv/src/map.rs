@@ -1,43 +1,86 @@
+use crate::CsvDialect;
 use async_trait::async_trait;
+use csv::{Reader, StringRecord};
 use pipebase::{
-    common::{ConfigInto, FromConfig, FromPath},
+    common::{ConfigFormat, ConfigInto, FromConfig, FromPath},
     map::Map,
 };
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::io::Read;
 use std::path::Path;
 
-#[derive(Deserialize)]
-pub struct CsvSerConfig {}
+/// Read config if path is specified, default dialect otherwise
+pub(crate) fn read_config<C, P>(path: P) -> anyhow::Result<C>
+where
+    C: DeserializeOwned + Default,
+    P: AsRef<Path>,
+{
+    if path.as_ref().as_os_str().is_empty() {
+        return Ok(C::default());
+    }
+    let format = ConfigFormat::from_path(&path);
+    let bytes = std::fs::read(path)?;
+    format.deserialize(&bytes)
+}
+
+/// Headers used to deserialize records, overridden headers first
+pub(crate) fn read_headers<R: Read>(
+    dialect: &CsvDialect,
+    rdr: &mut Reader<R>,
+) -> anyhow::Result<Option<StringRecord>> {
+    if let Some(headers) = dialect.get_headers() {
+        return Ok(Some(headers));
+    }
+    match dialect.has_headers() {
+        true => Ok(Some(rdr.headers()?.to_owned())),
+        false => Ok(None),
+    }
+}
+
+#[derive(Default, Deserialize)]
+pub struct CsvSerConfig {
+    #[serde(flatten)]
+    pub dialect: CsvDialect,
+}
 
 #[async_trait]
 impl FromPath for CsvSerConfig {
-    async fn from_path<P>(_path: P) -> anyhow::Result<Self>
+    async fn from_path<P>(path: P) -> anyhow::Result<Self>
     where
         P: AsRef<Path> + Send,
     {
-        Ok(CsvSerConfig {})
+        read_config(path)
     }
 }
 
 #[async_trait]
 impl ConfigInto<CsvSer> for CsvSerConfig {}
 
-pub struct CsvSer {}
+pub struct CsvSer {
+    dialect: CsvDialect,
+}
 
 #[async_trait]
 impl FromConfig<CsvSerConfig> for CsvSer {
-    async fn from_config(_config: CsvSerConfig) -> anyhow::Result<Self> {
-        Ok(CsvSer {})
+    async fn from_config(config: CsvSerConfig) -> anyhow::Result<Self> {
+        // validate dialect
+        config.dialect.writer_builder()?;
+        Ok(CsvSer {
+            dialect: config.dialect,
+        })
     }
 }
 
 impl CsvSer {
-    fn serialize<I, T>(t: T) -> anyhow::Result<Vec<u8>>
+    fn serialize<I, T>(&self, t: T) -> anyhow::Result<Vec<u8>>
     where
         I: Serialize,
         T: IntoIterator<Item = I>,
     {
-        let mut wtr = csv::Writer::from_writer(vec![]);
+        let mut wtr = self.dialect.writer_builder()?.from_writer(vec![]);
+        if let (true, Some(headers)) = (self.dialect.has_headers(), self.dialect.get_headers()) {
+            wtr.write_record(&headers)?;
+        }
         for item in t.into_iter() {
             wtr.serialize(item)?;
         }
@@ -53,41 +96,51 @@ where
     T: IntoIterator<Item = I> + Send + Sync + 'static,
 {
     async fn map(&mut self, t: T) -> anyhow::Result<Vec<u8>> {
-        CsvSer::serialize(t)
+        self.serialize(t)
     }
 }
 
-#[derive(Deserialize)]
-pub struct CsvDeserConfig {}
+#[derive(Default, Deserialize)]
+pub struct CsvDeserConfig {
+    #[serde(flatten)]
+    pub dialect: CsvDialect,
+}
 
 #[async_trait]
 impl FromPath for CsvDeserConfig {
-    async fn from_path<P>(_path: P) -> anyhow::Result<Self>
+    async fn from_path<P>(path: P) -> anyhow::Result<Self>
     where
         P: AsRef<Path> + Send,
     {
-        Ok(CsvDeserConfig {})
+        read_config(path)
     }
 }
 
 #[async_trait]
 impl ConfigInto<CsvDeser> for CsvDeserConfig {}
 
-pub struct CsvDeser {}
+pub struct CsvDeser {
+    dialect: CsvDialect,
+}
 
 #[async_trait]
 impl FromConfig<CsvDeserConfig> for CsvDeser {
-    async fn from_config(_config: CsvDeserConfig) -> anyhow::Result<Self> {
-        Ok(CsvDeser {})
+    async fn from_config(config: CsvDeserConfig) -> anyhow::Result<Self> {
+        // validate dialect
+        config.dialect.reader_builder()?;
+        Ok(CsvDeser {
+            dialect: config.dialect,
+        })
     }
 }
 
 impl CsvDeser {
-    fn deserialize<T: DeserializeOwned>(bytes: &[u8]) -> anyhow::Result<Vec<T>> {
-        let mut rdr = csv::Reader::from_reader(bytes);
+    fn deserialize<T: DeserializeOwned>(&self, bytes: &[u8]) -> anyhow::Result<Vec<T>> {
+        let mut rdr = self.dialect.reader_builder()?.from_reader(bytes);
+        let headers = read_headers(&self.dialect, &mut rdr)?;
         let mut records: Vec<T> = Vec::new();
-        for result in rdr.deserialize::<T>() {
-            let record = result?;
+        for result in rdr.records() {
+            let record = result?.deserialize::<T>(headers.as_ref())?;
             records.push(record);
         }
         Ok(records)
@@ -100,6 +153,96 @@ where
     T: DeserializeOwned + Sync,
 {
     async fn map(&mut self, bytes: Vec<u8>) -> anyhow::Result<Vec<T>> {
-        CsvDeser::deserialize(bytes.as_slice())
+        self.deserialize(bytes.as_slice())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::*;
+    use pipebase::prelude::*;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+    struct Record {
+        id: u32,
+        name: String,
+    }
+
+    fn records() -> Vec<Record> {
+        vec![
+            Record {
+                id: 1,
+                name: "foo;bar".to_owned(),
+            },
+            Record {
+                id: 2,
+                name: "baz".to_owned(),
+            },
+        ]
+    }
+
+    async fn ser_deser(dialect: &str) -> (CsvSer, CsvDeser) {
+        let ser = CsvSer::from_config(serde_yaml::from_str(dialect).unwrap())
+            .await
+            .unwrap();
+        let deser = CsvDeser::from_config(serde_yaml::from_str(dialect).unwrap())
+            .await
+            .unwrap();
+        (ser, deser)
+    }
+
+    #[tokio::test]
+    async fn test_csv_default_dialect() {
+        let (mut ser, mut deser) = ser_deser("{}").await;
+        let bytes = ser.map(records()).await.unwrap();
+        assert_eq!(
+            "id,name\n1,foo;bar\n2,baz\n",
+            String::from_utf8(bytes.to_owned()).unwrap()
+        );
+        let deserialized: Vec<Record> = deser.map(bytes).await.unwrap();
+        assert_eq!(records(), deserialized);
+    }
+
+    #[tokio::test]
+    async fn test_csv_non_default_dialect() {
+        let (mut ser, mut deser) = ser_deser("delimiter: ';'\nquote: \"'\"\n").await;
+        let bytes = ser.map(records()).await.unwrap();
+        assert_eq!(
+            "id;name\n1;'foo;bar'\n2;baz\n",
+            String::from_utf8(bytes.to_owned()).unwrap()
+        );
+        let deserialized: Vec<Record> = deser.map(bytes).await.unwrap();
+        assert_eq!(records(), deserialized);
+    }
+
+    #[tokio::test]
+    async fn test_csv_without_headers() {
+        let (mut ser, mut deser) = ser_deser("has_headers: false\n").await;
+        let bytes = ser.map(records()).await.unwrap();
+        assert_eq!(
+            "1,foo;bar\n2,baz\n",
+            String::from_utf8(bytes.to_owned()).unwrap()
+        );
+        // fields deserialized in order without headers
+        let deserialized: Vec<Record> = deser.map(bytes).await.unwrap();
+        assert_eq!(records(), deserialized);
+    }
+
+    #[tokio::test]
+    async fn test_csv_override_headers() {
+        let (mut ser, mut deser) = ser_deser("headers: [id, name]\ntrim: All\n").await;
+        let bytes = ser.map(records()).await.unwrap();
+        assert_eq!(
+            "id,name\n1,foo;bar\n2,baz\n",
+            String::from_utf8(bytes).unwrap()
+        );
+        // header row replaced by overridden headers
+        let deserialized: Vec<Record> = deser
+            .map(b"ID, NAME\n1, foo;bar\n2, baz\n".to_vec())
+            .await
+            .unwrap();
+        assert_eq!(records(), deserialized);
     }
 }
@@ -0,0 +1,133 @@
+use crate::{read_config, read_headers, CsvDialect};
+use async_trait::async_trait;
+use csv::StringRecord;
+use pipebase::{
+    common::{ConfigInto, FromConfig, FromPath},
+    stream::{FileStreamReader, Stream},
+};
+use serde::{de::DeserializeOwned, Deserialize};
+use std::path::Path;
+use tokio::sync::mpsc::Sender;
+
+#[derive(Default, Deserialize)]
+pub struct CsvFileReaderConfig {
+    #[serde(flatten)]
+    pub dialect: CsvDialect,
+}
+
+#[async_trait]
+impl FromPath for CsvFileReaderConfig {
+    async fn from_path<P>(path: P) -> anyhow::Result<Self>
+    where
+        P: AsRef<Path> + Send,
+    {
+        read_config(path)
+    }
+}
+
+impl<U> ConfigInto<CsvFileReader<U>> for CsvFileReaderConfig {}
+
+/// Stream csv file row by row, `.gz` and `.zst` files are decompressed
+pub struct CsvFileReader<U> {
+    dialect: CsvDialect,
+    tx: Option<Sender<U>>,
+}
+
+#[async_trait]
+impl<U> FromConfig<CsvFileReaderConfig> for CsvFileReader<U> {
+    async fn from_config(config: CsvFileReaderConfig) -> anyhow::Result<Self> {
+        // validate dialect
+        config.dialect.reader_builder()?;
+        Ok(CsvFileReader {
+            dialect: config.dialect,
+            tx: None,
+        })
+    }
+}
+
+#[async_trait]
+impl<U> FileStreamReader for CsvFileReader<U>
+where
+    U: DeserializeOwned + Send + Sync + 'static,
+{
+    async fn stream_file<P>(&self, path: P) -> anyhow::Result<()>
+    where
+        P: AsRef<Path> + Send,
+    {
+        let reader = Self::new_reader(path)?;
+        let mut rdr = self.dialect.reader_builder()?.from_reader(reader);
+        let headers = read_headers(&self.dialect, &mut rdr)?;
+        let tx = self.tx.as_ref().unwrap();
+        let mut record = StringRecord::new();
+        while rdr.read_record(&mut record)? {
+            let u: U = record.deserialize(headers.as_ref())?;
+            tx.send(u).await?;
+        }
+        Ok(())
+    }
+}
+
+/// # Parameters
+/// * P: input, file path
+/// * U: output, row
+#[async_trait]
+impl<P, U> Stream<P, U, CsvFileReaderConfig> for CsvFileReader<U>
+where
+    P: AsRef<Path> + Send + 'static,
+    U: DeserializeOwned + Send + Sync + 'static,
+{
+    async fn stream(&mut self, path: P) -> anyhow::Result<()> {
+        self.stream_file(path).await
+    }
+
+    fn set_sender(&mut self, sender: Sender<U>) {
+        self.tx = Some(sender)
+    }
+}
+
+#[cfg(test)]
+mod csv_file_streamer_tests {
+
+    use crate::*;
+    use pipebase::prelude::*;
+    use serde::Deserialize;
+    use std::path::PathBuf;
+
+    #[derive(Clone, Debug, Deserialize, PartialEq)]
+    struct Record {
+        id: u32,
+        name: String,
+    }
+
+    #[tokio::test]
+    async fn test_csv_file_streamer() {
+        let (tx0, rx0) = channel!(PathBuf, 1024);
+        let (tx1, mut rx1) = channel!(Record, 1024);
+        let channels = pipe_channels!(rx0, [tx1]);
+        let config = config!(
+            CsvFileReaderConfig,
+            "resources/catalogs/csv_file_streamer.yml"
+        );
+        let pipe = streamer!("csv_file_streamer");
+        tx0.send(PathBuf::from("resources/test_file_stream/records.csv"))
+            .await
+            .unwrap();
+        drop(tx0);
+        join_pipes!([run_pipe!(pipe, config, channels)]);
+        assert_eq!(
+            Record {
+                id: 1,
+                name: "foo".to_owned()
+            },
+            rx1.recv().await.unwrap()
+        );
+        assert_eq!(
+            Record {
+                id: 2,
+                name: "bar".to_owned()
+            },
+            rx1.recv().await.unwrap()
+        );
+        assert!(rx1.recv().await.is_none());
+    }
+}
@@ -23,8 +23,8 @@ impl DynamocDBClient {
         let attribute_values: HashMap<String, AttributeValue> = attribute_values
             .into_attributes()
             .into_iter()
-            .map(|(k, v)| (k, Self::convert_attribute_value(v)))
-            .collect();
+            .map(|(k, v)| Ok((k, Self::convert_attribute_value(v)?)))
+            .collect::<anyhow::Result<_>>()?;
         let mut request = self.client.put_item().table_name(&self.table);
         for (name, value) in attribute_values {
             request = request.item(name, value);
@@ -33,50 +33,79 @@ impl DynamocDBClient {
         Ok(())
     }
 
-    pub fn convert_attribute_value(v: Value) -> AttributeValue {
-        match v {
+    /// Date as `%Y-%m-%d`, datetime as `%Y-%m-%dT%H:%M:%S%.f`, time as rfc3339 and duration as millis
+    pub fn convert_attribute_value(v: Value) -> anyhow::Result<AttributeValue> {
+        let v = match v {
+            Value::Null => AttributeValue::Null(true),
             Value::Bool(v) => match v {
                 Some(v) => AttributeValue::Bool(v),
                 None => AttributeValue::Null(true),
             },
-            Value::UnsignedInteger(v) => match v {
-                Some(v) => AttributeValue::N(format!("{}", v)),
-                None => AttributeValue::Null(true),
-            },
-            Value::Integer(v) => match v {
-                Some(v) => AttributeValue::N(format!("{}", v)),
-                None => AttributeValue::Null(true),
-            },
-            Value::UnsignedLong(v) => match v {
-                Some(v) => AttributeValue::N(format!("{}", v)),
-                None => AttributeValue::Null(true),
-            },
-            Value::Long(v) => match v {
-                Some(v) => AttributeValue::N(format!("{}", v)),
-                None => AttributeValue::Null(true),
-            },
-            Value::Float(v) => match v {
-                Some(v) => AttributeValue::N(format!("{}", v)),
-                None => AttributeValue::Null(true),
-            },
-            Value::Double(v) => match v {
-                Some(v) => AttributeValue::N(format!("{}", v)),
-                None => AttributeValue::Null(true),
-            },
+            Value::UnsignedInteger(v) => Self::number(v),
+            Value::Integer(v) => Self::number(v),
+            Value::UnsignedLong(v) => Self::number(v),
+            Value::Long(v) => Self::number(v),
+            Value::Float(v) => Self::number(v),
+            Value::Double(v) => Self::number(v),
             Value::String(v) => match v {
                 Some(v) => AttributeValue::S(v),
                 None => AttributeValue::Null(true),
             },
-            Value::UnsignedBytes(bs) => AttributeValue::B(Blob::new(bs)),
-            Value::Array(vs) => {
-                AttributeValue::L(vs.into_iter().map(Self::convert_attribute_value).collect())
+            Value::Date(v) => Self::string(v.map(|v| v.format("%Y-%m-%d").to_string())),
+            Value::DateTime(v) => {
+                Self::string(v.map(|v| v.format("%Y-%m-%dT%H:%M:%S%.f").to_string()))
             }
+            Value::Duration(v) => Self::number(v.map(|v| v.num_milliseconds())),
+            Value::LocalTime(v) => Self::string(v.map(|v| v.to_rfc3339())),
+            Value::UtcTime(v) => Self::string(v.map(|v| v.to_rfc3339())),
+            Value::UnsignedBytes(bs) => AttributeValue::B(Blob::new(bs)),
+            Value::Array(vs) => AttributeValue::L(
+                vs.into_iter()
+                    .map(Self::convert_attribute_value)
+                    .collect::<anyhow::Result<_>>()?,
+            ),
             Value::Attributes(vs) => AttributeValue::M(
                 vs.into_iter()
-                    .map(|(k, v)| (k, Self::convert_attribute_value(v)))
-                    .collect(),
+                    .map(|(k, v)| Ok((k, Self::convert_attribute_value(v)?)))
+                    .collect::<anyhow::Result<_>>()?,
             ),
-            _ => unimplemented!(),
+        };
+        Ok(v)
+    }
+
+    fn number<N: ToString>(v: Option<N>) -> AttributeValue {
+        match v {
+            Some(v) => AttributeValue::N(v.to_string()),
+            None => AttributeValue::Null(true),
+        }
+    }
+
+    fn string(v: Option<String>) -> AttributeValue {
+        match v {
+            Some(v) => AttributeValue::S(v),
+            None => AttributeValue::Null(true),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_convert_attribute_value() {
+        assert_eq!(
+            AttributeValue::Null(true),
+            DynamocDBClient::convert_attribute_value(Value::Null).unwrap()
+        );
+        let values = Value::Array(vec![Value::Null, Value::from(1_i64)]);
+        assert_eq!(
+            AttributeValue::L(vec![
+                AttributeValue::Null(true),
+                AttributeValue::N("1".to_owned())
+            ]),
+            DynamocDBClient::convert_attribute_value(values).unwrap()
+        );
+    }
+}
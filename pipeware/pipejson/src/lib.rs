@@ -1,2 +1,5 @@
 mod map;
+mod stream;
+
 pub use map::*;
+pub use stream::*;
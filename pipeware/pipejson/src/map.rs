@@ -1,39 +1,57 @@
 use async_trait::async_trait;
+use jsonpath_lib::Compiled;
 use pipebase::{
-    common::{ConfigInto, FromConfig, FromPath, GroupAs, Pair},
+    common::{ConfigFormat, ConfigInto, FromConfig, FromPath, GroupAs, Pair},
     map::Map,
 };
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
 use std::path::Path;
 
-#[derive(Deserialize)]
-pub struct JsonSerConfig {}
+#[derive(Default, Deserialize)]
+pub struct JsonSerConfig {
+    /// Pretty print, compact by default
+    pub pretty: Option<bool>,
+}
 
 #[async_trait]
 impl FromPath for JsonSerConfig {
-    async fn from_path<P>(_path: P) -> anyhow::Result<Self>
+    async fn from_path<P>(path: P) -> anyhow::Result<Self>
     where
         P: AsRef<Path> + Send,
     {
-        Ok(JsonSerConfig {})
+        if path.as_ref().as_os_str().is_empty() {
+            return Ok(JsonSerConfig::default());
+        }
+        let format = ConfigFormat::from_path(&path);
+        let bytes = std::fs::read(path)?;
+        format.deserialize(&bytes)
     }
 }
 
 #[async_trait]
 impl ConfigInto<JsonSer> for JsonSerConfig {}
 
-pub struct JsonSer {}
+pub struct JsonSer {
+    pretty: bool,
+}
 
 #[async_trait]
 impl FromConfig<JsonSerConfig> for JsonSer {
-    async fn from_config(_config: JsonSerConfig) -> anyhow::Result<Self> {
-        Ok(JsonSer {})
+    async fn from_config(config: JsonSerConfig) -> anyhow::Result<Self> {
+        Ok(JsonSer {
+            pretty: config.pretty.unwrap_or(false),
+        })
     }
 }
 
 impl JsonSer {
-    fn serialize<T: Serialize>(t: &T) -> anyhow::Result<Vec<u8>> {
-        match serde_json::to_vec(t) {
+    fn serialize<T: Serialize>(t: &T, pretty: bool) -> anyhow::Result<Vec<u8>> {
+        let r = match pretty {
+            true => serde_json::to_vec_pretty(t),
+            false => serde_json::to_vec(t),
+        };
+        match r {
             Ok(r) => Ok(r),
             Err(err) => Err(err.into()),
         }
@@ -46,7 +64,7 @@ where
     T: Serialize + Send + Sync + 'static,
 {
     async fn map(&mut self, t: T) -> anyhow::Result<Vec<u8>> {
-        JsonSer::serialize(&t)
+        JsonSer::serialize(&t, self.pretty)
     }
 }
 
@@ -92,16 +110,24 @@ where
     }
 }
 
-#[derive(Deserialize)]
-pub struct JsonRecordSerConfig {}
+#[derive(Default, Deserialize)]
+pub struct JsonRecordSerConfig {
+    /// Pretty print, compact by default
+    pub pretty: Option<bool>,
+}
 
 #[async_trait]
 impl FromPath for JsonRecordSerConfig {
-    async fn from_path<P>(_path: P) -> anyhow::Result<Self>
+    async fn from_path<P>(path: P) -> anyhow::Result<Self>
     where
         P: AsRef<std::path::Path> + Send,
     {
-        Ok(JsonRecordSerConfig {})
+        if path.as_ref().as_os_str().is_empty() {
+            return Ok(JsonRecordSerConfig::default());
+        }
+        let format = ConfigFormat::from_path(&path);
+        let bytes = std::fs::read(path)?;
+        format.deserialize(&bytes)
     }
 }
 
@@ -109,19 +135,23 @@ impl ConfigInto<JsonRecordSer> for JsonRecordSerConfig {}
 
 #[async_trait]
 impl FromConfig<JsonRecordSerConfig> for JsonRecordSer {
-    async fn from_config(_config: JsonRecordSerConfig) -> anyhow::Result<Self> {
-        Ok(JsonRecordSer {})
+    async fn from_config(config: JsonRecordSerConfig) -> anyhow::Result<Self> {
+        Ok(JsonRecordSer {
+            pretty: config.pretty.unwrap_or(false),
+        })
     }
 }
 
-pub struct JsonRecordSer {}
+pub struct JsonRecordSer {
+    pretty: bool,
+}
 
 impl JsonRecordSer {
-    fn serialize<K, R>(record: &R) -> anyhow::Result<Pair<K, Vec<u8>>>
+    fn serialize<K, R>(record: &R, pretty: bool) -> anyhow::Result<Pair<K, Vec<u8>>>
     where
         R: GroupAs<K> + Serialize,
     {
-        let bytes = serde_json::to_vec(record)?;
+        let bytes = JsonSer::serialize(record, pretty)?;
         let key = record.group();
         Ok(Pair::new(key, bytes))
     }
@@ -133,6 +163,280 @@ where
     R: GroupAs<K> + Serialize + Send + 'static,
 {
     async fn map(&mut self, data: R) -> anyhow::Result<Pair<K, Vec<u8>>> {
-        Self::serialize(&data)
+        Self::serialize(&data, self.pretty)
+    }
+}
+
+#[derive(Deserialize)]
+pub struct NdjsonSerConfig {}
+
+#[async_trait]
+impl FromPath for NdjsonSerConfig {
+    async fn from_path<P>(_path: P) -> anyhow::Result<Self>
+    where
+        P: AsRef<Path> + Send,
+    {
+        Ok(NdjsonSerConfig {})
+    }
+}
+
+impl ConfigInto<NdjsonSer> for NdjsonSerConfig {}
+
+/// Serialize records into newline delimited json
+pub struct NdjsonSer {}
+
+#[async_trait]
+impl FromConfig<NdjsonSerConfig> for NdjsonSer {
+    async fn from_config(_config: NdjsonSerConfig) -> anyhow::Result<Self> {
+        Ok(NdjsonSer {})
+    }
+}
+
+impl NdjsonSer {
+    fn serialize<T, I>(ts: I) -> anyhow::Result<Vec<u8>>
+    where
+        T: Serialize,
+        I: IntoIterator<Item = T>,
+    {
+        let mut bytes: Vec<u8> = vec![];
+        for t in ts {
+            serde_json::to_writer(&mut bytes, &t)?;
+            bytes.push(b'\n');
+        }
+        Ok(bytes)
+    }
+}
+
+/// # Parameters
+/// * U: input, records
+/// * Vec<u8>: output
+#[async_trait]
+impl<T, U> Map<U, Vec<u8>, NdjsonSerConfig> for NdjsonSer
+where
+    T: Serialize,
+    U: IntoIterator<Item = T> + Send + 'static,
+{
+    async fn map(&mut self, data: U) -> anyhow::Result<Vec<u8>> {
+        NdjsonSer::serialize(data)
+    }
+}
+
+#[derive(Deserialize)]
+pub struct NdjsonDeserConfig {}
+
+#[async_trait]
+impl FromPath for NdjsonDeserConfig {
+    async fn from_path<P>(_path: P) -> anyhow::Result<Self>
+    where
+        P: AsRef<Path> + Send,
+    {
+        Ok(NdjsonDeserConfig {})
+    }
+}
+
+impl ConfigInto<NdjsonDeser> for NdjsonDeserConfig {}
+
+/// Deserialize newline delimited json into records, blank lines skipped
+pub struct NdjsonDeser {}
+
+#[async_trait]
+impl FromConfig<NdjsonDeserConfig> for NdjsonDeser {
+    async fn from_config(_config: NdjsonDeserConfig) -> anyhow::Result<Self> {
+        Ok(NdjsonDeser {})
+    }
+}
+
+/// # Parameters
+/// * Vec<u8>: input
+/// * Vec<T>: output, records
+#[async_trait]
+impl<T> Map<Vec<u8>, Vec<T>, NdjsonDeserConfig> for NdjsonDeser
+where
+    T: DeserializeOwned + Send + 'static,
+{
+    async fn map(&mut self, bytes: Vec<u8>) -> anyhow::Result<Vec<T>> {
+        ndjson_lines(&bytes).map(deserialize_line).collect()
+    }
+}
+
+/// Non blank lines of newline delimited json
+pub(crate) fn ndjson_lines(bytes: &[u8]) -> impl Iterator<Item = &[u8]> {
+    bytes
+        .split(|b| *b == b'\n')
+        .filter(|line| !line.iter().all(u8::is_ascii_whitespace))
+}
+
+pub(crate) fn deserialize_line<T: DeserializeOwned>(line: &[u8]) -> anyhow::Result<T> {
+    let t: T = serde_json::from_slice(line)?;
+    Ok(t)
+}
+
+#[derive(Deserialize)]
+pub struct JsonPathExtractorConfig {
+    /// JSONPath expression, e.g `$.data.items[*]`
+    pub path: String,
+    /// Split selected array into individual records
+    pub split: Option<bool>,
+}
+
+impl FromPath for JsonPathExtractorConfig {}
+
+impl ConfigInto<JsonPathExtractor> for JsonPathExtractorConfig {}
+
+/// Select sub-documents from raw json with JSONPath and deserialize
+pub struct JsonPathExtractor {
+    path: Compiled,
+    split: bool,
+}
+
+#[async_trait]
+impl FromConfig<JsonPathExtractorConfig> for JsonPathExtractor {
+    async fn from_config(config: JsonPathExtractorConfig) -> anyhow::Result<Self> {
+        let path = match Compiled::compile(&config.path) {
+            Ok(path) => path,
+            Err(err) => anyhow::bail!("invalid json path '{}': {}", config.path, err),
+        };
+        Ok(JsonPathExtractor {
+            path,
+            split: config.split.unwrap_or(false),
+        })
+    }
+}
+
+impl JsonPathExtractor {
+    fn extract<T: DeserializeOwned>(&self, bytes: &[u8]) -> anyhow::Result<Vec<T>> {
+        let json: Value = serde_json::from_slice(bytes)?;
+        let selected = match self.path.select(&json) {
+            Ok(selected) => selected,
+            Err(err) => anyhow::bail!("json path select failed: {:?}", err),
+        };
+        let mut ts: Vec<T> = vec![];
+        for value in selected {
+            match (self.split, value) {
+                (true, Value::Array(values)) => {
+                    for value in values {
+                        ts.push(T::deserialize(value)?);
+                    }
+                }
+                _ => ts.push(T::deserialize(value)?),
+            }
+        }
+        Ok(ts)
+    }
+}
+
+/// # Parameters
+/// * Vec<u8>: input
+/// * Vec<T>: output, selected records
+#[async_trait]
+impl<T> Map<Vec<u8>, Vec<T>, JsonPathExtractorConfig> for JsonPathExtractor
+where
+    T: DeserializeOwned + Send + 'static,
+{
+    async fn map(&mut self, bytes: Vec<u8>) -> anyhow::Result<Vec<T>> {
+        self.extract(bytes.as_slice())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::*;
+    use pipebase::prelude::*;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+    struct Record {
+        id: u32,
+        name: String,
+    }
+
+    fn records() -> Vec<Record> {
+        vec![
+            Record {
+                id: 1,
+                name: "foo".to_owned(),
+            },
+            Record {
+                id: 2,
+                name: "bar".to_owned(),
+            },
+        ]
+    }
+
+    async fn json_path_extractor(path: &str, split: bool) -> JsonPathExtractor {
+        JsonPathExtractor::from_config(JsonPathExtractorConfig {
+            path: path.to_owned(),
+            split: Some(split),
+        })
+        .await
+        .unwrap()
+    }
+
+    const ITEMS: &[u8] = br#"{"data":{"items":[{"id":1,"name":"foo"},{"id":2,"name":"bar"}]}}"#;
+
+    #[tokio::test]
+    async fn test_json_ser_deser() {
+        let mut ser = JsonSer::from_config(JsonSerConfig { pretty: Some(true) })
+            .await
+            .unwrap();
+        let mut deser = JsonDeser::from_config(JsonDeserConfig {}).await.unwrap();
+        let bytes = ser.map(records()).await.unwrap();
+        assert!(bytes.contains(&b'\n'));
+        let deserialized: Vec<Record> = deser.map(bytes).await.unwrap();
+        assert_eq!(records(), deserialized);
+    }
+
+    #[tokio::test]
+    async fn test_ndjson_ser_deser() {
+        let mut ser = NdjsonSer::from_config(NdjsonSerConfig {}).await.unwrap();
+        let mut deser = NdjsonDeser::from_config(NdjsonDeserConfig {})
+            .await
+            .unwrap();
+        let mut bytes = ser.map(records()).await.unwrap();
+        assert_eq!(
+            "{\"id\":1,\"name\":\"foo\"}\n{\"id\":2,\"name\":\"bar\"}\n",
+            String::from_utf8(bytes.to_owned()).unwrap()
+        );
+        // blank lines skipped
+        bytes.extend_from_slice(b"\n  \n");
+        let deserialized: Vec<Record> = deser.map(bytes).await.unwrap();
+        assert_eq!(records(), deserialized);
+        let malformed: anyhow::Result<Vec<Record>> = deser.map(b"{\"id\":1}\n".to_vec()).await;
+        assert!(malformed.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_json_path_extract() {
+        let mut extractor = json_path_extractor("$.data.items[*]", false).await;
+        let extracted: Vec<Record> = extractor.map(ITEMS.to_vec()).await.unwrap();
+        assert_eq!(records(), extracted);
+    }
+
+    #[tokio::test]
+    async fn test_json_path_extract_split() {
+        let mut extractor = json_path_extractor("$.data.items", true).await;
+        let extracted: Vec<Record> = extractor.map(ITEMS.to_vec()).await.unwrap();
+        assert_eq!(records(), extracted);
+        // selected array kept as one record without split
+        let mut extractor = json_path_extractor("$.data.items", false).await;
+        let extracted: Vec<Vec<Record>> = extractor.map(ITEMS.to_vec()).await.unwrap();
+        assert_eq!(vec![records()], extracted);
+    }
+
+    #[tokio::test]
+    async fn test_json_path_miss() {
+        let mut extractor = json_path_extractor("$.data.missing[*]", false).await;
+        let extracted: Vec<Record> = extractor.map(ITEMS.to_vec()).await.unwrap();
+        assert!(extracted.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_json_path_invalid() {
+        let config = JsonPathExtractorConfig {
+            path: "$.data[".to_owned(),
+            split: None,
+        };
+        assert!(JsonPathExtractor::from_config(config).await.is_err());
     }
 }
@@ -0,0 +1,219 @@
+use crate::deserialize_line;
+use async_trait::async_trait;
+use pipebase::{
+    common::{ConfigInto, FromConfig, FromPath},
+    stream::{FileStreamReader, Stream},
+};
+use serde::{de::DeserializeOwned, Deserialize};
+use std::io::BufRead;
+use std::path::Path;
+use tokio::sync::mpsc::Sender;
+
+/// Deserialize and send records line by line, blank lines skipped
+async fn send_lines<R, U>(mut reader: R, tx: &Sender<U>) -> anyhow::Result<()>
+where
+    R: BufRead + Send,
+    U: DeserializeOwned + Send + Sync + 'static,
+{
+    let mut line: Vec<u8> = vec![];
+    loop {
+        line.clear();
+        if reader.read_until(b'\n', &mut line)? == 0 {
+            // EOF
+            return Ok(());
+        }
+        if line.iter().all(u8::is_ascii_whitespace) {
+            continue;
+        }
+        let u: U = deserialize_line(&line)?;
+        tx.send(u).await?;
+    }
+}
+
+#[derive(Deserialize)]
+pub struct NdjsonReaderConfig {}
+
+#[async_trait]
+impl FromPath for NdjsonReaderConfig {
+    async fn from_path<P>(_path: P) -> anyhow::Result<Self>
+    where
+        P: AsRef<Path> + Send,
+    {
+        Ok(NdjsonReaderConfig {})
+    }
+}
+
+impl<U> ConfigInto<NdjsonReader<U>> for NdjsonReaderConfig {}
+
+/// Stream records from newline delimited json payload line by line, e.g request body
+pub struct NdjsonReader<U> {
+    tx: Option<Sender<U>>,
+}
+
+#[async_trait]
+impl<U> FromConfig<NdjsonReaderConfig> for NdjsonReader<U> {
+    async fn from_config(_config: NdjsonReaderConfig) -> anyhow::Result<Self> {
+        Ok(NdjsonReader { tx: None })
+    }
+}
+
+/// # Parameters
+/// * Vec<u8>: input
+/// * U: output, record
+#[async_trait]
+impl<U> Stream<Vec<u8>, U, NdjsonReaderConfig> for NdjsonReader<U>
+where
+    U: DeserializeOwned + Send + Sync + 'static,
+{
+    async fn stream(&mut self, bytes: Vec<u8>) -> anyhow::Result<()> {
+        let tx = self.tx.as_ref().unwrap();
+        send_lines(bytes.as_slice(), tx).await
+    }
+
+    fn set_sender(&mut self, sender: Sender<U>) {
+        self.tx = Some(sender)
+    }
+}
+
+#[derive(Deserialize)]
+pub struct NdjsonFileReaderConfig {}
+
+#[async_trait]
+impl FromPath for NdjsonFileReaderConfig {
+    async fn from_path<P>(_path: P) -> anyhow::Result<Self>
+    where
+        P: AsRef<Path> + Send,
+    {
+        Ok(NdjsonFileReaderConfig {})
+    }
+}
+
+impl<U> ConfigInto<NdjsonFileReader<U>> for NdjsonFileReaderConfig {}
+
+/// Stream records from newline delimited json file line by line,
+/// `.gz` and `.zst` files are decompressed
+pub struct NdjsonFileReader<U> {
+    tx: Option<Sender<U>>,
+}
+
+#[async_trait]
+impl<U> FromConfig<NdjsonFileReaderConfig> for NdjsonFileReader<U> {
+    async fn from_config(_config: NdjsonFileReaderConfig) -> anyhow::Result<Self> {
+        Ok(NdjsonFileReader { tx: None })
+    }
+}
+
+#[async_trait]
+impl<U> FileStreamReader for NdjsonFileReader<U>
+where
+    U: DeserializeOwned + Send + Sync + 'static,
+{
+    async fn stream_file<P>(&self, path: P) -> anyhow::Result<()>
+    where
+        P: AsRef<Path> + Send,
+    {
+        let reader = Self::new_reader(path)?;
+        let tx = self.tx.as_ref().unwrap();
+        send_lines(reader, tx).await
+    }
+}
+
+/// # Parameters
+/// * P: input, file path
+/// * U: output, record
+#[async_trait]
+impl<P, U> Stream<P, U, NdjsonFileReaderConfig> for NdjsonFileReader<U>
+where
+    P: AsRef<Path> + Send + 'static,
+    U: DeserializeOwned + Send + Sync + 'static,
+{
+    async fn stream(&mut self, path: P) -> anyhow::Result<()> {
+        self.stream_file(path).await
+    }
+
+    fn set_sender(&mut self, sender: Sender<U>) {
+        self.tx = Some(sender)
+    }
+}
+
+#[cfg(test)]
+mod ndjson_streamer_tests {
+
+    use crate::*;
+    use pipebase::prelude::*;
+    use pipebase::testing::*;
+    use serde::Deserialize;
+
+    #[derive(Clone, Debug, Deserialize, PartialEq)]
+    struct Record {
+        id: u32,
+        name: String,
+    }
+
+    #[tokio::test]
+    async fn test_ndjson_streamer() {
+        let config = config!(NdjsonReaderConfig);
+        let pipe = streamer!("ndjson_streamer");
+        let outputs: PipeOutputs<Record> = run_pipe_with_records(
+            pipe,
+            config,
+            vec![
+                b"{\"id\":1,\"name\":\"foo\"}\n\n{\"id\":2,\"name\":\"bar\"}\nnot json\n".to_vec(),
+            ],
+        )
+        .await
+        .unwrap();
+        // records before malformed line are sent
+        let expected = vec![
+            Record {
+                id: 1,
+                name: "foo".to_owned(),
+            },
+            Record {
+                id: 2,
+                name: "bar".to_owned(),
+            },
+        ];
+        assert_eq!(&expected, outputs.get_records());
+    }
+}
+
+#[cfg(test)]
+mod ndjson_file_streamer_tests {
+
+    use crate::*;
+    use pipebase::prelude::*;
+    use pipebase::testing::*;
+    use serde::Deserialize;
+    use std::path::PathBuf;
+
+    #[derive(Clone, Debug, Deserialize, PartialEq)]
+    struct Record {
+        id: u32,
+        name: String,
+    }
+
+    #[tokio::test]
+    async fn test_ndjson_file_streamer() {
+        let config = config!(NdjsonFileReaderConfig);
+        let pipe = streamer!("ndjson_file_streamer");
+        let outputs: PipeOutputs<Record> = run_pipe_with_records(
+            pipe,
+            config,
+            vec![PathBuf::from("resources/test_file_stream/records.ndjson")],
+        )
+        .await
+        .unwrap();
+        let expected = vec![
+            Record {
+                id: 1,
+                name: "foo".to_owned(),
+            },
+            Record {
+                id: 2,
+                name: "bar".to_owned(),
+            },
+        ];
+        assert_eq!(&expected, outputs.get_records());
+    }
+}
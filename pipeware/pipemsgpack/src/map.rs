@@ -0,0 +1,230 @@
+use async_trait::async_trait;
+use pipebase::{
+    common::{ConfigFormat, ConfigInto, FromConfig, FromPath, GroupAs, Pair},
+    map::Map,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::path::Path;
+
+#[derive(Default, Deserialize)]
+pub struct MsgpackSerConfig {
+    /// Encode struct as map with field names, as array if false, named by default
+    pub named: Option<bool>,
+}
+
+#[async_trait]
+impl FromPath for MsgpackSerConfig {
+    async fn from_path<P>(path: P) -> anyhow::Result<Self>
+    where
+        P: AsRef<Path> + Send,
+    {
+        if path.as_ref().as_os_str().is_empty() {
+            return Ok(MsgpackSerConfig::default());
+        }
+        let format = ConfigFormat::from_path(&path);
+        let bytes = std::fs::read(path)?;
+        format.deserialize(&bytes)
+    }
+}
+
+impl ConfigInto<MsgpackSer> for MsgpackSerConfig {}
+
+pub struct MsgpackSer {
+    named: bool,
+}
+
+#[async_trait]
+impl FromConfig<MsgpackSerConfig> for MsgpackSer {
+    async fn from_config(config: MsgpackSerConfig) -> anyhow::Result<Self> {
+        Ok(MsgpackSer {
+            named: config.named.unwrap_or(true),
+        })
+    }
+}
+
+impl MsgpackSer {
+    fn serialize<T: Serialize>(t: &T, named: bool) -> anyhow::Result<Vec<u8>> {
+        let r = match named {
+            true => rmp_serde::to_vec_named(t),
+            false => rmp_serde::to_vec(t),
+        };
+        match r {
+            Ok(r) => Ok(r),
+            Err(err) => Err(err.into()),
+        }
+    }
+}
+
+/// # Parameters
+/// * T: input
+/// * Vec<u8>: output
+#[async_trait]
+impl<T> Map<T, Vec<u8>, MsgpackSerConfig> for MsgpackSer
+where
+    T: Serialize + Send + Sync + 'static,
+{
+    async fn map(&mut self, t: T) -> anyhow::Result<Vec<u8>> {
+        MsgpackSer::serialize(&t, self.named)
+    }
+}
+
+#[derive(Default, Deserialize)]
+pub struct MsgpackRecordSerConfig {
+    /// Encode struct as map with field names, as array if false, named by default
+    pub named: Option<bool>,
+}
+
+#[async_trait]
+impl FromPath for MsgpackRecordSerConfig {
+    async fn from_path<P>(path: P) -> anyhow::Result<Self>
+    where
+        P: AsRef<Path> + Send,
+    {
+        if path.as_ref().as_os_str().is_empty() {
+            return Ok(MsgpackRecordSerConfig::default());
+        }
+        let format = ConfigFormat::from_path(&path);
+        let bytes = std::fs::read(path)?;
+        format.deserialize(&bytes)
+    }
+}
+
+impl ConfigInto<MsgpackRecordSer> for MsgpackRecordSerConfig {}
+
+pub struct MsgpackRecordSer {
+    named: bool,
+}
+
+#[async_trait]
+impl FromConfig<MsgpackRecordSerConfig> for MsgpackRecordSer {
+    async fn from_config(config: MsgpackRecordSerConfig) -> anyhow::Result<Self> {
+        Ok(MsgpackRecordSer {
+            named: config.named.unwrap_or(true),
+        })
+    }
+}
+
+impl MsgpackRecordSer {
+    fn serialize<K, R>(record: &R, named: bool) -> anyhow::Result<Pair<K, Vec<u8>>>
+    where
+        R: GroupAs<K> + Serialize,
+    {
+        let bytes = MsgpackSer::serialize(record, named)?;
+        let key = record.group();
+        Ok(Pair::new(key, bytes))
+    }
+}
+
+/// # Parameters
+/// * R: input, record
+/// * Pair<K, Vec<u8>>: output, record key and bytes
+#[async_trait]
+impl<K, R> Map<R, Pair<K, Vec<u8>>, MsgpackRecordSerConfig> for MsgpackRecordSer
+where
+    R: GroupAs<K> + Serialize + Send + 'static,
+{
+    async fn map(&mut self, data: R) -> anyhow::Result<Pair<K, Vec<u8>>> {
+        Self::serialize(&data, self.named)
+    }
+}
+
+#[derive(Deserialize)]
+pub struct MsgpackDeserConfig {}
+
+#[async_trait]
+impl FromPath for MsgpackDeserConfig {
+    async fn from_path<P>(_path: P) -> anyhow::Result<Self>
+    where
+        P: AsRef<Path> + Send,
+    {
+        Ok(MsgpackDeserConfig {})
+    }
+}
+
+impl ConfigInto<MsgpackDeser> for MsgpackDeserConfig {}
+
+pub struct MsgpackDeser {}
+
+#[async_trait]
+impl FromConfig<MsgpackDeserConfig> for MsgpackDeser {
+    async fn from_config(_config: MsgpackDeserConfig) -> anyhow::Result<Self> {
+        Ok(MsgpackDeser {})
+    }
+}
+
+impl MsgpackDeser {
+    fn deserialize<T: DeserializeOwned>(bytes: &[u8]) -> anyhow::Result<T> {
+        let t: T = rmp_serde::from_slice::<T>(bytes)?;
+        Ok(t)
+    }
+}
+
+/// # Parameters
+/// * Vec<u8>: input
+/// * T: output
+#[async_trait]
+impl<T> Map<Vec<u8>, T, MsgpackDeserConfig> for MsgpackDeser
+where
+    T: DeserializeOwned + Sync,
+{
+    async fn map(&mut self, bytes: Vec<u8>) -> anyhow::Result<T> {
+        MsgpackDeser::deserialize(bytes.as_slice())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::*;
+    use pipebase::common::{FromConfig, GroupAs, LeftRight, Pair};
+    use pipebase::map::Map;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+    struct Reading {
+        device: String,
+        value: f64,
+    }
+
+    impl GroupAs<String> for Reading {
+        fn group(&self) -> String {
+            self.device.to_owned()
+        }
+    }
+
+    fn reading() -> Reading {
+        Reading {
+            device: "sensor-1".to_owned(),
+            value: 21.5,
+        }
+    }
+
+    async fn round_trip(named: bool) {
+        let mut ser = MsgpackSer::from_config(MsgpackSerConfig { named: Some(named) })
+            .await
+            .unwrap();
+        let bytes = ser.map(reading()).await.unwrap();
+        let mut deser = MsgpackDeser::from_config(MsgpackDeserConfig {})
+            .await
+            .unwrap();
+        let actual: Reading = deser.map(bytes).await.unwrap();
+        assert_eq!(reading(), actual)
+    }
+
+    #[tokio::test]
+    async fn test_msgpack_ser_deser() {
+        round_trip(true).await;
+        round_trip(false).await;
+    }
+
+    #[tokio::test]
+    async fn test_msgpack_record_ser() {
+        let mut ser = MsgpackRecordSer::from_config(MsgpackRecordSerConfig::default())
+            .await
+            .unwrap();
+        let pair: Pair<String, Vec<u8>> = ser.map(reading()).await.unwrap();
+        assert_eq!("sensor-1", pair.left().as_str());
+        let actual: Reading = rmp_serde::from_slice(pair.right()).unwrap();
+        assert_eq!(reading(), actual)
+    }
+}
@@ -1,9 +1,17 @@
-use chrono::{Datelike, Duration, Timelike};
-use mysql_async::{prelude::*, OptsBuilder, Params, SslOpts};
-use pipebase::common::{IntoAttributes, Render, Value};
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, Timelike};
+use mysql_async::{
+    consts::ColumnType as MySQLColumnType, prelude::*, Column, Conn, OptsBuilder, Params,
+    PoolConstraints, PoolOpts, Row, SslOpts, TxOpts,
+};
+use pipebase::common::{
+    Backoff, Column as SchemaColumn, ColumnType, IntoAttributes, PoolConfig, Render, Value,
+};
 use serde::Deserialize;
 use std::collections::HashMap;
 
+// character set of binary string and non string columns
+const BINARY_CHARSET: u16 = 63;
+
 #[derive(Deserialize)]
 pub struct SslConfig {
     root_cert_path: Option<String>,
@@ -13,23 +21,47 @@ pub struct SslConfig {
 pub struct MySQLClientConfig {
     url: String,
     ssl: Option<SslConfig>,
+    pool: Option<PoolConfig>,
 }
 
 pub struct MySQLClient {
     pool: mysql_async::Pool,
+    backoff: Backoff,
 }
 
 impl MySQLClient {
-    pub fn new(config: MySQLClientConfig) -> Self {
+    pub fn new(config: MySQLClientConfig) -> anyhow::Result<Self> {
         let url = config.url;
         let ssl = config.ssl;
+        let pool = config.pool.unwrap_or_default();
         let opts = match ssl {
             Some(ssl) => OptsBuilder::from_opts(url.as_str()).ssl_opts(Self::new_ssl_opts(ssl)),
             None => OptsBuilder::from_opts(url.as_str()),
         };
-        MySQLClient {
+        let opts = opts.pool_opts(Self::new_pool_opts(&pool)?);
+        Ok(MySQLClient {
             pool: mysql_async::Pool::new(opts),
-        }
+            backoff: pool.get_backoff(),
+        })
+    }
+
+    fn new_pool_opts(pool: &PoolConfig) -> anyhow::Result<PoolOpts> {
+        let size = pool.get_size();
+        let constraints = match PoolConstraints::new(1, size) {
+            Some(constraints) => constraints,
+            None => anyhow::bail!("invalid pool size {}", size),
+        };
+        Ok(PoolOpts::default().with_constraints(constraints))
+    }
+
+    /// Get connection alive, reconnect with backoff
+    async fn get_conn(&self) -> anyhow::Result<Conn> {
+        self.backoff.retry(|| Self::connect(&self.pool)).await
+    }
+
+    // pool recycles broken connection, no ping per checkout
+    async fn connect(pool: &mysql_async::Pool) -> anyhow::Result<Conn> {
+        Ok(pool.get_conn().await?)
     }
 
     fn new_ssl_opts(ssl: SslConfig) -> SslOpts {
@@ -41,37 +73,207 @@ impl MySQLClient {
     where
         R: Render,
     {
-        let mut conn = self.pool.get_conn().await?;
+        let mut conn = self.get_conn().await?;
         let statement = r.render();
         conn.exec_drop(statement, mysql_async::Params::Empty)
             .await?;
         Ok(())
     }
 
+    /// Execute prepared statement per item in one transaction, rollback on failure
     pub async fn prepare_execute<A>(&self, statement: String, items: Vec<A>) -> anyhow::Result<()>
     where
         A: IntoAttributes,
     {
-        let params: Vec<Params> = items.into_iter().map(Self::mysql_params).collect();
-        let mut conn = self.pool.get_conn().await?;
-        conn.exec_batch(statement, params).await?;
-        Ok(())
+        let params = items
+            .into_iter()
+            .map(|item| Self::mysql_params(item.into_attributes()))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        self.execute_batch(statement, params).await
     }
 
-    fn mysql_params<A>(item: A) -> Params
+    /// Insert or update on duplicate key in one transaction, columns are attribute names
+    pub async fn upsert<A>(&self, table: &str, keys: &[String], items: Vec<A>) -> anyhow::Result<()>
     where
         A: IntoAttributes,
     {
-        let attributes: HashMap<String, Value> = item.into_attributes();
-        let params: HashMap<String, mysql_async::Value> = attributes
+        let rows: Vec<Vec<(String, Value)>> = items
+            .into_iter()
+            .map(IntoAttributes::into_attribute_tuples)
+            .collect();
+        let columns = match rows.first() {
+            Some(row) => row
+                .iter()
+                .map(|(name, _)| name.to_owned())
+                .collect::<Vec<_>>(),
+            None => return Ok(()),
+        };
+        let statement = upsert_statement(table, &columns, keys);
+        let params = rows
+            .into_iter()
+            .map(Self::mysql_params)
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        self.execute_batch(statement, params).await
+    }
+
+    async fn execute_batch(&self, statement: String, params: Vec<Params>) -> anyhow::Result<()> {
+        let mut conn = self.get_conn().await?;
+        // transaction rollback on drop if not committed
+        let mut transaction = conn.start_transaction(TxOpts::default()).await?;
+        transaction.exec_batch(statement, params).await?;
+        transaction.commit().await?;
+        Ok(())
+    }
+
+    /// Validate statement against record columns, fail at startup instead of first write
+    /// if statement can not be prepared or a named param is not a record column
+    pub async fn validate_params(
+        &self,
+        statement: &str,
+        columns: &[SchemaColumn],
+    ) -> anyhow::Result<()> {
+        let mut conn = self.get_conn().await?;
+        conn.prep(statement).await?;
+        for param in named_params(statement) {
+            if !columns.iter().any(|column| column.name == param) {
+                anyhow::bail!("statement param ':{}' is not a record column", param)
+            }
+        }
+        Ok(())
+    }
+
+    /// Validate upsert statement of record columns
+    pub async fn validate_upsert(
+        &self,
+        table: &str,
+        columns: &[SchemaColumn],
+        keys: &[String],
+    ) -> anyhow::Result<()> {
+        let names: Vec<String> = columns
+            .iter()
+            .map(|column| column.name.to_owned())
+            .collect();
+        self.validate_params(&upsert_statement(table, &names, keys), columns)
+            .await
+    }
+
+    /// Create table if not exists and add missing columns
+    pub async fn create_table(
+        &self,
+        table: &str,
+        columns: &[SchemaColumn],
+        keys: &[String],
+    ) -> anyhow::Result<()> {
+        let mut conn = self.get_conn().await?;
+        conn.query_drop(create_table_statement(table, columns, keys))
+            .await?;
+        let (schema, name) = match table.rsplit_once('.') {
+            Some((schema, name)) => (Some(schema), name),
+            None => (None, table),
+        };
+        let existing: Vec<(String,)> = conn
+            .exec(
+                "SELECT COLUMN_NAME FROM information_schema.COLUMNS WHERE TABLE_SCHEMA = COALESCE(?, DATABASE()) AND TABLE_NAME = ?",
+                (schema, name),
+            )
+            .await?;
+        let existing: Vec<String> = existing.into_iter().map(|(name,)| name).collect();
+        for column in columns {
+            if existing
+                .iter()
+                .any(|name| name.eq_ignore_ascii_case(&column.name))
+            {
+                continue;
+            }
+            conn.query_drop(add_column_statement(table, column)).await?;
+        }
+        Ok(())
+    }
+
+    /// Query rows as `Value::Attributes` keyed by column name
+    pub async fn query(&self, statement: &str, params: Vec<Value>) -> anyhow::Result<Vec<Value>> {
+        let params = params
             .into_iter()
-            .map(|(name, value)| (name, Self::mysql_value(value)))
+            .map(Self::mysql_value)
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let mut conn = self.get_conn().await?;
+        let rows: Vec<Row> = conn.exec(statement, Params::Positional(params)).await?;
+        Ok(rows.into_iter().map(Self::from_mysql_row).collect())
+    }
+
+    fn from_mysql_row(row: Row) -> Value {
+        let columns = row.columns();
+        let attributes: HashMap<String, Value> = columns
+            .iter()
+            .zip(row.unwrap())
+            .map(|(column, value)| {
+                (
+                    column.name_str().into_owned(),
+                    Self::from_mysql_value(value, column),
+                )
+            })
             .collect();
-        Params::Named(params)
+        Value::Attributes(attributes)
     }
 
-    fn mysql_value(value: Value) -> mysql_async::Value {
+    fn from_mysql_value(value: mysql_async::Value, column: &Column) -> Value {
         match value {
+            mysql_async::Value::NULL => Value::Null,
+            mysql_async::Value::Bytes(value) => match column.column_type() {
+                MySQLColumnType::MYSQL_TYPE_DECIMAL
+                | MySQLColumnType::MYSQL_TYPE_NEWDECIMAL
+                | MySQLColumnType::MYSQL_TYPE_JSON => {
+                    Value::String(Some(String::from_utf8_lossy(&value).into_owned()))
+                }
+                _ if column.character_set() == BINARY_CHARSET => Value::UnsignedBytes(value),
+                _ => Value::String(Some(String::from_utf8_lossy(&value).into_owned())),
+            },
+            mysql_async::Value::Int(value) => Value::Long(Some(value)),
+            mysql_async::Value::UInt(value) => Value::UnsignedLong(Some(value)),
+            mysql_async::Value::Float(value) => Value::Float(Some(value)),
+            mysql_async::Value::Double(value) => Value::Double(Some(value)),
+            mysql_async::Value::Date(year, month, day, hour, minute, second, micros) => {
+                // zero date read as null
+                let date = NaiveDate::from_ymd_opt(year.into(), month.into(), day.into());
+                match column.column_type() {
+                    MySQLColumnType::MYSQL_TYPE_DATE => Value::Date(date),
+                    _ => Value::DateTime(date.and_then(|date| {
+                        date.and_hms_micro_opt(hour.into(), minute.into(), second.into(), micros)
+                    })),
+                }
+            }
+            mysql_async::Value::Time(negative, days, hours, minutes, seconds, micros) => {
+                let duration = Duration::days(days.into())
+                    + Duration::hours(hours.into())
+                    + Duration::minutes(minutes.into())
+                    + Duration::seconds(seconds.into())
+                    + Duration::microseconds(micros.into());
+                match negative {
+                    true => Value::Duration(Some(-duration)),
+                    false => Value::Duration(Some(duration)),
+                }
+            }
+        }
+    }
+
+    fn mysql_params<A>(attributes: A) -> anyhow::Result<Params>
+    where
+        A: IntoIterator<Item = (String, Value)>,
+    {
+        let params = attributes
+            .into_iter()
+            .map(|(name, value)| Ok((name, Self::mysql_value(value)?)))
+            .collect::<anyhow::Result<HashMap<String, mysql_async::Value>>>()?;
+        Ok(Params::Named(params))
+    }
+
+    pub(crate) fn mysql_value(value: Value) -> anyhow::Result<mysql_async::Value> {
+        let value = match value {
+            Value::Null => mysql_async::Value::NULL,
+            Value::Bool(value) => match value {
+                Some(value) => mysql_async::Value::Int(value.into()),
+                None => mysql_async::Value::NULL,
+            },
             Value::Integer(value) => match value {
                 Some(value) => mysql_async::Value::Int(value.into()),
                 None => mysql_async::Value::NULL,
@@ -101,52 +303,258 @@ impl MySQLClient {
                 Some(value) => mysql_async::Value::Bytes(value.into()),
                 None => mysql_async::Value::NULL,
             },
+            Value::Date(value) => match value {
+                Some(value) => mysql_async::Value::Date(
+                    value.year() as u16,
+                    value.month() as u8,
+                    value.day() as u8,
+                    0,
+                    0,
+                    0,
+                    0,
+                ),
+                None => mysql_async::Value::NULL,
+            },
             Value::DateTime(value) => match value {
-                Some(value) => {
-                    let date = value.date();
-                    let time = value.time();
-                    mysql_async::Value::Date(
-                        date.year() as u16,
-                        date.month() as u8,
-                        date.day() as u8,
-                        time.hour() as u8,
-                        time.minute() as u8,
-                        time.second() as u8,
-                        0,
-                    )
-                }
+                Some(value) => mysql_datetime(value),
+                None => mysql_async::Value::NULL,
+            },
+            // time with timezone stored as utc datetime
+            Value::LocalTime(value) => match value {
+                Some(value) => mysql_datetime(value.naive_utc()),
+                None => mysql_async::Value::NULL,
+            },
+            Value::UtcTime(value) => match value {
+                Some(value) => mysql_datetime(value.naive_utc()),
                 None => mysql_async::Value::NULL,
             },
             Value::Duration(value) => match value {
-                Some(value) => {
-                    let negative = value < Duration::zero();
-                    let days = absolute_i64(value.num_days());
-                    let hours = absolute_i64(value.num_hours());
-                    let minutes = absolute_i64(value.num_minutes());
-                    let seconds = absolute_i64(value.num_seconds());
-                    let milliseconds = absolute_i64(value.num_milliseconds());
-                    let microseconds = absolute_i64(value.num_microseconds().unwrap_or_default());
-                    let microseconds = milliseconds * 1000 + microseconds;
-                    mysql_async::Value::Time(
-                        negative,
-                        days as u32,
-                        hours as u8,
-                        minutes as u8,
-                        seconds as u8,
-                        microseconds as u32,
-                    )
-                }
+                Some(value) => mysql_time(value),
                 None => mysql_async::Value::NULL,
             },
-            _ => unimplemented!(),
+            // nested values stored as json text
+            value @ (Value::Array(_) | Value::Attributes(_)) => {
+                mysql_async::Value::Bytes(serde_json::to_vec(&value)?)
+            }
+        };
+        Ok(value)
+    }
+}
+
+fn mysql_datetime(value: NaiveDateTime) -> mysql_async::Value {
+    let date = value.date();
+    let time = value.time();
+    mysql_async::Value::Date(
+        date.year() as u16,
+        date.month() as u8,
+        date.day() as u8,
+        time.hour() as u8,
+        time.minute() as u8,
+        time.second() as u8,
+        time.nanosecond() / 1000,
+    )
+}
+
+fn mysql_time(value: Duration) -> mysql_async::Value {
+    let negative = value < Duration::zero();
+    let value = match negative {
+        true => -value,
+        false => value,
+    };
+    let days = value.num_days();
+    let hours = value.num_hours() - days * 24;
+    let minutes = value.num_minutes() - value.num_hours() * 60;
+    let seconds = value.num_seconds() - value.num_minutes() * 60;
+    let microseconds = (value - Duration::seconds(value.num_seconds()))
+        .num_microseconds()
+        .unwrap_or_default();
+    mysql_async::Value::Time(
+        negative,
+        days as u32,
+        hours as u8,
+        minutes as u8,
+        seconds as u8,
+        microseconds as u32,
+    )
+}
+
+/// `INSERT ... ON DUPLICATE KEY UPDATE` non key columns, keys unchanged if all columns are keys
+fn upsert_statement(table: &str, columns: &[String], keys: &[String]) -> String {
+    let placeholders: Vec<String> = columns
+        .iter()
+        .map(|column| format!(":{}", column))
+        .collect();
+    let mut updates: Vec<String> = columns
+        .iter()
+        .filter(|column| !keys.contains(column))
+        .map(|column| format!("{} = VALUES({})", quote(column), quote(column)))
+        .collect();
+    if updates.is_empty() {
+        updates = keys
+            .iter()
+            .take(1)
+            .map(|key| format!("{} = {}", quote(key), quote(key)))
+            .collect();
+    }
+    let columns: Vec<String> = columns.iter().map(|column| quote(column)).collect();
+    format!(
+        "INSERT INTO {} ({}) VALUES ({}) ON DUPLICATE KEY UPDATE {}",
+        table,
+        columns.join(", "),
+        placeholders.join(", "),
+        updates.join(", ")
+    )
+}
+
+/// Names of `:name` params, quoted text skipped
+fn named_params(statement: &str) -> Vec<&str> {
+    let mut params = Vec::new();
+    let mut quote: Option<char> = None;
+    let mut chars = statement.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if q == c => quote = None,
+            (Some(_), _) => (),
+            (None, '\'') | (None, '"') | (None, '`') => quote = Some(c),
+            (None, ':') => {
+                let start = i + 1;
+                let mut end = start;
+                while let Some(&(j, c)) = chars.peek() {
+                    if !(c.is_ascii_alphanumeric() || c == '_') {
+                        break;
+                    }
+                    end = j + c.len_utf8();
+                    chars.next();
+                }
+                if end > start {
+                    params.push(&statement[start..end])
+                }
+            }
+            _ => (),
         }
     }
+    params
+}
+
+/// Quote column name, e.g reserved word `key`
+fn quote(name: &str) -> String {
+    format!("`{}`", name)
+}
+
+fn column_type(ty: &ColumnType, key: bool) -> &'static str {
+    match ty {
+        ColumnType::Bool => "BOOLEAN",
+        ColumnType::UnsignedInteger => "INT UNSIGNED",
+        ColumnType::Integer => "INT",
+        ColumnType::UnsignedLong => "BIGINT UNSIGNED",
+        ColumnType::Long => "BIGINT",
+        ColumnType::Float => "FLOAT",
+        ColumnType::Double => "DOUBLE",
+        // key prefix length required for text and blob
+        ColumnType::String if key => "VARCHAR(255)",
+        ColumnType::String => "TEXT",
+        ColumnType::Date => "DATE",
+        ColumnType::DateTime | ColumnType::LocalTime | ColumnType::UtcTime => "DATETIME(6)",
+        ColumnType::Duration => "TIME(6)",
+        ColumnType::UnsignedBytes if key => "VARBINARY(255)",
+        ColumnType::UnsignedBytes => "BLOB",
+        ColumnType::Array(_) | ColumnType::Attributes(_) => "JSON",
+    }
+}
+
+fn column_definition(column: &SchemaColumn, keys: &[String]) -> String {
+    format!(
+        "{} {}",
+        quote(&column.name),
+        column_type(&column.ty, keys.contains(&column.name))
+    )
+}
+
+/// `CREATE TABLE IF NOT EXISTS` with primary key if any
+fn create_table_statement(table: &str, columns: &[SchemaColumn], keys: &[String]) -> String {
+    let mut definitions: Vec<String> = columns
+        .iter()
+        .map(|column| column_definition(column, keys))
+        .collect();
+    if !keys.is_empty() {
+        let keys: Vec<String> = keys.iter().map(|key| quote(key)).collect();
+        definitions.push(format!("PRIMARY KEY ({})", keys.join(", ")))
+    }
+    format!(
+        "CREATE TABLE IF NOT EXISTS {} ({})",
+        table,
+        definitions.join(", ")
+    )
+}
+
+fn add_column_statement(table: &str, column: &SchemaColumn) -> String {
+    format!(
+        "ALTER TABLE {} ADD COLUMN {}",
+        table,
+        column_definition(column, &[])
+    )
 }
 
-// return absolute value of i64
-fn absolute_i64(value: i64) -> u64 {
-    if value > 0 {
-        return value as u64;
+#[cfg(test)]
+mod tests {
+
+    use super::{add_column_statement, create_table_statement, named_params, upsert_statement};
+    use pipebase::common::{Column, ColumnType};
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn test_upsert_statement() {
+        let columns = names(&["id", "name", "score"]);
+        assert_eq!(
+            "INSERT INTO records (`id`, `name`, `score`) VALUES (:id, :name, :score) ON DUPLICATE KEY UPDATE `name` = VALUES(`name`), `score` = VALUES(`score`)",
+            upsert_statement("records", &columns, &names(&["id"]))
+        );
+        assert_eq!(
+            "INSERT INTO records (`id`, `name`, `score`) VALUES (:id, :name, :score) ON DUPLICATE KEY UPDATE `id` = `id`",
+            upsert_statement("records", &columns, &columns)
+        );
+    }
+
+    #[test]
+    fn test_named_params() {
+        assert_eq!(
+            vec!["id", "name"],
+            named_params("INSERT INTO records (id, name, note) VALUES (:id, :name, ':note')")
+        );
+        assert_eq!(
+            vec!["id", "name", "id"],
+            named_params(
+                "INSERT INTO `a:b` (id, name) VALUES (:id, :name) ON DUPLICATE KEY UPDATE id = :id"
+            )
+        );
+    }
+
+    #[test]
+    fn test_create_table_statement() {
+        let columns = vec![
+            Column {
+                name: "id".to_owned(),
+                ty: ColumnType::String,
+            },
+            Column {
+                name: "name".to_owned(),
+                ty: ColumnType::String,
+            },
+            Column {
+                name: "tags".to_owned(),
+                ty: ColumnType::Array(Box::new(ColumnType::String)),
+            },
+        ];
+        assert_eq!(
+            "CREATE TABLE IF NOT EXISTS records (`id` VARCHAR(255), `name` TEXT, `tags` JSON, PRIMARY KEY (`id`))",
+            create_table_statement("records", &columns, &names(&["id"]))
+        );
+        assert_eq!(
+            "ALTER TABLE records ADD COLUMN `tags` JSON",
+            add_column_statement("records", &columns[2])
+        );
     }
-    (-value) as u64
 }
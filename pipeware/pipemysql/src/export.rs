@@ -1,10 +1,11 @@
 use crate::client::{MySQLClient, MySQLClientConfig};
 use async_trait::async_trait;
 use pipebase::{
-    common::{ConfigInto, FromConfig, FromPath, IntoAttributes, Render},
+    common::{ConfigInto, CreateTableConfig, FromConfig, FromPath, IntoAttributes, Render, Schema},
     export::Export,
 };
 use serde::Deserialize;
+use std::marker::PhantomData;
 
 #[derive(Deserialize)]
 pub struct MySQLWriterConfig {
@@ -23,7 +24,7 @@ pub struct MySQLWriter {
 impl FromConfig<MySQLWriterConfig> for MySQLWriter {
     async fn from_config(config: MySQLWriterConfig) -> anyhow::Result<Self> {
         Ok(MySQLWriter {
-            client: MySQLClient::new(config.client),
+            client: MySQLClient::new(config.client)?,
         })
     }
 }
@@ -57,12 +58,14 @@ pub struct MySQLPreparedWriter {
 impl FromConfig<MySQLPreparedWriterConfig> for MySQLPreparedWriter {
     async fn from_config(config: MySQLPreparedWriterConfig) -> anyhow::Result<Self> {
         Ok(MySQLPreparedWriter {
-            client: MySQLClient::new(config.client),
+            client: MySQLClient::new(config.client)?,
             statement: config.statement,
         })
     }
 }
 
+/// # Parameters
+/// * Vec<T>: input, records with attributes bound as statement params
 #[async_trait]
 impl<T> Export<Vec<T>, MySQLPreparedWriterConfig> for MySQLPreparedWriter
 where
@@ -73,3 +76,134 @@ where
         self.client.prepare_execute(statement, items).await
     }
 }
+
+#[derive(Deserialize)]
+pub struct MySQLSchemaPreparedWriterConfig {
+    #[serde(flatten)]
+    writer: MySQLPreparedWriterConfig,
+    /// Create table from record schema if not exists
+    create_table: Option<CreateTableConfig>,
+    /// Validate record columns against statement params at startup, true if table created
+    validate: Option<bool>,
+}
+
+impl FromPath for MySQLSchemaPreparedWriterConfig {}
+
+impl<T> ConfigInto<MySQLSchemaPreparedWriter<T>> for MySQLSchemaPreparedWriterConfig where T: Schema {}
+
+/// Prepared writer creating table from schema of record type at startup
+pub struct MySQLSchemaPreparedWriter<T> {
+    writer: MySQLPreparedWriter,
+    _record: PhantomData<T>,
+}
+
+#[async_trait]
+impl<T> FromConfig<MySQLSchemaPreparedWriterConfig> for MySQLSchemaPreparedWriter<T>
+where
+    T: Schema,
+{
+    async fn from_config(config: MySQLSchemaPreparedWriterConfig) -> anyhow::Result<Self> {
+        let client = MySQLClient::new(config.writer.client)?;
+        let created = match config.create_table {
+            Some(create_table) => {
+                client
+                    .create_table(&create_table.table, &T::schema(), &create_table.get_keys())
+                    .await?;
+                true
+            }
+            None => false,
+        };
+        // table may be created by upstream pipe after startup
+        if config.validate.unwrap_or(created) {
+            client
+                .validate_params(&config.writer.statement, &T::schema())
+                .await?;
+        }
+        Ok(MySQLSchemaPreparedWriter {
+            writer: MySQLPreparedWriter {
+                client,
+                statement: config.writer.statement,
+            },
+            _record: PhantomData,
+        })
+    }
+}
+
+/// # Parameters
+/// * Vec<T>: input, records with attributes bound as statement params
+#[async_trait]
+impl<T> Export<Vec<T>, MySQLSchemaPreparedWriterConfig> for MySQLSchemaPreparedWriter<T>
+where
+    T: IntoAttributes + Schema + Send + Sync + 'static,
+{
+    async fn export(&mut self, items: Vec<T>) -> anyhow::Result<()> {
+        let statement = self.writer.statement.to_owned();
+        self.writer.client.prepare_execute(statement, items).await
+    }
+}
+
+#[derive(Deserialize)]
+pub struct MySQLUpsertWriterConfig {
+    client: MySQLClientConfig,
+    table: String,
+    /// Columns of primary key or unique index, not updated on duplicate key
+    keys: Vec<String>,
+    /// Create table with keys as primary key, false by default
+    create_table: Option<bool>,
+    /// Validate record columns against table at startup, true if table created
+    validate: Option<bool>,
+}
+
+impl FromPath for MySQLUpsertWriterConfig {}
+
+impl<T> ConfigInto<MySQLUpsertWriter<T>> for MySQLUpsertWriterConfig where T: Schema {}
+
+/// Insert or update batch in one transaction
+pub struct MySQLUpsertWriter<T> {
+    client: MySQLClient,
+    table: String,
+    keys: Vec<String>,
+    _record: PhantomData<T>,
+}
+
+#[async_trait]
+impl<T> FromConfig<MySQLUpsertWriterConfig> for MySQLUpsertWriter<T>
+where
+    T: Schema,
+{
+    async fn from_config(config: MySQLUpsertWriterConfig) -> anyhow::Result<Self> {
+        if config.keys.is_empty() {
+            anyhow::bail!("upsert keys of table '{}' not configured", config.table)
+        }
+        let client = MySQLClient::new(config.client)?;
+        let created = config.create_table.unwrap_or_default();
+        if created {
+            client
+                .create_table(&config.table, &T::schema(), &config.keys)
+                .await?;
+        }
+        if config.validate.unwrap_or(created) {
+            client
+                .validate_upsert(&config.table, &T::schema(), &config.keys)
+                .await?;
+        }
+        Ok(MySQLUpsertWriter {
+            client,
+            table: config.table,
+            keys: config.keys,
+            _record: PhantomData,
+        })
+    }
+}
+
+/// # Parameters
+/// * Vec<T>: input, records with attribute names as column names
+#[async_trait]
+impl<T> Export<Vec<T>, MySQLUpsertWriterConfig> for MySQLUpsertWriter<T>
+where
+    T: IntoAttributes + Schema + Send + Sync + 'static,
+{
+    async fn export(&mut self, items: Vec<T>) -> anyhow::Result<()> {
+        self.client.upsert(&self.table, &self.keys, items).await
+    }
+}
@@ -0,0 +1,281 @@
+use arrow_json::{reader::infer_json_schema_from_iterator, ReaderBuilder};
+use arrow_schema::{Schema as ArrowSchema, SchemaRef};
+use async_trait::async_trait;
+use bytes::Bytes;
+use parquet::{
+    arrow::{parquet_to_arrow_schema, ArrowWriter},
+    basic::{Compression as ParquetCodec, GzipLevel},
+    file::{
+        properties::WriterProperties,
+        reader::{FileReader, SerializedFileReader},
+    },
+    schema::{parser::parse_message_type, types::SchemaDescriptor},
+};
+use pipebase::{
+    common::{ConfigFormat, ConfigInto, FromConfig, FromPath},
+    map::Map,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{path::Path, sync::Arc};
+
+const DEFAULT_ROW_GROUP_SIZE: usize = 1024 * 1024;
+
+#[derive(Clone, Copy, Deserialize)]
+pub enum Compression {
+    Uncompressed,
+    Snappy,
+    Gzip,
+    Lz4,
+}
+
+fn get_codec(compression: Compression) -> ParquetCodec {
+    match compression {
+        Compression::Uncompressed => ParquetCodec::UNCOMPRESSED,
+        Compression::Snappy => ParquetCodec::SNAPPY,
+        Compression::Gzip => ParquetCodec::GZIP(GzipLevel::default()),
+        Compression::Lz4 => ParquetCodec::LZ4_RAW,
+    }
+}
+
+/// Parse parquet message type into arrow schema
+fn parse_schema(schema: &str) -> anyhow::Result<SchemaRef> {
+    let message_type = parse_message_type(schema)?;
+    let descriptor = SchemaDescriptor::new(Arc::new(message_type));
+    let schema = parquet_to_arrow_schema(&descriptor, None)?;
+    Ok(Arc::new(schema))
+}
+
+/// Infer arrow schema from json representation of records
+fn infer_schema(values: &[serde_json::Value]) -> anyhow::Result<SchemaRef> {
+    let schema: ArrowSchema = infer_json_schema_from_iterator(values.iter().map(Ok))?;
+    Ok(Arc::new(schema))
+}
+
+/// Read records in row group
+pub(crate) fn read_row_group<R, U>(reader: &R, row_group: usize) -> anyhow::Result<Vec<U>>
+where
+    R: FileReader,
+    U: DeserializeOwned,
+{
+    let row_group = reader.get_row_group(row_group)?;
+    let mut records = Vec::with_capacity(row_group.metadata().num_rows() as usize);
+    for row in row_group.get_row_iter(None)? {
+        let record: U = serde_json::from_value(row?.to_json_value())?;
+        records.push(record);
+    }
+    Ok(records)
+}
+
+#[derive(Default, Deserialize)]
+pub struct ParquetSerConfig {
+    /// Parquet message type, inferred from records if absent
+    schema: Option<String>,
+    /// Uncompressed by default
+    compression: Option<Compression>,
+    /// Max number of rows per row group
+    row_group_size: Option<usize>,
+}
+
+#[async_trait]
+impl FromPath for ParquetSerConfig {
+    async fn from_path<P>(path: P) -> anyhow::Result<Self>
+    where
+        P: AsRef<Path> + Send,
+    {
+        if path.as_ref().as_os_str().is_empty() {
+            return Ok(ParquetSerConfig::default());
+        }
+        let format = ConfigFormat::from_path(&path);
+        let bytes = std::fs::read(path)?;
+        format.deserialize(&bytes)
+    }
+}
+
+impl ConfigInto<ParquetSer> for ParquetSerConfig {}
+
+/// Serialize batch of records into parquet file bytes
+pub struct ParquetSer {
+    schema: Option<SchemaRef>,
+    properties: WriterProperties,
+}
+
+#[async_trait]
+impl FromConfig<ParquetSerConfig> for ParquetSer {
+    async fn from_config(config: ParquetSerConfig) -> anyhow::Result<Self> {
+        let schema = match config.schema {
+            Some(ref schema) => Some(parse_schema(schema)?),
+            None => None,
+        };
+        let compression = config.compression.unwrap_or(Compression::Uncompressed);
+        let properties = WriterProperties::builder()
+            .set_compression(get_codec(compression))
+            .set_max_row_group_size(config.row_group_size.unwrap_or(DEFAULT_ROW_GROUP_SIZE))
+            .build();
+        Ok(ParquetSer { schema, properties })
+    }
+}
+
+impl ParquetSer {
+    fn serialize<T: Serialize>(&self, items: Vec<T>) -> anyhow::Result<Vec<u8>> {
+        let values = items
+            .iter()
+            .map(serde_json::to_value)
+            .collect::<Result<Vec<_>, _>>()?;
+        let schema = match self.schema {
+            Some(ref schema) => schema.to_owned(),
+            None => infer_schema(&values)?,
+        };
+        let mut decoder = ReaderBuilder::new(schema.to_owned())
+            .with_batch_size(values.len().max(1))
+            .build_decoder()?;
+        decoder.serialize(&values)?;
+        let mut writer = ArrowWriter::try_new(Vec::new(), schema, Some(self.properties.clone()))?;
+        while let Some(batch) = decoder.flush()? {
+            writer.write(&batch)?;
+        }
+        Ok(writer.into_inner()?)
+    }
+}
+
+/// # Parameters
+/// * U: input, batch of records
+/// * Vec<u8>: output, parquet file bytes
+#[async_trait]
+impl<T, U> Map<U, Vec<u8>, ParquetSerConfig> for ParquetSer
+where
+    T: Serialize,
+    U: IntoIterator<Item = T> + Send + 'static,
+{
+    async fn map(&mut self, data: U) -> anyhow::Result<Vec<u8>> {
+        self.serialize(data.into_iter().collect())
+    }
+}
+
+#[derive(Deserialize)]
+pub struct ParquetDeserConfig {}
+
+#[async_trait]
+impl FromPath for ParquetDeserConfig {
+    async fn from_path<P>(_path: P) -> anyhow::Result<Self>
+    where
+        P: AsRef<Path> + Send,
+    {
+        Ok(ParquetDeserConfig {})
+    }
+}
+
+impl ConfigInto<ParquetDeser> for ParquetDeserConfig {}
+
+/// Deserialize parquet file bytes into records
+pub struct ParquetDeser {}
+
+#[async_trait]
+impl FromConfig<ParquetDeserConfig> for ParquetDeser {
+    async fn from_config(_config: ParquetDeserConfig) -> anyhow::Result<Self> {
+        Ok(ParquetDeser {})
+    }
+}
+
+impl ParquetDeser {
+    fn deserialize<T: DeserializeOwned>(bytes: Vec<u8>) -> anyhow::Result<Vec<T>> {
+        let reader = SerializedFileReader::new(Bytes::from(bytes))?;
+        let mut records = Vec::new();
+        for row_group in 0..reader.num_row_groups() {
+            records.extend(read_row_group(&reader, row_group)?);
+        }
+        Ok(records)
+    }
+}
+
+/// # Parameters
+/// * Vec<u8>: input, parquet file bytes
+/// * Vec<T>: output, records
+#[async_trait]
+impl<T> Map<Vec<u8>, Vec<T>, ParquetDeserConfig> for ParquetDeser
+where
+    T: DeserializeOwned,
+{
+    async fn map(&mut self, bytes: Vec<u8>) -> anyhow::Result<Vec<T>> {
+        Self::deserialize(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::*;
+    use pipebase::common::{FromConfig, Value};
+    use pipebase::map::Map;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+    struct Record {
+        key: String,
+        value: i64,
+        score: Option<f64>,
+    }
+
+    fn records() -> Vec<Record> {
+        vec![
+            Record {
+                key: "foo".to_owned(),
+                value: 1,
+                score: Some(0.5),
+            },
+            Record {
+                key: "bar".to_owned(),
+                value: 2,
+                score: None,
+            },
+        ]
+    }
+
+    async fn round_trip(config: ParquetSerConfig) -> Vec<Record> {
+        let mut ser = ParquetSer::from_config(config).await.unwrap();
+        let bytes = ser.map(records()).await.unwrap();
+        let mut deser = ParquetDeser::from_config(ParquetDeserConfig {})
+            .await
+            .unwrap();
+        deser.map(bytes).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_parquet_inferred_schema() {
+        let actual = round_trip(ParquetSerConfig {
+            schema: None,
+            compression: Some(Compression::Snappy),
+            row_group_size: Some(1),
+        })
+        .await;
+        assert_eq!(records(), actual)
+    }
+
+    #[tokio::test]
+    async fn test_parquet_configured_schema() {
+        let schema = "message record { required binary key (UTF8); required int64 value; optional double score; }";
+        let actual = round_trip(ParquetSerConfig {
+            schema: Some(schema.to_owned()),
+            compression: Some(Compression::Gzip),
+            row_group_size: None,
+        })
+        .await;
+        assert_eq!(records(), actual)
+    }
+
+    #[tokio::test]
+    async fn test_parquet_value_records() {
+        let values: Vec<Value> = records()
+            .into_iter()
+            .map(|record| serde_json::from_value(serde_json::to_value(record).unwrap()).unwrap())
+            .collect();
+        let mut ser = ParquetSer::from_config(ParquetSerConfig::default())
+            .await
+            .unwrap();
+        let bytes = ser.map(values).await.unwrap();
+        let mut deser = ParquetDeser::from_config(ParquetDeserConfig {})
+            .await
+            .unwrap();
+        let actual: Vec<Record> = deser.map(bytes).await.unwrap();
+        assert_eq!(records(), actual)
+    }
+}
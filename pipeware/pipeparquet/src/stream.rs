@@ -0,0 +1,124 @@
+use crate::read_row_group;
+use async_trait::async_trait;
+use parquet::file::reader::{FileReader, SerializedFileReader};
+use pipebase::{
+    common::{ConfigInto, FromConfig, FromPath},
+    stream::Stream,
+};
+use serde::{de::DeserializeOwned, Deserialize};
+use std::{fs::File, path::Path};
+use tokio::sync::mpsc::Sender;
+
+#[derive(Deserialize)]
+pub struct ParquetFileReaderConfig {}
+
+#[async_trait]
+impl FromPath for ParquetFileReaderConfig {
+    async fn from_path<P>(_path: P) -> anyhow::Result<Self>
+    where
+        P: AsRef<Path> + Send,
+    {
+        Ok(ParquetFileReaderConfig {})
+    }
+}
+
+impl<U> ConfigInto<ParquetFileReader<U>> for ParquetFileReaderConfig {}
+
+/// Stream records of parquet file row group by row group
+pub struct ParquetFileReader<U> {
+    tx: Option<Sender<U>>,
+}
+
+#[async_trait]
+impl<U> FromConfig<ParquetFileReaderConfig> for ParquetFileReader<U> {
+    async fn from_config(_config: ParquetFileReaderConfig) -> anyhow::Result<Self> {
+        Ok(ParquetFileReader { tx: None })
+    }
+}
+
+impl<U> ParquetFileReader<U>
+where
+    U: DeserializeOwned + Send + Sync + 'static,
+{
+    async fn stream_file<P>(&self, path: P) -> anyhow::Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        let reader = SerializedFileReader::new(File::open(path)?)?;
+        let tx = self.tx.as_ref().unwrap();
+        for row_group in 0..reader.num_row_groups() {
+            let records: Vec<U> = read_row_group(&reader, row_group)?;
+            for record in records {
+                tx.send(record).await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// # Parameters
+/// * P: input, file path
+/// * U: output, record
+#[async_trait]
+impl<P, U> Stream<P, U, ParquetFileReaderConfig> for ParquetFileReader<U>
+where
+    P: AsRef<Path> + Send + 'static,
+    U: DeserializeOwned + Send + Sync + 'static,
+{
+    async fn stream(&mut self, path: P) -> anyhow::Result<()> {
+        self.stream_file(path).await
+    }
+
+    fn set_sender(&mut self, sender: Sender<U>) {
+        self.tx = Some(sender)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::*;
+    use parquet::file::reader::{FileReader, SerializedFileReader};
+    use pipebase::prelude::*;
+    use pipebase::testing::*;
+    use serde::{Deserialize, Serialize};
+    use std::fs::{self, File};
+    use std::path::PathBuf;
+
+    #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+    struct Record {
+        key: String,
+        value: i64,
+    }
+
+    fn records() -> Vec<Record> {
+        (0..5)
+            .map(|i| Record {
+                key: format!("key{}", i),
+                value: i,
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_parquet_file_reader() {
+        let directory = "resources/parquet_stream";
+        fs::create_dir_all(directory).unwrap();
+        let path = PathBuf::from(format!("{}/records.parquet", directory));
+        let pipe = mapper!("parquet_ser");
+        let config = config!(ParquetSerConfig, "resources/catalogs/parquet_ser.yml");
+        let outputs: PipeOutputs<Vec<u8>> = run_pipe_with_records(pipe, config, vec![records()])
+            .await
+            .unwrap();
+        fs::write(&path, &outputs.get_records()[0]).unwrap();
+        let reader = SerializedFileReader::new(File::open(&path).unwrap()).unwrap();
+        assert_eq!(3, reader.num_row_groups());
+        let config = config!(ParquetFileReaderConfig);
+        let pipe = streamer!("parquet_file_reader");
+        let outputs: PipeOutputs<Record> = run_pipe_with_records(pipe, config, vec![path])
+            .await
+            .unwrap();
+        assert_eq!(&records(), outputs.get_records());
+        fs::remove_dir_all(directory).unwrap();
+    }
+}
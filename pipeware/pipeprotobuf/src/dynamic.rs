@@ -0,0 +1,226 @@
+use async_trait::async_trait;
+use pipebase::{
+    common::{ConfigInto, FromConfig, FromPath},
+    map::Map,
+};
+use prost::Message;
+use prost_reflect::{DescriptorPool, DynamicMessage, MessageDescriptor, SerializeOptions};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+/// Load message descriptor from file descriptor set
+pub fn load_message_descriptor(
+    descriptor_set: &str,
+    message: &str,
+) -> anyhow::Result<MessageDescriptor> {
+    let bytes = std::fs::read(descriptor_set)?;
+    let pool = DescriptorPool::decode(bytes.as_slice())?;
+    match pool.get_message_by_name(message) {
+        Some(descriptor) => Ok(descriptor),
+        None => Err(anyhow::anyhow!(
+            "message '{}' not found in descriptor set '{}'",
+            message,
+            descriptor_set
+        )),
+    }
+}
+
+#[derive(Deserialize)]
+pub struct ProtobufDynamicSerConfig {
+    /// File descriptor set, output of `protoc --include_imports --descriptor_set_out`
+    descriptor_set: String,
+    /// Fully qualified message name
+    message: String,
+}
+
+impl FromPath for ProtobufDynamicSerConfig {}
+
+impl ConfigInto<ProtobufDynamicSer> for ProtobufDynamicSerConfig {}
+
+/// Encode record as message described in descriptor set
+pub struct ProtobufDynamicSer {
+    descriptor: MessageDescriptor,
+}
+
+#[async_trait]
+impl FromConfig<ProtobufDynamicSerConfig> for ProtobufDynamicSer {
+    async fn from_config(config: ProtobufDynamicSerConfig) -> anyhow::Result<Self> {
+        Ok(ProtobufDynamicSer {
+            descriptor: load_message_descriptor(&config.descriptor_set, &config.message)?,
+        })
+    }
+}
+
+impl ProtobufDynamicSer {
+    fn serialize<T: Serialize>(&self, t: &T) -> anyhow::Result<Vec<u8>> {
+        let value = serde_json::to_value(t)?;
+        let message = DynamicMessage::deserialize(self.descriptor.to_owned(), value)?;
+        Ok(message.encode_to_vec())
+    }
+}
+
+/// # Parameters
+/// * T: input, record such as `Value`
+/// * Vec<u8>: output, encoded bytes
+#[async_trait]
+impl<T> Map<T, Vec<u8>, ProtobufDynamicSerConfig> for ProtobufDynamicSer
+where
+    T: Serialize + Send + 'static,
+{
+    async fn map(&mut self, t: T) -> anyhow::Result<Vec<u8>> {
+        self.serialize(&t)
+    }
+}
+
+#[derive(Deserialize)]
+pub struct ProtobufDynamicDeserConfig {
+    /// File descriptor set, output of `protoc --include_imports --descriptor_set_out`
+    descriptor_set: String,
+    /// Fully qualified message name
+    message: String,
+}
+
+impl FromPath for ProtobufDynamicDeserConfig {}
+
+impl ConfigInto<ProtobufDynamicDeser> for ProtobufDynamicDeserConfig {}
+
+/// Decode message described in descriptor set into record
+pub struct ProtobufDynamicDeser {
+    descriptor: MessageDescriptor,
+    options: SerializeOptions,
+}
+
+#[async_trait]
+impl FromConfig<ProtobufDynamicDeserConfig> for ProtobufDynamicDeser {
+    async fn from_config(config: ProtobufDynamicDeserConfig) -> anyhow::Result<Self> {
+        // keep proto field names and numeric 64 bit integers, fields with default value included
+        let options = SerializeOptions::new()
+            .use_proto_field_name(true)
+            .stringify_64_bit_integers(false)
+            .skip_default_fields(false);
+        Ok(ProtobufDynamicDeser {
+            descriptor: load_message_descriptor(&config.descriptor_set, &config.message)?,
+            options,
+        })
+    }
+}
+
+impl ProtobufDynamicDeser {
+    fn deserialize<U: DeserializeOwned>(&self, bytes: &[u8]) -> anyhow::Result<U> {
+        let message = DynamicMessage::decode(self.descriptor.to_owned(), bytes)?;
+        let value = message.serialize_with_options(serde_json::value::Serializer, &self.options)?;
+        Ok(serde_json::from_value(value)?)
+    }
+}
+
+/// # Parameters
+/// * Vec<u8>: input, encoded bytes
+/// * U: output, record such as `Value`
+#[async_trait]
+impl<U> Map<Vec<u8>, U, ProtobufDynamicDeserConfig> for ProtobufDynamicDeser
+where
+    U: DeserializeOwned,
+{
+    async fn map(&mut self, bytes: Vec<u8>) -> anyhow::Result<U> {
+        self.deserialize(bytes.as_slice())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::*;
+    use pipebase::common::{FromConfig, Value, ValuePath};
+    use pipebase::map::Map;
+    use prost::Message;
+    use prost_types::{
+        field_descriptor_proto::{Label, Type},
+        DescriptorProto, FieldDescriptorProto, FileDescriptorProto, FileDescriptorSet,
+    };
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    struct Telemetry {
+        #[prost(string, tag = "1")]
+        device: String,
+        #[prost(int64, tag = "2")]
+        ts: i64,
+        #[prost(double, tag = "3")]
+        temperature: f64,
+    }
+
+    fn field(name: &str, number: i32, ty: Type) -> FieldDescriptorProto {
+        FieldDescriptorProto {
+            name: Some(name.to_owned()),
+            number: Some(number),
+            label: Some(Label::Optional as i32),
+            r#type: Some(ty as i32),
+            ..Default::default()
+        }
+    }
+
+    fn write_descriptor_set(path: &str) {
+        let file = FileDescriptorProto {
+            name: Some("telemetry.proto".to_owned()),
+            package: Some("device".to_owned()),
+            message_type: vec![DescriptorProto {
+                name: Some("Telemetry".to_owned()),
+                field: vec![
+                    field("device", 1, Type::String),
+                    field("ts", 2, Type::Int64),
+                    field("temperature", 3, Type::Double),
+                ],
+                ..Default::default()
+            }],
+            syntax: Some("proto3".to_owned()),
+            ..Default::default()
+        };
+        let set = FileDescriptorSet { file: vec![file] };
+        std::fs::write(path, set.encode_to_vec()).unwrap();
+    }
+
+    fn telemetry() -> Telemetry {
+        Telemetry {
+            device: "sensor-1".to_owned(),
+            ts: 1625000000000,
+            temperature: 21.5,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_protobuf_ser_deser() {
+        let mut ser = ProtobufSer::from_config(ProtobufSerConfig {})
+            .await
+            .unwrap();
+        let bytes = ser.map(telemetry()).await.unwrap();
+        let mut deser = ProtobufDeser::from_config(ProtobufDeserConfig {})
+            .await
+            .unwrap();
+        let actual: Telemetry = deser.map(bytes).await.unwrap();
+        assert_eq!(telemetry(), actual)
+    }
+
+    #[tokio::test]
+    async fn test_protobuf_dynamic_ser_deser() {
+        let descriptor_set = std::env::temp_dir().join("pipeprotobuf_telemetry.bin");
+        let descriptor_set = descriptor_set.to_str().unwrap();
+        write_descriptor_set(descriptor_set);
+        let mut deser = ProtobufDynamicDeser::from_config(ProtobufDynamicDeserConfig {
+            descriptor_set: descriptor_set.to_owned(),
+            message: "device.Telemetry".to_owned(),
+        })
+        .await
+        .unwrap();
+        let value: Value = deser.map(telemetry().encode_to_vec()).await.unwrap();
+        let device: ValuePath = "device".parse().unwrap();
+        let ts: ValuePath = "ts".parse().unwrap();
+        assert_eq!(Some(&Value::from("sensor-1")), value.get_path(&device));
+        assert_eq!(Some(&Value::from(1625000000000_i64)), value.get_path(&ts));
+        let mut ser = ProtobufDynamicSer::from_config(ProtobufDynamicSerConfig {
+            descriptor_set: descriptor_set.to_owned(),
+            message: "device.Telemetry".to_owned(),
+        })
+        .await
+        .unwrap();
+        let bytes = ser.map(value).await.unwrap();
+        assert_eq!(telemetry(), Telemetry::decode(bytes.as_slice()).unwrap())
+    }
+}
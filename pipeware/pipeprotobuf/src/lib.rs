@@ -0,0 +1,5 @@
+mod dynamic;
+mod map;
+
+pub use dynamic::*;
+pub use map::*;
@@ -0,0 +1,84 @@
+use async_trait::async_trait;
+use pipebase::{
+    common::{ConfigInto, FromConfig, FromPath},
+    map::Map,
+};
+use prost::Message;
+use serde::Deserialize;
+use std::path::Path;
+
+#[derive(Deserialize)]
+pub struct ProtobufSerConfig {}
+
+#[async_trait]
+impl FromPath for ProtobufSerConfig {
+    async fn from_path<P>(_path: P) -> anyhow::Result<Self>
+    where
+        P: AsRef<Path> + Send,
+    {
+        Ok(ProtobufSerConfig {})
+    }
+}
+
+impl ConfigInto<ProtobufSer> for ProtobufSerConfig {}
+
+/// Encode prost message
+pub struct ProtobufSer {}
+
+#[async_trait]
+impl FromConfig<ProtobufSerConfig> for ProtobufSer {
+    async fn from_config(_config: ProtobufSerConfig) -> anyhow::Result<Self> {
+        Ok(ProtobufSer {})
+    }
+}
+
+/// # Parameters
+/// * T: input, prost message
+/// * Vec<u8>: output, encoded bytes
+#[async_trait]
+impl<T> Map<T, Vec<u8>, ProtobufSerConfig> for ProtobufSer
+where
+    T: Message + 'static,
+{
+    async fn map(&mut self, t: T) -> anyhow::Result<Vec<u8>> {
+        Ok(t.encode_to_vec())
+    }
+}
+
+#[derive(Deserialize)]
+pub struct ProtobufDeserConfig {}
+
+#[async_trait]
+impl FromPath for ProtobufDeserConfig {
+    async fn from_path<P>(_path: P) -> anyhow::Result<Self>
+    where
+        P: AsRef<Path> + Send,
+    {
+        Ok(ProtobufDeserConfig {})
+    }
+}
+
+impl ConfigInto<ProtobufDeser> for ProtobufDeserConfig {}
+
+/// Decode prost message
+pub struct ProtobufDeser {}
+
+#[async_trait]
+impl FromConfig<ProtobufDeserConfig> for ProtobufDeser {
+    async fn from_config(_config: ProtobufDeserConfig) -> anyhow::Result<Self> {
+        Ok(ProtobufDeser {})
+    }
+}
+
+/// # Parameters
+/// * Vec<u8>: input, encoded bytes
+/// * T: output, prost message
+#[async_trait]
+impl<T> Map<Vec<u8>, T, ProtobufDeserConfig> for ProtobufDeser
+where
+    T: Message + Default,
+{
+    async fn map(&mut self, bytes: Vec<u8>) -> anyhow::Result<T> {
+        Ok(T::decode(bytes.as_slice())?)
+    }
+}
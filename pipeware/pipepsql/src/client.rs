@@ -1,9 +1,21 @@
+use crate::value::{PsqlArray, PsqlNull, PsqlRowValue};
+use anyhow::Context;
+use async_trait::async_trait;
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, Utc};
 use openssl::ssl::{SslConnector, SslMethod};
-use pipebase::common::{IntoAttributes, Render, Value};
+use pipebase::common::{
+    Column, ColumnType, ConnectionManager, IntoAttributes, Pool, PoolConfig, Render, Value,
+    ValueType,
+};
 use postgres_openssl::MakeTlsConnector;
 use serde::Deserialize;
-use tokio_postgres::{types::ToSql, Client, NoTls};
-use tracing::info;
+use std::{collections::HashMap, convert::TryFrom};
+use tokio_postgres::{
+    binary_copy::BinaryCopyInWriter,
+    types::{ToSql, Type},
+    Client, NoTls, Row,
+};
+use tracing::{error, info};
 
 #[derive(Deserialize)]
 pub struct SslConfig {
@@ -14,25 +26,15 @@ pub struct SslConfig {
 pub struct PsqlClientConfig {
     params: String,
     ssl: Option<SslConfig>,
+    pool: Option<PoolConfig>,
 }
 
-pub struct PsqlClient {
-    client: Client,
+struct PsqlConnectionManager {
+    params: String,
+    tls: Option<MakeTlsConnector>,
 }
 
-impl PsqlClient {
-    // params schema: https://github.com/sfackler/rust-postgres/blob/master/postgres/src/config.rs
-    // type supoort: https://docs.rs/postgres/0.19.1/postgres/types/trait.ToSql.html
-    pub async fn new(config: PsqlClientConfig) -> anyhow::Result<Self> {
-        let params = config.params;
-        let ssl = config.ssl;
-        let client = match ssl {
-            Some(ssl) => Self::connect_tls(params, ssl).await?,
-            None => Self::connect(params).await?,
-        };
-        Ok(PsqlClient { client })
-    }
-
+impl PsqlConnectionManager {
     fn make_tls(ssl: SslConfig) -> anyhow::Result<MakeTlsConnector> {
         let root_cert_path = ssl.root_cert_path;
         let mut builder = SslConnector::builder(SslMethod::tls())?;
@@ -41,37 +43,79 @@ impl PsqlClient {
         Ok(connector)
     }
 
-    async fn connect(params: String) -> anyhow::Result<Client> {
-        let (client, connection) = tokio_postgres::connect(&params, NoTls).await?;
+    async fn connect_no_tls(&self) -> anyhow::Result<Client> {
+        let (client, connection) = tokio_postgres::connect(&self.params, NoTls).await?;
         tokio::spawn(async move {
             if let Err(e) = connection.await {
-                eprintln!("connection error: {}", e);
+                error!("connection error: {}", e);
             }
         });
         Ok(client)
     }
 
-    async fn connect_tls(params: String, ssl: SslConfig) -> anyhow::Result<Client> {
-        let connector = Self::make_tls(ssl)?;
-        let (client, connection) = tokio_postgres::connect(&params, connector).await?;
+    async fn connect_tls(&self, connector: MakeTlsConnector) -> anyhow::Result<Client> {
+        let (client, connection) = tokio_postgres::connect(&self.params, connector).await?;
         tokio::spawn(async move {
             if let Err(e) = connection.await {
-                eprintln!("connection error: {}", e);
+                error!("connection error: {}", e);
             }
         });
         Ok(client)
     }
+}
+
+#[async_trait]
+impl ConnectionManager for PsqlConnectionManager {
+    type Connection = Client;
+
+    async fn connect(&self) -> anyhow::Result<Client> {
+        match self.tls {
+            Some(ref connector) => self.connect_tls(connector.to_owned()).await,
+            None => self.connect_no_tls().await,
+        }
+    }
+
+    async fn check(&self, client: &mut Client) -> anyhow::Result<()> {
+        // connection task exits once connection lost
+        match client.is_closed() {
+            true => anyhow::bail!("connection closed"),
+            false => Ok(()),
+        }
+    }
+}
+
+pub struct PsqlClient {
+    pool: Pool<PsqlConnectionManager>,
+}
+
+impl PsqlClient {
+    // params schema: https://github.com/sfackler/rust-postgres/blob/master/postgres/src/config.rs
+    // type supoort: https://docs.rs/postgres/0.19.1/postgres/types/trait.ToSql.html
+    pub async fn new(config: PsqlClientConfig) -> anyhow::Result<Self> {
+        let tls = match config.ssl {
+            Some(ssl) => Some(PsqlConnectionManager::make_tls(ssl)?),
+            None => None,
+        };
+        let manager = PsqlConnectionManager {
+            params: config.params,
+            tls,
+        };
+        let pool = Pool::new(manager, config.pool.unwrap_or_default()).await?;
+        Ok(PsqlClient { pool })
+    }
 
     pub async fn execute<R>(&mut self, r: R) -> anyhow::Result<()>
     where
         R: Render,
     {
         let statement = r.render();
-        let rows_updated = self.client.execute(&statement[..], &[]).await?;
+        let client = self.pool.get().await?;
+        let rows_updated = client.execute(&statement[..], &[]).await?;
         info!("{} rows updated", rows_updated);
         Ok(())
     }
 
+    /// Execute prepared statement per item in one transaction, rollback on failure
     pub async fn prepare_execute<A>(
         &mut self,
         statement: String,
@@ -80,19 +124,199 @@ impl PsqlClient {
     where
         A: IntoAttributes,
     {
-        let prepared_statement = self.client.prepare(&statement).await?;
-        for item in items {
-            let params = Self::from_attributes_to_params(item);
+        let params = items
+            .into_iter()
+            .map(Self::from_attributes_to_params)
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        self.execute_batch(&statement, params).await
+    }
+
+    /// Insert or update on conflict of keys in one transaction, columns are attribute names
+    pub async fn upsert<A>(
+        &mut self,
+        table: &str,
+        keys: &[String],
+        items: Vec<A>,
+    ) -> anyhow::Result<()>
+    where
+        A: IntoAttributes,
+    {
+        let rows: Vec<Vec<(String, Value)>> = items
+            .into_iter()
+            .map(IntoAttributes::into_attribute_tuples)
+            .collect();
+        let columns = match rows.first() {
+            Some(row) => row
+                .iter()
+                .map(|(name, _)| name.to_owned())
+                .collect::<Vec<_>>(),
+            None => return Ok(()),
+        };
+        let statement = upsert_statement(table, &columns, keys);
+        let params = rows
+            .into_iter()
+            .map(|row| {
+                row.into_iter()
+                    .map(|(_, value)| Self::psql_value(value))
+                    .collect()
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        self.execute_batch(&statement, params).await
+    }
+
+    async fn execute_batch(
+        &mut self,
+        statement: &str,
+        params: Vec<Vec<Box<dyn ToSql + Send + Sync>>>,
+    ) -> anyhow::Result<()> {
+        // transaction rollback on drop if not committed
+        let mut client = self.pool.get().await?;
+        let transaction = client.transaction().await?;
+        let prepared_statement = transaction.prepare(statement).await?;
+        let mut rows_updated: u64 = 0;
+        for params in params {
             let params: Vec<&(dyn ToSql + Send + Sync)> =
                 params.iter().map(|p| p.as_ref()).collect();
-            self.client
+            rows_updated += transaction
                 .execute_raw(&prepared_statement, slice_iter(&params))
                 .await?;
         }
+        transaction.commit().await?;
+        info!("{} rows updated", rows_updated);
         Ok(())
     }
 
-    fn from_attributes_to_params<A>(attributes: A) -> Vec<Box<dyn ToSql + Send + Sync>>
+    /// Bulk load items with binary `COPY`, columns are attribute names
+    pub async fn copy_in<A>(&self, table: &str, items: Vec<A>) -> anyhow::Result<()>
+    where
+        A: IntoAttributes,
+    {
+        let rows: Vec<Vec<(String, Value)>> = items
+            .into_iter()
+            .map(IntoAttributes::into_attribute_tuples)
+            .collect();
+        let columns = match rows.first() {
+            Some(row) => row
+                .iter()
+                .map(|(name, _)| quote(name))
+                .collect::<Vec<_>>()
+                .join(", "),
+            None => return Ok(()),
+        };
+        let client = self.pool.get().await?;
+        // resolve column types to encode values in binary format
+        let types: Vec<Type> = client
+            .prepare(&format!("SELECT {} FROM {}", columns, table))
+            .await?
+            .columns()
+            .iter()
+            .map(|column| column.type_().to_owned())
+            .collect();
+        let sink = client
+            .copy_in(&format!("COPY {} ({}) FROM STDIN BINARY", table, columns))
+            .await?;
+        let writer = BinaryCopyInWriter::new(sink, &types);
+        tokio::pin!(writer);
+        for row in rows {
+            let params = row
+                .into_iter()
+                .zip(types.iter())
+                .map(|((_, value), ty)| Self::psql_param(value, ty))
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            let params: Vec<&(dyn ToSql + Sync)> = params
+                .iter()
+                .map(|p| p.as_ref() as &(dyn ToSql + Sync))
+                .collect();
+            writer.as_mut().write(&params).await?;
+        }
+        let rows_copied = writer.finish().await?;
+        info!("{} rows copied", rows_copied);
+        Ok(())
+    }
+
+    /// Validate statement params against record columns bound in order, fail at startup
+    /// instead of first write if a column can not be bound to its param type
+    pub async fn validate_params(&self, statement: &str, columns: &[Column]) -> anyhow::Result<()> {
+        let client = self.pool.get().await?;
+        let prepared_statement = client.prepare(statement).await?;
+        let params = prepared_statement.params();
+        if params.len() != columns.len() {
+            anyhow::bail!(
+                "statement expects {} params but record has {} columns",
+                params.len(),
+                columns.len()
+            )
+        }
+        for (i, (column, ty)) in columns.iter().zip(params).enumerate() {
+            if !accepts(&column.ty, ty) {
+                anyhow::bail!(
+                    "column '{}' of type {:?} can not be bound to param ${} of type {}",
+                    column.name,
+                    column.ty,
+                    i + 1,
+                    ty
+                )
+            }
+        }
+        Ok(())
+    }
+
+    /// Validate upsert statement of record columns
+    pub async fn validate_upsert(
+        &self,
+        table: &str,
+        columns: &[Column],
+        keys: &[String],
+    ) -> anyhow::Result<()> {
+        let names: Vec<String> = columns
+            .iter()
+            .map(|column| column.name.to_owned())
+            .collect();
+        self.validate_params(&upsert_statement(table, &names, keys), columns)
+            .await
+    }
+
+    /// Create table if not exists and add missing columns
+    pub async fn create_table(
+        &self,
+        table: &str,
+        columns: &[Column],
+        keys: &[String],
+    ) -> anyhow::Result<()> {
+        let client = self.pool.get().await?;
+        client
+            .batch_execute(&create_table_statement(table, columns, keys))
+            .await?;
+        for column in columns {
+            client
+                .batch_execute(&add_column_statement(table, column))
+                .await?;
+        }
+        info!("table '{}' created", table);
+        Ok(())
+    }
+
+    /// Query rows as `Value::Attributes` keyed by column name, params cast into statement param types
+    pub async fn query(&self, statement: &str, params: Vec<Value>) -> anyhow::Result<Vec<Value>> {
+        let client = self.pool.get().await?;
+        let prepared_statement = client.prepare(statement).await?;
+        let params = prepared_statement
+            .params()
+            .iter()
+            .zip(params)
+            .map(|(ty, value)| Self::psql_param(value, ty))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let params: Vec<&(dyn ToSql + Sync)> = params
+            .iter()
+            .map(|p| p.as_ref() as &(dyn ToSql + Sync))
+            .collect();
+        let rows = client.query(&prepared_statement, &params).await?;
+        rows.iter().map(Self::from_psql_row).collect()
+    }
+
+    fn from_attributes_to_params<A>(
+        attributes: A,
+    ) -> anyhow::Result<Vec<Box<dyn ToSql + Send + Sync>>>
     where
         A: IntoAttributes,
     {
@@ -103,11 +327,14 @@ impl PsqlClient {
             .collect()
     }
 
-    fn psql_value(value: Value) -> Box<dyn ToSql + Send + Sync> {
-        match value {
+    fn psql_value(value: Value) -> anyhow::Result<Box<dyn ToSql + Send + Sync>> {
+        let value: Box<dyn ToSql + Send + Sync> = match value {
+            Value::Null => Box::new(PsqlNull),
             Value::Bool(value) => Box::new(value),
-            Value::UnsignedInteger(value) => Box::new(value),
+            // postgres has no unsigned type
+            Value::UnsignedInteger(value) => Box::new(value.map(i64::from)),
             Value::Integer(value) => Box::new(value),
+            Value::UnsignedLong(value) => Box::new(value.map(i64::try_from).transpose()?),
             Value::Long(value) => Box::new(value),
             Value::Float(value) => Box::new(value),
             Value::Double(value) => Box::new(value),
@@ -115,15 +342,227 @@ impl PsqlClient {
             Value::UnsignedBytes(value) => Box::new(value),
             Value::Date(value) => Box::new(value),
             Value::DateTime(value) => Box::new(value),
+            // duration as millis
+            Value::Duration(value) => Box::new(value.map(|value| value.num_milliseconds())),
             Value::UtcTime(value) => Box::new(value),
             Value::LocalTime(value) => Box::new(value),
-            _ => unimplemented!(),
+            Value::Array(values) => Box::new(PsqlArray(values)),
+            value @ Value::Attributes(_) => Box::new(serde_json::to_value(&value)?),
+        };
+        Ok(value)
+    }
+
+    pub(crate) fn psql_param(
+        value: Value,
+        ty: &Type,
+    ) -> anyhow::Result<Box<dyn ToSql + Send + Sync>> {
+        let value_type = match ty.name() {
+            "bool" => ValueType::Bool,
+            "int2" => {
+                let value = match value.cast(&ValueType::Integer)? {
+                    Value::Integer(Some(value)) => Some(i16::try_from(value)?),
+                    _ => None,
+                };
+                return Ok(Box::new(value));
+            }
+            "int4" => ValueType::Integer,
+            "int8" => ValueType::Long,
+            "float4" => ValueType::Float,
+            "float8" => ValueType::Double,
+            "text" | "varchar" | "bpchar" | "name" => ValueType::String,
+            "date" => ValueType::Date,
+            "timestamp" => ValueType::DateTime,
+            "timestamptz" => ValueType::UtcTime,
+            _ => return Self::psql_value(value),
+        };
+        Self::psql_value(value.cast(&value_type)?)
+    }
+
+    fn from_psql_row(row: &Row) -> anyhow::Result<Value> {
+        let mut attributes: HashMap<String, Value> = HashMap::new();
+        for (i, column) in row.columns().iter().enumerate() {
+            let value = row
+                .try_get::<_, PsqlRowValue>(i)
+                .with_context(|| format!("decode column '{}'", column.name()))?;
+            attributes.insert(column.name().to_owned(), value.0);
         }
+        Ok(Value::Attributes(attributes))
     }
 }
 
+/// Whether param type accepts value of column type bound by `psql_value`
+fn accepts(column_type: &ColumnType, ty: &Type) -> bool {
+    match column_type {
+        ColumnType::Bool => bool::accepts(ty),
+        ColumnType::Integer => i32::accepts(ty),
+        // unsigned integer, unsigned long and duration millis bound as bigint
+        ColumnType::UnsignedInteger
+        | ColumnType::UnsignedLong
+        | ColumnType::Long
+        | ColumnType::Duration => i64::accepts(ty),
+        ColumnType::Float => f32::accepts(ty),
+        ColumnType::Double => f64::accepts(ty),
+        ColumnType::String => String::accepts(ty),
+        ColumnType::UnsignedBytes => Vec::<u8>::accepts(ty),
+        ColumnType::Date => NaiveDate::accepts(ty),
+        ColumnType::DateTime => NaiveDateTime::accepts(ty),
+        ColumnType::LocalTime => DateTime::<Local>::accepts(ty),
+        ColumnType::UtcTime => DateTime::<Utc>::accepts(ty),
+        ColumnType::Array(_) => PsqlArray::accepts(ty),
+        ColumnType::Attributes(_) => serde_json::Value::accepts(ty),
+    }
+}
+
+/// `INSERT ... ON CONFLICT (keys) DO UPDATE` non key columns, `DO NOTHING` if all columns are keys
+fn upsert_statement(table: &str, columns: &[String], keys: &[String]) -> String {
+    let placeholders: Vec<String> = (1..=columns.len()).map(|i| format!("${}", i)).collect();
+    let updates: Vec<String> = columns
+        .iter()
+        .filter(|column| !keys.contains(column))
+        .map(|column| format!("{} = EXCLUDED.{}", quote(column), quote(column)))
+        .collect();
+    let action = match updates.is_empty() {
+        true => "NOTHING".to_owned(),
+        false => format!("UPDATE SET {}", updates.join(", ")),
+    };
+    let columns: Vec<String> = columns.iter().map(|column| quote(column)).collect();
+    let keys: Vec<String> = keys.iter().map(|key| quote(key)).collect();
+    format!(
+        "INSERT INTO {} ({}) VALUES ({}) ON CONFLICT ({}) DO {}",
+        table,
+        columns.join(", "),
+        placeholders.join(", "),
+        keys.join(", "),
+        action
+    )
+}
+
+fn quote(name: &str) -> String {
+    format!("\"{}\"", name)
+}
+
+fn column_type(ty: &ColumnType) -> String {
+    let ty = match ty {
+        ColumnType::Bool => "BOOLEAN",
+        // unsigned bound as signed of wider type
+        ColumnType::UnsignedInteger => "BIGINT",
+        ColumnType::Integer => "INTEGER",
+        ColumnType::UnsignedLong => "BIGINT",
+        ColumnType::Long => "BIGINT",
+        ColumnType::Float => "REAL",
+        ColumnType::Double => "DOUBLE PRECISION",
+        ColumnType::String => "TEXT",
+        ColumnType::Date => "DATE",
+        ColumnType::DateTime => "TIMESTAMP",
+        // duration as millis
+        ColumnType::Duration => "BIGINT",
+        ColumnType::LocalTime | ColumnType::UtcTime => "TIMESTAMPTZ",
+        ColumnType::UnsignedBytes => "BYTEA",
+        // nested arrays and attributes as json
+        ColumnType::Array(ty) if !ty.is_collection() => return format!("{}[]", column_type(ty)),
+        ColumnType::Array(_) | ColumnType::Attributes(_) => "JSONB",
+    };
+    ty.to_owned()
+}
+
+/// `CREATE TABLE IF NOT EXISTS` with primary key if any
+fn create_table_statement(table: &str, columns: &[Column], keys: &[String]) -> String {
+    let mut definitions: Vec<String> = columns
+        .iter()
+        .map(|column| format!("{} {}", quote(&column.name), column_type(&column.ty)))
+        .collect();
+    if !keys.is_empty() {
+        let keys: Vec<String> = keys.iter().map(|key| quote(key)).collect();
+        definitions.push(format!("PRIMARY KEY ({})", keys.join(", ")))
+    }
+    format!(
+        "CREATE TABLE IF NOT EXISTS {} ({})",
+        table,
+        definitions.join(", ")
+    )
+}
+
+fn add_column_statement(table: &str, column: &Column) -> String {
+    format!(
+        "ALTER TABLE {} ADD COLUMN IF NOT EXISTS {} {}",
+        table,
+        quote(&column.name),
+        column_type(&column.ty)
+    )
+}
+
 fn slice_iter<'a>(
     s: &'a [&'a (dyn ToSql + Send + Sync)],
 ) -> impl ExactSizeIterator<Item = &'a dyn ToSql> + 'a {
     s.iter().map(|s| *s as _)
 }
+
+#[cfg(test)]
+mod tests {
+
+    use super::{accepts, add_column_statement, create_table_statement, upsert_statement};
+    use pipebase::common::{Column, ColumnType};
+    use tokio_postgres::types::Type;
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn test_upsert_statement() {
+        let columns = names(&["id", "name", "score"]);
+        assert_eq!(
+            r#"INSERT INTO records ("id", "name", "score") VALUES ($1, $2, $3) ON CONFLICT ("id") DO UPDATE SET "name" = EXCLUDED."name", "score" = EXCLUDED."score""#,
+            upsert_statement("records", &columns, &names(&["id"]))
+        );
+        assert_eq!(
+            r#"INSERT INTO records ("id", "name", "score") VALUES ($1, $2, $3) ON CONFLICT ("id", "name", "score") DO NOTHING"#,
+            upsert_statement("records", &columns, &columns)
+        );
+    }
+
+    #[test]
+    fn test_create_table_statement() {
+        let columns = vec![
+            Column {
+                name: "id".to_owned(),
+                ty: ColumnType::UnsignedLong,
+            },
+            Column {
+                name: "tags".to_owned(),
+                ty: ColumnType::Array(Box::new(ColumnType::String)),
+            },
+            Column {
+                name: "labels".to_owned(),
+                ty: ColumnType::Attributes(Box::new(ColumnType::String)),
+            },
+        ];
+        assert_eq!(
+            r#"CREATE TABLE IF NOT EXISTS records ("id" BIGINT, "tags" TEXT[], "labels" JSONB, PRIMARY KEY ("id"))"#,
+            create_table_statement("records", &columns, &names(&["id"]))
+        );
+        assert_eq!(
+            r#"ALTER TABLE records ADD COLUMN IF NOT EXISTS "tags" TEXT[]"#,
+            add_column_statement("records", &columns[1])
+        );
+    }
+
+    #[test]
+    fn test_accepts() {
+        assert!(accepts(&ColumnType::Duration, &Type::INT8));
+        // duration bound as bigint millis
+        assert!(!accepts(&ColumnType::Duration, &Type::INTERVAL));
+        assert!(accepts(&ColumnType::UnsignedInteger, &Type::INT8));
+        assert!(!accepts(&ColumnType::Integer, &Type::INT8));
+        assert!(!accepts(&ColumnType::String, &Type::INT4));
+        assert!(accepts(&ColumnType::UtcTime, &Type::TIMESTAMPTZ));
+        assert!(accepts(
+            &ColumnType::Array(Box::new(ColumnType::String)),
+            &Type::TEXT_ARRAY
+        ));
+        assert!(accepts(
+            &ColumnType::Attributes(Box::new(ColumnType::Long)),
+            &Type::JSONB
+        ));
+    }
+}
@@ -1,10 +1,11 @@
 use crate::client::{PsqlClient, PsqlClientConfig};
 use async_trait::async_trait;
 use pipebase::{
-    common::{ConfigInto, FromConfig, FromPath, IntoAttributes, Render},
+    common::{ConfigInto, CreateTableConfig, FromConfig, FromPath, IntoAttributes, Render, Schema},
     export::Export,
 };
 use serde::Deserialize;
+use std::marker::PhantomData;
 
 #[derive(Deserialize)]
 pub struct PsqlWriterConfig {
@@ -63,6 +64,8 @@ impl FromConfig<PsqlPreparedWriterConfig> for PsqlPreparedWriter {
     }
 }
 
+/// # Parameters
+/// * Vec<T>: input, records with attributes bound as statement params
 #[async_trait]
 impl<T> Export<Vec<T>, PsqlPreparedWriterConfig> for PsqlPreparedWriter
 where
@@ -73,3 +76,181 @@ where
         self.client.prepare_execute(statement, items).await
     }
 }
+
+#[derive(Deserialize)]
+pub struct PsqlSchemaPreparedWriterConfig {
+    #[serde(flatten)]
+    writer: PsqlPreparedWriterConfig,
+    /// Create table from record schema if not exists
+    create_table: Option<CreateTableConfig>,
+    /// Validate record columns against statement params at startup, true if table created
+    validate: Option<bool>,
+}
+
+impl FromPath for PsqlSchemaPreparedWriterConfig {}
+
+impl<T> ConfigInto<PsqlSchemaPreparedWriter<T>> for PsqlSchemaPreparedWriterConfig where T: Schema {}
+
+/// Prepared writer creating table from schema of record type at startup
+pub struct PsqlSchemaPreparedWriter<T> {
+    writer: PsqlPreparedWriter,
+    _record: PhantomData<T>,
+}
+
+#[async_trait]
+impl<T> FromConfig<PsqlSchemaPreparedWriterConfig> for PsqlSchemaPreparedWriter<T>
+where
+    T: Schema,
+{
+    async fn from_config(config: PsqlSchemaPreparedWriterConfig) -> anyhow::Result<Self> {
+        let client = PsqlClient::new(config.writer.client).await?;
+        let statement = config.writer.statement;
+        let created = match config.create_table {
+            Some(create_table) => {
+                client
+                    .create_table(&create_table.table, &T::schema(), &create_table.get_keys())
+                    .await?;
+                true
+            }
+            None => false,
+        };
+        // table may be created by upstream pipe after startup
+        if config.validate.unwrap_or(created) {
+            client.validate_params(&statement, &T::schema()).await?;
+        }
+        Ok(PsqlSchemaPreparedWriter {
+            writer: PsqlPreparedWriter { client, statement },
+            _record: PhantomData,
+        })
+    }
+}
+
+/// # Parameters
+/// * Vec<T>: input, records with attributes bound as statement params
+#[async_trait]
+impl<T> Export<Vec<T>, PsqlSchemaPreparedWriterConfig> for PsqlSchemaPreparedWriter<T>
+where
+    T: IntoAttributes + Schema + Send + Sync + 'static,
+{
+    async fn export(&mut self, items: Vec<T>) -> anyhow::Result<()> {
+        let statement = self.writer.statement.to_owned();
+        self.writer.client.prepare_execute(statement, items).await
+    }
+}
+
+#[derive(Deserialize)]
+pub struct PsqlUpsertWriterConfig {
+    client: PsqlClientConfig,
+    table: String,
+    /// Conflict target, columns of primary key or unique constraint
+    keys: Vec<String>,
+    /// Create table with keys as primary key, false by default
+    create_table: Option<bool>,
+    /// Validate record columns against table at startup, true if table created
+    validate: Option<bool>,
+}
+
+impl FromPath for PsqlUpsertWriterConfig {}
+
+impl<T> ConfigInto<PsqlUpsertWriter<T>> for PsqlUpsertWriterConfig where T: Schema {}
+
+/// Insert or update batch in one transaction
+pub struct PsqlUpsertWriter<T> {
+    client: PsqlClient,
+    table: String,
+    keys: Vec<String>,
+    _record: PhantomData<T>,
+}
+
+#[async_trait]
+impl<T> FromConfig<PsqlUpsertWriterConfig> for PsqlUpsertWriter<T>
+where
+    T: Schema,
+{
+    async fn from_config(config: PsqlUpsertWriterConfig) -> anyhow::Result<Self> {
+        if config.keys.is_empty() {
+            anyhow::bail!("upsert keys of table '{}' not configured", config.table)
+        }
+        let client = PsqlClient::new(config.client).await?;
+        let created = config.create_table.unwrap_or_default();
+        if created {
+            client
+                .create_table(&config.table, &T::schema(), &config.keys)
+                .await?;
+        }
+        if config.validate.unwrap_or(created) {
+            client
+                .validate_upsert(&config.table, &T::schema(), &config.keys)
+                .await?;
+        }
+        Ok(PsqlUpsertWriter {
+            client,
+            table: config.table,
+            keys: config.keys,
+            _record: PhantomData,
+        })
+    }
+}
+
+/// # Parameters
+/// * Vec<T>: input, records with attribute names as column names
+#[async_trait]
+impl<T> Export<Vec<T>, PsqlUpsertWriterConfig> for PsqlUpsertWriter<T>
+where
+    T: IntoAttributes + Schema + Send + Sync + 'static,
+{
+    async fn export(&mut self, items: Vec<T>) -> anyhow::Result<()> {
+        self.client.upsert(&self.table, &self.keys, items).await
+    }
+}
+
+#[derive(Deserialize)]
+pub struct PsqlCopyWriterConfig {
+    client: PsqlClientConfig,
+    table: String,
+    /// Create table without primary key, false by default
+    create_table: Option<bool>,
+}
+
+impl FromPath for PsqlCopyWriterConfig {}
+
+impl<T> ConfigInto<PsqlCopyWriter<T>> for PsqlCopyWriterConfig where T: Schema {}
+
+/// Bulk load batch with binary `COPY`
+pub struct PsqlCopyWriter<T> {
+    client: PsqlClient,
+    table: String,
+    _record: PhantomData<T>,
+}
+
+#[async_trait]
+impl<T> FromConfig<PsqlCopyWriterConfig> for PsqlCopyWriter<T>
+where
+    T: Schema,
+{
+    async fn from_config(config: PsqlCopyWriterConfig) -> anyhow::Result<Self> {
+        let client = PsqlClient::new(config.client).await?;
+        if config.create_table.unwrap_or_default() {
+            client
+                .create_table(&config.table, &T::schema(), &[])
+                .await?;
+        }
+        Ok(PsqlCopyWriter {
+            client,
+            table: config.table,
+            _record: PhantomData,
+        })
+    }
+}
+
+/// # Parameters
+/// * Vec<T>: input, records with attribute names as column names
+#[async_trait]
+impl<T> Export<Vec<T>, PsqlCopyWriterConfig> for PsqlCopyWriter<T>
+where
+    T: IntoAttributes + Schema + Send + Sync + 'static,
+{
+    async fn export(&mut self, items: Vec<T>) -> anyhow::Result<()> {
+        self.client.copy_in(&self.table, items).await
+    }
+}
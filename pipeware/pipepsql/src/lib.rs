@@ -1,4 +1,7 @@
 mod client;
 mod export;
+mod poll;
+mod value;
 
 pub use export::*;
+pub use poll::*;
@@ -0,0 +1,152 @@
+use crate::client::{PsqlClient, PsqlClientConfig};
+use async_trait::async_trait;
+use pipebase::{
+    common::{ConfigInto, FromConfig, FromPath, Period},
+    poll::{CursorCheckpoint, CursorConfig, Poll, PollResponse},
+};
+use serde::{de::DeserializeOwned, Deserialize};
+use std::time::Duration;
+
+#[derive(Deserialize)]
+pub struct PsqlQueryPollerConfig {
+    client: PsqlClientConfig,
+    /// Query with cursor bound to first parameter and ordered by cursor column,
+    /// for example `SELECT * FROM events WHERE id > $1 ORDER BY id LIMIT 1000`
+    /// with `LIMIT`, cursor column must be unique, otherwise rows sharing cursor value of last row are skipped
+    query: String,
+    cursor: CursorConfig,
+    initial_delay: Period,
+    interval: Period,
+}
+
+impl FromPath for PsqlQueryPollerConfig {}
+
+impl ConfigInto<PsqlQueryPoller> for PsqlQueryPollerConfig {}
+
+/// Poll rows newer than last cursor
+pub struct PsqlQueryPoller {
+    client: PsqlClient,
+    query: String,
+    cursor: CursorCheckpoint,
+    initial_delay: Duration,
+    interval: Duration,
+}
+
+#[async_trait]
+impl FromConfig<PsqlQueryPollerConfig> for PsqlQueryPoller {
+    async fn from_config(config: PsqlQueryPollerConfig) -> anyhow::Result<Self> {
+        Ok(PsqlQueryPoller {
+            client: PsqlClient::new(config.client).await?,
+            query: config.query,
+            cursor: CursorCheckpoint::load(config.cursor)?,
+            initial_delay: config.initial_delay.into(),
+            interval: config.interval.into(),
+        })
+    }
+}
+
+/// # Parameters
+/// * Vec<U>: output, rows deserialized from `Value`
+#[async_trait]
+impl<U> Poll<Vec<U>, PsqlQueryPollerConfig> for PsqlQueryPoller
+where
+    U: DeserializeOwned + Send + 'static,
+{
+    async fn poll(&mut self) -> anyhow::Result<PollResponse<Vec<U>>> {
+        let cursor = self.cursor.get().to_owned();
+        let rows = self.client.query(&self.query, vec![cursor]).await?;
+        self.cursor.poll_rows(rows)
+    }
+
+    fn get_initial_delay(&self) -> Duration {
+        self.initial_delay.to_owned()
+    }
+
+    fn get_interval(&self) -> tokio::time::Interval {
+        let interval = self.interval.to_owned();
+        tokio::time::interval(interval)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::client::PsqlClient;
+    use chrono::{TimeZone, Utc};
+    use pipebase::common::Value;
+    use std::collections::HashMap;
+    use tokio_postgres::types::Type;
+
+    const CONFIG: &str = r#"
+client:
+  params: host=localhost user=postgres password=postgres dbname=postgres
+query: SELECT id, updated_at FROM events WHERE id > $1 ORDER BY id LIMIT 2
+cursor:
+  column: id
+  initial: 0
+initial_delay:
+  Millis: 0
+interval:
+  Secs: 10
+"#;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Event {
+        id: i64,
+        updated_at: String,
+    }
+
+    fn row(id: i64) -> Value {
+        let mut attributes = HashMap::new();
+        attributes.insert("id".to_owned(), Value::from(id));
+        let updated_at = Utc.timestamp_millis_opt(id * 1000).unwrap();
+        attributes.insert("updated_at".to_owned(), Value::from(updated_at));
+        Value::Attributes(attributes)
+    }
+
+    #[test]
+    fn test_psql_query_poller_config() {
+        let config: PsqlQueryPollerConfig = serde_yaml::from_str(CONFIG).unwrap();
+        assert_eq!(
+            "SELECT id, updated_at FROM events WHERE id > $1 ORDER BY id LIMIT 2",
+            config.query
+        );
+        assert_eq!(Duration::from_secs(10), Duration::from(config.interval));
+        let cursor = CursorCheckpoint::load(config.cursor).unwrap();
+        assert_eq!("id", cursor.get_column());
+        // initial cursor cast into type of first query param
+        assert!(PsqlClient::psql_param(cursor.get().to_owned(), &Type::INT8).is_ok());
+    }
+
+    #[test]
+    fn test_psql_poll_rows() {
+        let config: PsqlQueryPollerConfig = serde_yaml::from_str(CONFIG).unwrap();
+        let mut cursor = CursorCheckpoint::load(config.cursor).unwrap();
+        let events = match cursor.poll_rows::<Event>(vec![row(1), row(2)]).unwrap() {
+            PollResponse::PollResult(Some(events)) => events,
+            _ => panic!("expect polled rows"),
+        };
+        assert_eq!(
+            vec![
+                Event {
+                    id: 1,
+                    updated_at: "1970-01-01T00:00:01+00:00".to_owned()
+                },
+                Event {
+                    id: 2,
+                    updated_at: "1970-01-01T00:00:02+00:00".to_owned()
+                },
+            ],
+            events
+        );
+        // next poll binds id of last row
+        assert_eq!(&Value::from(2_i64), cursor.get());
+        assert!(PsqlClient::psql_param(cursor.get().to_owned(), &Type::INT8).is_ok());
+        assert!(matches!(
+            cursor.poll_rows::<Event>(vec![]).unwrap(),
+            PollResponse::PollResult(None)
+        ));
+        assert_eq!(&Value::from(2_i64), cursor.get());
+    }
+}
@@ -0,0 +1,359 @@
+use bytes::BytesMut;
+use chrono::Duration;
+use pipebase::common::{Value, ValueType};
+use std::{
+    convert::{TryFrom, TryInto},
+    error::Error,
+};
+use tokio_postgres::types::{to_sql_checked, FromSql, IsNull, Kind, ToSql, Type};
+
+/// Null accepted by any column type
+#[derive(Debug)]
+pub(crate) struct PsqlNull;
+
+impl ToSql for PsqlNull {
+    fn to_sql(
+        &self,
+        _ty: &Type,
+        _out: &mut BytesMut,
+    ) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        Ok(IsNull::Yes)
+    }
+
+    fn accepts(_ty: &Type) -> bool {
+        true
+    }
+
+    to_sql_checked!();
+}
+
+/// Array encoded as array of column member type, or json array
+#[derive(Debug)]
+pub(crate) struct PsqlArray(pub Vec<Value>);
+
+impl ToSql for PsqlArray {
+    fn to_sql(
+        &self,
+        ty: &Type,
+        out: &mut BytesMut,
+    ) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        let member = match ty.kind() {
+            Kind::Array(member) => member,
+            _ => return serde_json::to_value(&self.0)?.to_sql(ty, out),
+        };
+        let values = self.0.as_slice();
+        match member.name() {
+            "bool" => cast(values, ValueType::Bool, |v| match v {
+                Value::Bool(v) => v,
+                _ => None,
+            })?
+            .to_sql(ty, out),
+            "int2" => cast(values, ValueType::Integer, |v| match v {
+                Value::Integer(v) => v,
+                _ => None,
+            })?
+            .into_iter()
+            .map(|v| v.map(i16::try_from).transpose())
+            .collect::<Result<Vec<_>, _>>()?
+            .to_sql(ty, out),
+            "int4" => cast(values, ValueType::Integer, |v| match v {
+                Value::Integer(v) => v,
+                _ => None,
+            })?
+            .to_sql(ty, out),
+            "int8" => cast(values, ValueType::Long, |v| match v {
+                Value::Long(v) => v,
+                _ => None,
+            })?
+            .to_sql(ty, out),
+            "float4" => cast(values, ValueType::Float, |v| match v {
+                Value::Float(v) => v,
+                _ => None,
+            })?
+            .to_sql(ty, out),
+            "float8" => cast(values, ValueType::Double, |v| match v {
+                Value::Double(v) => v,
+                _ => None,
+            })?
+            .to_sql(ty, out),
+            "text" | "varchar" | "bpchar" | "name" => {
+                cast(values, ValueType::String, |v| match v {
+                    Value::String(v) => v,
+                    _ => None,
+                })?
+                .to_sql(ty, out)
+            }
+            "date" => cast(values, ValueType::Date, |v| match v {
+                Value::Date(v) => v,
+                _ => None,
+            })?
+            .to_sql(ty, out),
+            "timestamp" => cast(values, ValueType::DateTime, |v| match v {
+                Value::DateTime(v) => v,
+                _ => None,
+            })?
+            .to_sql(ty, out),
+            "timestamptz" => cast(values, ValueType::UtcTime, |v| match v {
+                Value::UtcTime(v) => v,
+                _ => None,
+            })?
+            .to_sql(ty, out),
+            "bytea" => values
+                .iter()
+                .map(|v| match v {
+                    Value::UnsignedBytes(v) => Ok(Some(v.to_owned())),
+                    v if v.is_null() => Ok(None),
+                    v => Err(format!("can not cast {:?} into bytea", v)),
+                })
+                .collect::<Result<Vec<_>, _>>()?
+                .to_sql(ty, out),
+            "json" | "jsonb" => values
+                .iter()
+                .map(serde_json::to_value)
+                .collect::<Result<Vec<_>, _>>()?
+                .to_sql(ty, out),
+            name => Err(format!("unsupported array member type '{}'", name).into()),
+        }
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        matches!(ty.kind(), Kind::Array(_)) || *ty == Type::JSON || *ty == Type::JSONB
+    }
+
+    to_sql_checked!();
+}
+
+/// Cast array elements into member type
+fn cast<T, F>(values: &[Value], ty: ValueType, extract: F) -> anyhow::Result<Vec<Option<T>>>
+where
+    F: Fn(Value) -> Option<T>,
+{
+    values
+        .iter()
+        .map(|value| Ok(extract(value.cast(&ty)?)))
+        .collect()
+}
+
+/// Column value decoded by column type, typed null if column is null
+pub(crate) struct PsqlRowValue(pub Value);
+
+impl<'a> FromSql<'a> for PsqlRowValue {
+    fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<Self, Box<dyn Error + Sync + Send>> {
+        Ok(PsqlRowValue(from_sql(ty, Some(raw))?))
+    }
+
+    fn from_sql_null(ty: &Type) -> Result<Self, Box<dyn Error + Sync + Send>> {
+        Ok(PsqlRowValue(from_sql(ty, None)?))
+    }
+
+    fn accepts(_ty: &Type) -> bool {
+        true
+    }
+}
+
+fn from_sql(ty: &Type, raw: Option<&[u8]>) -> Result<Value, Box<dyn Error + Sync + Send>> {
+    if let Kind::Array(_) = ty.kind() {
+        let values = match raw {
+            Some(raw) => Vec::<PsqlRowValue>::from_sql(ty, raw)?,
+            None => return Ok(Value::Null),
+        };
+        return Ok(Value::Array(values.into_iter().map(|v| v.0).collect()));
+    }
+    let value = match ty.name() {
+        "bool" => Value::Bool(FromSql::from_sql_nullable(ty, raw)?),
+        "int2" => Value::Integer(Option::<i16>::from_sql_nullable(ty, raw)?.map(i32::from)),
+        "int4" => Value::Integer(FromSql::from_sql_nullable(ty, raw)?),
+        "int8" => Value::Long(FromSql::from_sql_nullable(ty, raw)?),
+        "oid" => Value::UnsignedInteger(FromSql::from_sql_nullable(ty, raw)?),
+        "float4" => Value::Float(FromSql::from_sql_nullable(ty, raw)?),
+        "float8" => Value::Double(FromSql::from_sql_nullable(ty, raw)?),
+        "bytea" => match Option::<Vec<u8>>::from_sql_nullable(ty, raw)? {
+            Some(value) => Value::UnsignedBytes(value),
+            None => Value::Null,
+        },
+        "date" => Value::Date(FromSql::from_sql_nullable(ty, raw)?),
+        "timestamp" => Value::DateTime(FromSql::from_sql_nullable(ty, raw)?),
+        "timestamptz" => Value::UtcTime(FromSql::from_sql_nullable(ty, raw)?),
+        "json" | "jsonb" => match Option::<serde_json::Value>::from_sql_nullable(ty, raw)? {
+            Some(value) => serde_json::from_value(value)?,
+            None => Value::Null,
+        },
+        // exact decimal as text
+        "numeric" => Value::String(raw.map(numeric_text).transpose()?),
+        "uuid" => Value::String(raw.map(uuid_text).transpose()?),
+        "interval" => Value::Duration(raw.map(interval_duration).transpose()?),
+        // text, varchar and text like extension types, e.g citext
+        _ if <String as FromSql>::accepts(ty) => {
+            Value::String(FromSql::from_sql_nullable(ty, raw)?)
+        }
+        name => return Err(format!("unsupported type '{}'", name).into()),
+    };
+    Ok(value)
+}
+
+fn read_i16(raw: &[u8], pos: usize) -> Result<i16, Box<dyn Error + Sync + Send>> {
+    let bytes = raw.get(pos..pos + 2).ok_or("invalid numeric")?;
+    Ok(i16::from_be_bytes(bytes.try_into()?))
+}
+
+/// Binary numeric: ndigits, weight, sign, dscale and base 10000 digits
+fn numeric_text(raw: &[u8]) -> Result<String, Box<dyn Error + Sync + Send>> {
+    let ndigits = read_i16(raw, 0)? as i64;
+    let weight = read_i16(raw, 2)? as i64;
+    let sign = read_i16(raw, 4)? as u16;
+    let dscale = read_i16(raw, 6)? as usize;
+    match sign {
+        0xC000 => return Ok("NaN".to_owned()),
+        0xD000 => return Ok("Infinity".to_owned()),
+        0xF000 => return Ok("-Infinity".to_owned()),
+        _ => (),
+    };
+    let digits = (0..ndigits)
+        .map(|i| read_i16(raw, 8 + 2 * i as usize))
+        .collect::<Result<Vec<_>, _>>()?;
+    let digit = |i: i64| match i >= 0 && i < ndigits {
+        true => digits[i as usize],
+        false => 0,
+    };
+    let mut text = String::new();
+    if sign == 0x4000 {
+        text.push('-');
+    }
+    match weight < 0 {
+        true => text.push('0'),
+        false => {
+            text.push_str(&digit(0).to_string());
+            for i in 1..=weight {
+                text.push_str(&format!("{:04}", digit(i)));
+            }
+        }
+    }
+    if dscale > 0 {
+        let mut fraction = String::new();
+        let mut i = weight + 1;
+        while fraction.len() < dscale {
+            fraction.push_str(&format!("{:04}", digit(i)));
+            i += 1;
+        }
+        fraction.truncate(dscale);
+        text.push('.');
+        text.push_str(&fraction);
+    }
+    Ok(text)
+}
+
+fn uuid_text(raw: &[u8]) -> Result<String, Box<dyn Error + Sync + Send>> {
+    if raw.len() != 16 {
+        return Err("invalid uuid".into());
+    }
+    let hex: String = raw.iter().map(|b| format!("{:02x}", b)).collect();
+    Ok(format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..32]
+    ))
+}
+
+/// Binary interval: micros, days and months, month as 30 days and year as 365.25 days
+/// same as `EXTRACT(EPOCH FROM interval)`
+fn interval_duration(raw: &[u8]) -> Result<Duration, Box<dyn Error + Sync + Send>> {
+    if raw.len() != 16 {
+        return Err("invalid interval".into());
+    }
+    let micros = i64::from_be_bytes(raw[0..8].try_into()?);
+    let days = i32::from_be_bytes(raw[8..12].try_into()?) as i64;
+    let months = i32::from_be_bytes(raw[12..16].try_into()?) as i64;
+    Ok(Duration::microseconds(micros)
+        + Duration::days(days + (months % 12) * 30)
+        + Duration::seconds(months / 12 * 31_557_600))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn encode<T: ToSql>(t: &T, ty: &Type) -> BytesMut {
+        let mut out = BytesMut::new();
+        t.to_sql_checked(ty, &mut out).unwrap();
+        out
+    }
+
+    #[test]
+    fn test_psql_array() {
+        let array = PsqlArray(vec![Value::from(1_i64), Value::Null, Value::from("3")]);
+        let expected: Vec<Option<i32>> = vec![Some(1), None, Some(3)];
+        assert_eq!(
+            encode(&expected, &Type::INT4_ARRAY),
+            encode(&array, &Type::INT4_ARRAY)
+        );
+        let tags = PsqlArray(vec![Value::from("foo"), Value::from("bar")]);
+        assert_eq!(
+            encode(&vec!["foo", "bar"], &Type::TEXT_ARRAY),
+            encode(&tags, &Type::TEXT_ARRAY)
+        );
+        assert_eq!(
+            encode(&serde_json::json!(["foo", "bar"]), &Type::JSONB),
+            encode(&tags, &Type::JSONB)
+        );
+        let mut out = BytesMut::new();
+        assert!(tags.to_sql_checked(&Type::INT4, &mut out).is_err());
+    }
+
+    fn numeric(weight: i16, sign: u16, dscale: i16, digits: &[i16]) -> Vec<u8> {
+        let mut raw = vec![];
+        raw.extend_from_slice(&(digits.len() as i16).to_be_bytes());
+        raw.extend_from_slice(&weight.to_be_bytes());
+        raw.extend_from_slice(&sign.to_be_bytes());
+        raw.extend_from_slice(&dscale.to_be_bytes());
+        for digit in digits {
+            raw.extend_from_slice(&digit.to_be_bytes());
+        }
+        raw
+    }
+
+    fn decode(ty: &Type, raw: &[u8]) -> Value {
+        PsqlRowValue::from_sql(ty, raw).unwrap().0
+    }
+
+    #[test]
+    fn test_psql_row_value() {
+        assert_eq!(
+            Value::from("123.45"),
+            decode(&Type::NUMERIC, &numeric(0, 0, 2, &[123, 4500]))
+        );
+        assert_eq!(
+            Value::from("-0.001"),
+            decode(&Type::NUMERIC, &numeric(-1, 0x4000, 3, &[10]))
+        );
+        assert_eq!(
+            Value::from("10000"),
+            decode(&Type::NUMERIC, &numeric(1, 0, 0, &[1]))
+        );
+        let uuid: Vec<u8> = (0..16).collect();
+        assert_eq!(
+            Value::from("00010203-0405-0607-0809-0a0b0c0d0e0f"),
+            decode(&Type::UUID, &uuid)
+        );
+        let mut interval = vec![];
+        interval.extend_from_slice(&1_000_000_i64.to_be_bytes());
+        interval.extend_from_slice(&1_i32.to_be_bytes());
+        interval.extend_from_slice(&0_i32.to_be_bytes());
+        assert_eq!(
+            Value::from(Duration::seconds(86_401)),
+            decode(&Type::INTERVAL, &interval)
+        );
+        let ids: Vec<Option<i32>> = vec![Some(1), None];
+        assert_eq!(
+            Value::Array(vec![Value::from(1), Value::Integer(None)]),
+            decode(&Type::INT4_ARRAY, &encode(&ids, &Type::INT4_ARRAY))
+        );
+        assert_eq!(
+            Value::Integer(None),
+            PsqlRowValue::from_sql_null(&Type::INT4).unwrap().0
+        );
+    }
+}
@@ -1,114 +1,148 @@
-use pipebase::common::LeftRight;
-use redis::{aio::PubSub, Client, Commands, Connection, FromRedisValue, RedisResult, ToRedisArgs};
+use anyhow::Context;
+use async_trait::async_trait;
+use pipebase::common::{ConnectionManager, LeftRight, Pool, PoolConfig};
+use redis::{
+    aio::Connection as AsyncConnection, AsyncCommands, Client, Commands, Connection,
+    FromRedisValue, RedisResult, ToRedisArgs,
+};
+use tracing::warn;
 
-pub struct RedisClient {
+struct RedisConnectionManager {
     client: Client,
-    connection: Connection,
-    // flag indicate whether to reconnect
-    reconnect: bool,
+}
+
+#[async_trait]
+impl ConnectionManager for RedisConnectionManager {
+    type Connection = AsyncConnection;
+
+    async fn connect(&self) -> anyhow::Result<AsyncConnection> {
+        self.client
+            .get_async_connection()
+            .await
+            .context("connect redis")
+    }
+
+    async fn check(&self, connection: &mut AsyncConnection) -> anyhow::Result<()> {
+        redis::cmd("PING")
+            .query_async::<_, String>(connection)
+            .await
+            .context("ping redis")?;
+        Ok(())
+    }
+}
+
+/// Async client with pooled connections, broken connection reconnected with backoff
+pub struct RedisClient {
+    pool: Pool<RedisConnectionManager>,
 }
 
 impl RedisClient {
-    pub fn new(url: String) -> anyhow::Result<Self> {
-        let client = redis::Client::open(url)?;
-        let connection = client.get_connection()?;
+    pub async fn new(url: String, pool: PoolConfig) -> anyhow::Result<Self> {
+        let manager = RedisConnectionManager {
+            client: redis::Client::open(url)?,
+        };
         Ok(RedisClient {
-            client,
-            connection,
-            reconnect: false,
+            pool: Pool::new(manager, pool).await?,
         })
     }
 
-    pub fn get<K, V>(&mut self, k: K) -> RedisResult<Option<V>>
-    where
-        K: ToRedisArgs,
-        V: FromRedisValue,
-    {
-        self.reconnect()?;
-        match self.connection.get(k) {
-            Ok(v) => Ok(v),
-            Err(err) => {
-                self.set_reconnect();
-                Err(err)
-            }
-        }
-    }
-
-    pub fn set<K, V, P>(&mut self, p: P) -> RedisResult<()>
+    pub async fn set<K, V, P>(&mut self, p: P) -> anyhow::Result<()>
     where
         P: LeftRight<L = K, R = V>,
-        K: ToRedisArgs + Clone,
-        V: ToRedisArgs + Clone,
+        K: ToRedisArgs + Send + Sync,
+        V: ToRedisArgs + Send + Sync,
     {
-        self.reconnect()?;
         let (k, v) = p.into_tuple();
-        match self.connection.set::<K, V, ()>(k, v) {
-            Ok(_) => Ok(()),
-            Err(e) => {
-                self.set_reconnect();
-                Err(e)
-            }
-        }
+        let mut connection = self.pool.get().await?;
+        connection.set::<K, V, ()>(k, v).await?;
+        Ok(())
     }
 
-    pub fn set_all<K, V, T, U>(&mut self, entries: U) -> RedisResult<()>
+    pub async fn set_all<K, V, T, U>(&mut self, entries: U) -> anyhow::Result<()>
     where
-        K: ToRedisArgs,
-        V: ToRedisArgs,
+        K: ToRedisArgs + Send + Sync,
+        V: ToRedisArgs + Send + Sync,
         T: LeftRight<L = K, R = V>,
         U: IntoIterator<Item = T>,
     {
-        self.reconnect()?;
         let entries: Vec<(K, V)> = entries
             .into_iter()
             .map(|entry| entry.into_tuple())
             .collect();
-        match self.connection.set_multiple::<K, V, ()>(entries.as_slice()) {
-            Ok(_) => Ok(()),
-            Err(err) => {
-                self.set_reconnect();
-                Err(err)
-            }
-        }
+        let mut connection = self.pool.get().await?;
+        connection
+            .set_multiple::<K, V, ()>(entries.as_slice())
+            .await?;
+        Ok(())
     }
 
-    pub fn publish<C, M, P>(&mut self, p: P) -> RedisResult<()>
+    pub async fn publish<C, M, P>(&mut self, p: P) -> anyhow::Result<()>
     where
-        C: ToRedisArgs,
-        M: ToRedisArgs,
+        C: ToRedisArgs + Send + Sync,
+        M: ToRedisArgs + Send + Sync,
         P: LeftRight<L = C, R = M>,
     {
-        self.reconnect()?;
         let (channel, message) = p.into_tuple();
-        match self.connection.publish::<C, M, ()>(channel, message) {
-            Ok(_) => Ok(()),
-            Err(err) => {
-                self.set_reconnect();
-                Err(err)
-            }
-        }
+        let mut connection = self.pool.get().await?;
+        connection.publish::<C, M, ()>(channel, message).await?;
+        Ok(())
+    }
+}
+
+/// Blocking client for sync group table, reconnect on next command once connection broken
+pub struct RedisSyncClient {
+    client: Client,
+    connection: Option<Connection>,
+}
+
+impl RedisSyncClient {
+    pub fn new(url: String) -> anyhow::Result<Self> {
+        let client = redis::Client::open(url)?;
+        let connection = client.get_connection().context("connect redis")?;
+        Ok(RedisSyncClient {
+            client,
+            connection: Some(connection),
+        })
     }
 
-    pub async fn subscribe<C>(&mut self, channel: C) -> RedisResult<PubSub>
+    pub fn get<K, V>(&mut self, k: K) -> anyhow::Result<Option<V>>
     where
-        C: ToRedisArgs,
+        K: ToRedisArgs,
+        V: FromRedisValue,
     {
-        let conn = self.client.get_async_connection().await?;
-        let mut pubsub = conn.into_pubsub();
-        pubsub.subscribe(channel).await?;
-        Ok(pubsub)
+        self.run(|connection| connection.get(k))
     }
 
-    fn reconnect(&mut self) -> RedisResult<()> {
-        if !self.reconnect {
-            return Ok(());
-        }
-        self.connection = self.client.get_connection()?;
-        self.reconnect = false;
-        Ok(())
+    pub fn set_all<K, V, T, U>(&mut self, entries: U) -> anyhow::Result<()>
+    where
+        K: ToRedisArgs,
+        V: ToRedisArgs,
+        T: LeftRight<L = K, R = V>,
+        U: IntoIterator<Item = T>,
+    {
+        let entries: Vec<(K, V)> = entries
+            .into_iter()
+            .map(|entry| entry.into_tuple())
+            .collect();
+        self.run(|connection| connection.set_multiple::<K, V, ()>(entries.as_slice()))
     }
 
-    fn set_reconnect(&mut self) {
-        self.reconnect = true;
+    fn run<T, F>(&mut self, f: F) -> anyhow::Result<T>
+    where
+        F: FnOnce(&mut Connection) -> RedisResult<T>,
+    {
+        let mut connection = match self.connection.take() {
+            Some(connection) => connection,
+            None => self.client.get_connection().context("connect redis")?,
+        };
+        let result = f(&mut connection);
+        match result {
+            Ok(_) => self.connection = Some(connection),
+            Err(ref err) => match redis::cmd("PING").query::<String>(&mut connection) {
+                Ok(_) => self.connection = Some(connection),
+                Err(_) => warn!("drop broken connection, error: {:#}", err),
+            },
+        }
+        Ok(result?)
     }
 }
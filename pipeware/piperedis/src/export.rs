@@ -1,7 +1,7 @@
 use crate::client::RedisClient;
 use async_trait::async_trait;
 use pipebase::{
-    common::{ConfigInto, FromConfig, FromPath, LeftRight},
+    common::{ConfigInto, FromConfig, FromPath, LeftRight, PoolConfig},
     export::Export,
 };
 use redis::ToRedisArgs;
@@ -11,6 +11,8 @@ use serde::Deserialize;
 pub struct RedisStringWriterConfig {
     // connection parameter: https://docs.rs/redis/0.20.2/redis/#connection-parameters
     url: String,
+    /// Pool size and reconnect backoff
+    pool: Option<PoolConfig>,
 }
 
 impl FromPath for RedisStringWriterConfig {}
@@ -25,7 +27,7 @@ pub struct RedisStringWriter {
 impl FromConfig<RedisStringWriterConfig> for RedisStringWriter {
     async fn from_config(config: RedisStringWriterConfig) -> anyhow::Result<Self> {
         Ok(RedisStringWriter {
-            client: RedisClient::new(config.url)?,
+            client: RedisClient::new(config.url, config.pool.unwrap_or_default()).await?,
         })
     }
 }
@@ -34,12 +36,11 @@ impl FromConfig<RedisStringWriterConfig> for RedisStringWriter {
 impl<K, V, P> Export<P, RedisStringWriterConfig> for RedisStringWriter
 where
     P: LeftRight<L = K, R = V> + Send + 'static,
-    K: ToRedisArgs + Clone + Send + 'static,
-    V: ToRedisArgs + Clone + Send + 'static,
+    K: ToRedisArgs + Clone + Send + Sync + 'static,
+    V: ToRedisArgs + Clone + Send + Sync + 'static,
 {
     async fn export(&mut self, p: P) -> anyhow::Result<()> {
-        self.client.set(p)?;
-        Ok(())
+        self.client.set(p).await
     }
 }
 
@@ -47,6 +48,8 @@ where
 pub struct RedisPublisherConfig {
     // connection parameter: https://docs.rs/redis/0.20.2/redis/#connection-parameters
     url: String,
+    /// Pool size and reconnect backoff
+    pool: Option<PoolConfig>,
 }
 
 impl FromPath for RedisPublisherConfig {}
@@ -61,7 +64,7 @@ pub struct RedisPublisher {
 impl FromConfig<RedisPublisherConfig> for RedisPublisher {
     async fn from_config(config: RedisPublisherConfig) -> anyhow::Result<Self> {
         Ok(RedisPublisher {
-            client: RedisClient::new(config.url)?,
+            client: RedisClient::new(config.url, config.pool.unwrap_or_default()).await?,
         })
     }
 }
@@ -70,12 +73,11 @@ impl FromConfig<RedisPublisherConfig> for RedisPublisher {
 impl<K, V, P> Export<P, RedisPublisherConfig> for RedisPublisher
 where
     P: LeftRight<L = K, R = V> + Send + 'static,
-    K: ToRedisArgs + Clone + Send + 'static,
-    V: ToRedisArgs + Clone + Send + 'static,
+    K: ToRedisArgs + Clone + Send + Sync + 'static,
+    V: ToRedisArgs + Clone + Send + Sync + 'static,
 {
     async fn export(&mut self, p: P) -> anyhow::Result<()> {
-        self.client.publish(p)?;
-        Ok(())
+        self.client.publish(p).await
     }
 }
 
@@ -83,6 +85,8 @@ where
 pub struct RedisStringBatchWriterConfig {
     // connection parameter: https://docs.rs/redis/0.20.2/redis/#connection-parameters
     url: String,
+    /// Pool size and reconnect backoff
+    pool: Option<PoolConfig>,
 }
 
 impl FromPath for RedisStringBatchWriterConfig {}
@@ -97,7 +101,7 @@ pub struct RedisStringBatchWriter {
 impl FromConfig<RedisStringBatchWriterConfig> for RedisStringBatchWriter {
     async fn from_config(config: RedisStringBatchWriterConfig) -> anyhow::Result<Self> {
         Ok(RedisStringBatchWriter {
-            client: RedisClient::new(config.url)?,
+            client: RedisClient::new(config.url, config.pool.unwrap_or_default()).await?,
         })
     }
 }
@@ -106,11 +110,10 @@ impl FromConfig<RedisStringBatchWriterConfig> for RedisStringBatchWriter {
 impl<K, V, P> Export<Vec<P>, RedisStringBatchWriterConfig> for RedisStringBatchWriter
 where
     P: LeftRight<L = K, R = V> + Send + 'static,
-    K: ToRedisArgs + Clone + Send + 'static,
-    V: ToRedisArgs + Clone + Send + 'static,
+    K: ToRedisArgs + Clone + Send + Sync + 'static,
+    V: ToRedisArgs + Clone + Send + Sync + 'static,
 {
     async fn export(&mut self, entries: Vec<P>) -> anyhow::Result<()> {
-        self.client.set_all(entries)?;
-        Ok(())
+        self.client.set_all(entries).await
     }
 }
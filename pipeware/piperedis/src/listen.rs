@@ -1,12 +1,11 @@
 use std::fmt::Debug;
 
-use crate::client::RedisClient;
 use async_trait::async_trait;
 use pipebase::{
     common::{ConfigInto, FromConfig, FromPath},
     listen::Listen,
 };
-use redis::FromRedisValue;
+use redis::{Client, FromRedisValue};
 use serde::Deserialize;
 use tokio::sync::mpsc::Sender;
 use tokio_stream::StreamExt;
@@ -25,7 +24,7 @@ pub struct RedisSubscriber<T>
 where
     T: FromRedisValue,
 {
-    client: RedisClient,
+    client: Client,
     channel: String,
     tx: Option<Sender<T>>,
 }
@@ -37,7 +36,7 @@ where
 {
     async fn from_config(config: RedisSubscriberConfig) -> anyhow::Result<Self> {
         Ok(RedisSubscriber {
-            client: RedisClient::new(config.url)?,
+            client: Client::open(config.url)?,
             channel: config.channel,
             tx: None,
         })
@@ -63,7 +62,8 @@ where
     T: Debug + FromRedisValue + Send + Sync + 'static,
 {
     async fn do_run(&mut self) -> anyhow::Result<()> {
-        let mut pubsub = self.client.subscribe(&self.channel).await?;
+        let mut pubsub = self.client.get_async_connection().await?.into_pubsub();
+        pubsub.subscribe(&self.channel).await?;
         let tx = self
             .tx
             .as_ref()
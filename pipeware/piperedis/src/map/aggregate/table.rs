@@ -1,10 +1,10 @@
-use crate::client::RedisClient;
+use crate::client::RedisSyncClient;
 use pipebase::common::GroupTable;
 use redis::{FromRedisValue, ToRedisArgs};
 
 pub struct RedisGroupTable<C> {
     cache: C,
-    client: RedisClient,
+    client: RedisSyncClient,
 }
 
 impl<K, V, C> IntoIterator for RedisGroupTable<C>
@@ -60,7 +60,7 @@ impl<C> RedisGroupTable<C> {
     pub fn new(url: String, cache: C) -> anyhow::Result<Self> {
         Ok(RedisGroupTable {
             cache,
-            client: RedisClient::new(url)?,
+            client: RedisSyncClient::new(url)?,
         })
     }
 }
@@ -0,0 +1,154 @@
+use pipebase::common::{IntoAttributes, Render, Value};
+use rusqlite::{params_from_iter, types::Null, types::ValueRef, Connection, ToSql};
+use serde::Deserialize;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+use tokio::task;
+use tracing::info;
+
+#[derive(Deserialize)]
+pub struct SqliteClientConfig {
+    /// Database file path, created if not exists
+    path: String,
+}
+
+/// Sqlite client, blocking calls run on tokio blocking thread pool
+pub struct SqliteClient {
+    connection: Arc<Mutex<Connection>>,
+}
+
+impl SqliteClient {
+    pub async fn new(config: SqliteClientConfig) -> anyhow::Result<Self> {
+        let connection = task::spawn_blocking(move || Connection::open(config.path)).await??;
+        Ok(SqliteClient {
+            connection: Arc::new(Mutex::new(connection)),
+        })
+    }
+
+    /// Run blocking closure with connection on blocking thread pool
+    async fn run_blocking<F, T>(&self, f: F) -> anyhow::Result<T>
+    where
+        F: FnOnce(&mut Connection) -> anyhow::Result<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let connection = self.connection.clone();
+        task::spawn_blocking(move || {
+            let mut connection = match connection.lock() {
+                Ok(connection) => connection,
+                Err(_) => anyhow::bail!("sqlite connection poisoned"),
+            };
+            f(&mut connection)
+        })
+        .await?
+    }
+
+    pub async fn execute<R>(&self, r: R) -> anyhow::Result<()>
+    where
+        R: Render,
+    {
+        let statement = r.render();
+        let rows_updated = self
+            .run_blocking(move |connection| Ok(connection.execute(&statement, [])?))
+            .await?;
+        info!("{} rows updated", rows_updated);
+        Ok(())
+    }
+
+    /// Execute prepared statement per item in one transaction
+    pub async fn prepare_execute<A>(&self, statement: &str, items: Vec<A>) -> anyhow::Result<()>
+    where
+        A: IntoAttributes,
+    {
+        let statement = statement.to_owned();
+        let items: Vec<Vec<Value>> = items
+            .into_iter()
+            .map(|item| {
+                item.into_attribute_tuples()
+                    .into_iter()
+                    .map(|(_, value)| value)
+                    .collect()
+            })
+            .collect();
+        self.run_blocking(move |connection| {
+            let transaction = connection.transaction()?;
+            {
+                let mut prepared_statement = transaction.prepare_cached(&statement)?;
+                for item in items {
+                    let params = Self::sqlite_values(item)?;
+                    prepared_statement.execute(params_from_iter(params.iter()))?;
+                }
+            }
+            transaction.commit()?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Query rows as `Value::Attributes` keyed by column name
+    pub async fn query(&self, statement: &str, params: Vec<Value>) -> anyhow::Result<Vec<Value>> {
+        let statement = statement.to_owned();
+        self.run_blocking(move |connection| {
+            let params = Self::sqlite_values(params)?;
+            let mut prepared_statement = connection.prepare_cached(&statement)?;
+            let columns: Vec<String> = prepared_statement
+                .column_names()
+                .into_iter()
+                .map(String::from)
+                .collect();
+            let mut rows = prepared_statement.query(params_from_iter(params.iter()))?;
+            let mut values: Vec<Value> = Vec::new();
+            while let Some(row) = rows.next()? {
+                let mut attributes: HashMap<String, Value> = HashMap::new();
+                for (i, column) in columns.iter().enumerate() {
+                    attributes.insert(column.to_owned(), Self::from_sqlite_value(row.get_ref(i)?));
+                }
+                values.push(Value::Attributes(attributes));
+            }
+            Ok(values)
+        })
+        .await
+    }
+
+    fn sqlite_values(values: Vec<Value>) -> anyhow::Result<Vec<Box<dyn ToSql>>> {
+        values.into_iter().map(Self::sqlite_value).collect()
+    }
+
+    fn sqlite_value(value: Value) -> anyhow::Result<Box<dyn ToSql>> {
+        let value: Box<dyn ToSql> = match value {
+            Value::Null => Box::new(Null),
+            Value::Bool(value) => Box::new(value),
+            Value::UnsignedInteger(value) => Box::new(value),
+            Value::Integer(value) => Box::new(value),
+            Value::UnsignedLong(value) => Box::new(value),
+            Value::Long(value) => Box::new(value),
+            Value::Float(value) => Box::new(value),
+            Value::Double(value) => Box::new(value),
+            Value::String(value) => Box::new(value),
+            Value::Date(value) => Box::new(value),
+            Value::DateTime(value) => Box::new(value),
+            Value::Duration(value) => Box::new(value.map(|value| value.num_milliseconds())),
+            Value::LocalTime(value) => Box::new(value),
+            Value::UtcTime(value) => Box::new(value),
+            Value::UnsignedBytes(value) => Box::new(value),
+            // nested values stored as json text
+            value @ (Value::Array(_) | Value::Attributes(_)) => {
+                Box::new(serde_json::to_string(&value)?)
+            }
+        };
+        Ok(value)
+    }
+
+    fn from_sqlite_value(value: ValueRef<'_>) -> Value {
+        match value {
+            ValueRef::Null => Value::Null,
+            ValueRef::Integer(value) => Value::Long(Some(value)),
+            ValueRef::Real(value) => Value::Double(Some(value)),
+            ValueRef::Text(value) => {
+                Value::String(Some(String::from_utf8_lossy(value).into_owned()))
+            }
+            ValueRef::Blob(value) => Value::UnsignedBytes(value.to_vec()),
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use crate::client::{SqliteClient, SqliteClientConfig};
+use async_trait::async_trait;
+use pipebase::{
+    common::{ConfigInto, FromConfig, FromPath, IntoAttributes, Render},
+    export::Export,
+};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct SqliteWriterConfig {
+    client: SqliteClientConfig,
+}
+
+impl FromPath for SqliteWriterConfig {}
+
+impl ConfigInto<SqliteWriter> for SqliteWriterConfig {}
+
+pub struct SqliteWriter {
+    client: SqliteClient,
+}
+
+#[async_trait]
+impl FromConfig<SqliteWriterConfig> for SqliteWriter {
+    async fn from_config(config: SqliteWriterConfig) -> anyhow::Result<Self> {
+        Ok(SqliteWriter {
+            client: SqliteClient::new(config.client).await?,
+        })
+    }
+}
+
+/// # Parameters
+/// * T: input, rendered as statement
+#[async_trait]
+impl<T> Export<T, SqliteWriterConfig> for SqliteWriter
+where
+    T: Render + Send + 'static,
+{
+    async fn export(&mut self, t: T) -> anyhow::Result<()> {
+        self.client.execute(t).await
+    }
+}
+
+#[derive(Deserialize)]
+pub struct SqlitePreparedWriterConfig {
+    client: SqliteClientConfig,
+    statement: String,
+}
+
+impl FromPath for SqlitePreparedWriterConfig {}
+
+impl ConfigInto<SqlitePreparedWriter> for SqlitePreparedWriterConfig {}
+
+pub struct SqlitePreparedWriter {
+    client: SqliteClient,
+    statement: String,
+}
+
+#[async_trait]
+impl FromConfig<SqlitePreparedWriterConfig> for SqlitePreparedWriter {
+    async fn from_config(config: SqlitePreparedWriterConfig) -> anyhow::Result<Self> {
+        Ok(SqlitePreparedWriter {
+            client: SqliteClient::new(config.client).await?,
+            statement: config.statement,
+        })
+    }
+}
+
+/// # Parameters
+/// * Vec<T>: input, batch written in one transaction
+#[async_trait]
+impl<T> Export<Vec<T>, SqlitePreparedWriterConfig> for SqlitePreparedWriter
+where
+    T: IntoAttributes + Send + 'static,
+{
+    async fn export(&mut self, items: Vec<T>) -> anyhow::Result<()> {
+        self.client.prepare_execute(&self.statement, items).await
+    }
+}
@@ -0,0 +1,6 @@
+mod client;
+mod export;
+mod poll;
+
+pub use export::*;
+pub use poll::*;
@@ -0,0 +1,176 @@
+use crate::client::{SqliteClient, SqliteClientConfig};
+use async_trait::async_trait;
+use pipebase::{
+    common::{ConfigInto, FromConfig, FromPath, Period},
+    poll::{CursorCheckpoint, CursorConfig, Poll, PollResponse},
+};
+use serde::{de::DeserializeOwned, Deserialize};
+use std::time::Duration;
+
+#[derive(Deserialize)]
+pub struct SqliteQueryPollerConfig {
+    client: SqliteClientConfig,
+    /// Query with cursor bound to first parameter and ordered by cursor column,
+    /// for example `SELECT * FROM events WHERE id > ?1 ORDER BY id LIMIT 1000`
+    query: String,
+    cursor: CursorConfig,
+    initial_delay: Period,
+    interval: Period,
+}
+
+impl FromPath for SqliteQueryPollerConfig {}
+
+impl ConfigInto<SqliteQueryPoller> for SqliteQueryPollerConfig {}
+
+/// Poll rows newer than last cursor
+pub struct SqliteQueryPoller {
+    client: SqliteClient,
+    query: String,
+    cursor: CursorCheckpoint,
+    initial_delay: Duration,
+    interval: Duration,
+}
+
+#[async_trait]
+impl FromConfig<SqliteQueryPollerConfig> for SqliteQueryPoller {
+    async fn from_config(config: SqliteQueryPollerConfig) -> anyhow::Result<Self> {
+        Ok(SqliteQueryPoller {
+            client: SqliteClient::new(config.client).await?,
+            query: config.query,
+            cursor: CursorCheckpoint::load(config.cursor)?,
+            initial_delay: config.initial_delay.into(),
+            interval: config.interval.into(),
+        })
+    }
+}
+
+/// # Parameters
+/// * Vec<U>: output, rows deserialized from `Value`
+#[async_trait]
+impl<U> Poll<Vec<U>, SqliteQueryPollerConfig> for SqliteQueryPoller
+where
+    U: DeserializeOwned + Send + 'static,
+{
+    async fn poll(&mut self) -> anyhow::Result<PollResponse<Vec<U>>> {
+        let cursor = self.cursor.get().to_owned();
+        let rows = self.client.query(&self.query, vec![cursor]).await?;
+        self.cursor.poll_rows(rows)
+    }
+
+    fn get_initial_delay(&self) -> Duration {
+        self.initial_delay.to_owned()
+    }
+
+    fn get_interval(&self) -> tokio::time::Interval {
+        let interval = self.interval.to_owned();
+        tokio::time::interval(interval)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::*;
+    use pipebase::prelude::*;
+    use serde::Deserialize;
+    use std::path::PathBuf;
+
+    #[derive(Clone, Debug, Deserialize, IntoAttributes, PartialEq)]
+    struct Event {
+        id: i64,
+        name: String,
+        score: Option<f64>,
+    }
+
+    #[derive(Render)]
+    #[render(
+        template = "CREATE TABLE IF NOT EXISTS {} (id INTEGER PRIMARY KEY, name TEXT, score REAL)"
+    )]
+    struct CreateTable {
+        #[render(pos = 0)]
+        table: String,
+    }
+
+    fn events() -> Vec<Event> {
+        vec![
+            Event {
+                id: 1,
+                name: "foo".to_owned(),
+                score: Some(0.5),
+            },
+            Event {
+                id: 2,
+                name: "bar".to_owned(),
+                score: None,
+            },
+        ]
+    }
+
+    fn directory() -> PathBuf {
+        let directory = std::env::temp_dir().join("pipesqlite_test_poll");
+        let _ = std::fs::remove_dir_all(&directory);
+        std::fs::create_dir_all(&directory).unwrap();
+        directory
+    }
+
+    fn client_config(database: &str) -> String {
+        format!("client:\n  path: {}\n", database)
+    }
+
+    async fn poll_events(poller: &mut SqliteQueryPoller) -> Option<Vec<Event>> {
+        match poller.poll().await.unwrap() {
+            PollResponse::PollResult(events) => events,
+            PollResponse::Exit => unreachable!(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_write_poll() {
+        let directory = directory();
+        let database = directory.join("events.db");
+        let database = database.to_str().unwrap();
+        let checkpoint = directory.join("cursor.json");
+        let writer_config: SqliteWriterConfig =
+            serde_yaml::from_str(&client_config(database)).unwrap();
+        let mut writer = SqliteWriter::from_config(writer_config).await.unwrap();
+        writer
+            .export(CreateTable {
+                table: "events".to_owned(),
+            })
+            .await
+            .unwrap();
+        let prepared_writer_config: SqlitePreparedWriterConfig = serde_yaml::from_str(&format!(
+            "{}statement: INSERT INTO events (id, name, score) VALUES (?1, ?2, ?3)\n",
+            client_config(database)
+        ))
+        .unwrap();
+        let mut prepared_writer = SqlitePreparedWriter::from_config(prepared_writer_config)
+            .await
+            .unwrap();
+        prepared_writer.export(events()).await.unwrap();
+        let poller_config = format!(
+            "{}query: SELECT id, name, score FROM events WHERE id > ?1 ORDER BY id LIMIT 1\ncursor:\n  column: id\n  initial: 0\n  checkpoint: {}\ninitial_delay:\n  Millis: 0\ninterval:\n  Millis: 100\n",
+            client_config(database),
+            checkpoint.to_str().unwrap()
+        );
+        let mut poller =
+            SqliteQueryPoller::from_config(serde_yaml::from_str(&poller_config).unwrap())
+                .await
+                .unwrap();
+        assert_eq!(
+            Some(vec![events()[0].to_owned()]),
+            poll_events(&mut poller).await
+        );
+        // restart and resume from persisted cursor
+        let mut poller =
+            SqliteQueryPoller::from_config(serde_yaml::from_str(&poller_config).unwrap())
+                .await
+                .unwrap();
+        assert_eq!(
+            Some(vec![events()[1].to_owned()]),
+            poll_events(&mut poller).await
+        );
+        assert_eq!(None, poll_events(&mut poller).await);
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -2,19 +2,33 @@ use async_trait::async_trait;
 use std::{collections::HashMap, net::SocketAddr};
 
 use pipebase::common::{ConfigInto, Context, FromConfig, FromPath, PipeContext};
-use pipebase::context::StoreContext;
+use pipebase::context::{
+    ContextHistory, ContextHistoryWriter, ContextHistoryWriterConfig, PipeContextSample,
+    StoreContext,
+};
 use serde::{Deserialize, Serialize};
 use tokio::sync::mpsc::channel;
+use tracing::error;
 
 #[derive(Deserialize, Serialize)]
 pub struct PipeContextQuery {
     state: String,
 }
 
+#[derive(Deserialize, Serialize)]
+pub struct PipeContextHistoryQuery {
+    /// Start timestamp in milliseconds, inclusive
+    from: Option<i64>,
+    /// End timestamp in milliseconds, inclusive
+    to: Option<i64>,
+}
+
 #[derive(Deserialize)]
 pub struct WarpContextServerConfig {
     ip: String,
     port: u16,
+    /// Sample contexts into local history if configured
+    history: Option<ContextHistoryWriterConfig>,
 }
 
 impl FromPath for WarpContextServerConfig {}
@@ -25,6 +39,7 @@ impl ConfigInto<WarpContextServer> for WarpContextServerConfig {}
 pub struct WarpContextServer {
     socket: SocketAddr,
     contexts: HashMap<String, std::sync::Arc<Context>>,
+    history: Option<ContextHistoryWriterConfig>,
 }
 
 #[async_trait]
@@ -35,6 +50,7 @@ impl FromConfig<WarpContextServerConfig> for WarpContextServer {
         Ok(WarpContextServer {
             socket,
             contexts: HashMap::new(),
+            history: config.history,
         })
     }
 }
@@ -50,14 +66,18 @@ impl StoreContext<WarpContextServerConfig> for WarpContextServer {
     }
 
     async fn run(&mut self) -> anyhow::Result<()> {
-        self.do_run().await;
+        let history = match self.history {
+            Some(ref config) => Some(self.run_history_writer(config.to_owned()).await?),
+            None => None,
+        };
+        self.do_run(history).await;
         Ok(())
     }
 }
 
 impl WarpContextServer {
-    async fn do_run(&self) {
-        let repository = self.repository();
+    async fn do_run(&self, history: Option<ContextHistory>) {
+        let repository = self.repository(history);
         let (shutdown_tx, mut shutdown_rx) = channel::<()>(1);
         let api = filters::contexts(repository, shutdown_tx);
         let socket = self.socket.to_owned();
@@ -67,19 +87,40 @@ impl WarpContextServer {
         server.await;
     }
 
-    fn repository(&self) -> PipeContextRepository {
-        PipeContextRepository::new(self.contexts.to_owned())
+    fn repository(&self, history: Option<ContextHistory>) -> PipeContextRepository {
+        PipeContextRepository::new(self.contexts.to_owned(), history)
+    }
+
+    async fn run_history_writer(
+        &self,
+        config: ContextHistoryWriterConfig,
+    ) -> anyhow::Result<ContextHistory> {
+        let mut writer = ContextHistoryWriter::from_config(config).await?;
+        for (name, context) in &self.contexts {
+            writer.store_context(name.to_owned(), context.to_owned());
+        }
+        let history = writer.get_history();
+        tokio::spawn(async move {
+            if let Err(err) = writer.run().await {
+                error!("context history writer exit with error '{:#?}'", err)
+            }
+        });
+        Ok(history)
     }
 }
 
 #[derive(Clone)]
 pub struct PipeContextRepository {
     contexts: HashMap<String, std::sync::Arc<Context>>,
+    history: Option<ContextHistory>,
 }
 
 impl PipeContextRepository {
-    fn new(contexts: HashMap<String, std::sync::Arc<Context>>) -> Self {
-        PipeContextRepository { contexts }
+    fn new(
+        contexts: HashMap<String, std::sync::Arc<Context>>,
+        history: Option<ContextHistory>,
+    ) -> Self {
+        PipeContextRepository { contexts, history }
     }
 
     fn get_context(&self, name: &str) -> Option<PipeContext> {
@@ -112,6 +153,21 @@ impl PipeContextRepository {
         contexts
     }
 
+    /// Read history on blocking thread pool, history file is parsed with blocking io
+    async fn query_context_history(
+        &self,
+        name: &str,
+        query: PipeContextHistoryQuery,
+    ) -> Option<anyhow::Result<Vec<PipeContextSample>>> {
+        let history = self.history.to_owned()?;
+        let name = name.to_owned();
+        let query = move || history.query(&name, query.from, query.to);
+        match tokio::task::spawn_blocking(query).await {
+            Ok(samples) => Some(samples),
+            Err(err) => Some(Err(err.into())),
+        }
+    }
+
     fn list_contexts(&self) -> Vec<PipeContext> {
         let contexts: Vec<PipeContext> = self
             .contexts
@@ -130,7 +186,7 @@ impl PipeContextRepository {
 }
 
 mod filters {
-    use crate::PipeContextQuery;
+    use crate::{PipeContextHistoryQuery, PipeContextQuery};
 
     use super::handlers;
     use super::PipeContextRepository;
@@ -142,6 +198,7 @@ mod filters {
         shutdown_tx: Sender<()>,
     ) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
         context_query_v1(repository.to_owned())
+            .or(context_history_v1(repository.to_owned()))
             .or(context_get_v1(repository.to_owned()))
             .or(context_list_v1(repository))
             .or(shutdown_v1(shutdown_tx))
@@ -176,6 +233,16 @@ mod filters {
             .and_then(handlers::query_context)
     }
 
+    pub fn context_history_v1(
+        repository: PipeContextRepository,
+    ) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+        warp::path!("v1" / "pipe" / String / "history")
+            .and(warp::get())
+            .and(warp::query::<PipeContextHistoryQuery>())
+            .and(with_repository(repository))
+            .and_then(handlers::query_context_history)
+    }
+
     pub fn shutdown_v1(
         shutdown_tx: Sender<()>,
     ) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
@@ -206,7 +273,7 @@ mod filters {
 }
 
 mod handlers {
-    use super::{models, PipeContextQuery, PipeContextRepository};
+    use super::{models, PipeContextHistoryQuery, PipeContextQuery, PipeContextRepository};
     use std::convert::Infallible;
     use tokio::sync::mpsc::Sender;
     use warp::http::{Response, StatusCode};
@@ -240,6 +307,32 @@ mod handlers {
         Ok(warp::reply::json(&contexts))
     }
 
+    pub async fn query_context_history(
+        name: String,
+        query: PipeContextHistoryQuery,
+        repository: PipeContextRepository,
+    ) -> Result<impl warp::Reply, Infallible> {
+        if repository.get_context(&name).is_none() {
+            return Ok(Response::builder()
+                .status(StatusCode::NOT_FOUND)
+                .body(format!("pipe '{}' not found", &name)));
+        }
+        match repository.query_context_history(&name, query).await {
+            Some(Ok(samples)) => Ok(Response::builder()
+                .status(StatusCode::OK)
+                .body(serde_json::to_string(&samples).unwrap())),
+            Some(Err(err)) => {
+                let failure = models::Failure::new(format!("{:#}", err));
+                Ok(Response::builder()
+                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                    .body(serde_json::to_string(&failure).unwrap()))
+            }
+            None => Ok(Response::builder()
+                .status(StatusCode::NOT_FOUND)
+                .body("context history not configured".to_string())),
+        }
+    }
+
     pub async fn shutdown(shutdown_tx: Sender<()>) -> Result<impl warp::Reply, Infallible> {
         let sent = shutdown_tx.send(()).await.is_ok();
         if sent {
@@ -273,3 +366,48 @@ mod models {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+
+    use super::{filters, PipeContextRepository};
+    use pipebase::common::{Context, PipeContext, State};
+    use pipebase::context::{ContextHistory, PipeContextSample};
+    use std::{collections::HashMap, sync::Arc, time::Duration};
+    use tokio::sync::mpsc::channel;
+    use warp::http::StatusCode;
+
+    #[tokio::test]
+    async fn test_context_history_endpoint() {
+        let directory = std::env::temp_dir().join("pipewarp_test_context_history");
+        let _ = std::fs::remove_dir_all(&directory);
+        let history = ContextHistory::new(directory.join("history.jsonl"), Duration::from_secs(60));
+        history
+            .append(&[PipeContextSample::new(
+                1,
+                PipeContext::new("foo".to_owned(), State::Receive, 1, 0),
+            )])
+            .unwrap();
+        let mut contexts = HashMap::new();
+        contexts.insert("foo".to_owned(), Arc::new(Context::default()));
+        let repository = PipeContextRepository::new(contexts, Some(history));
+        let (shutdown_tx, _shutdown_rx) = channel::<()>(1);
+        let api = filters::contexts(repository, shutdown_tx);
+        let response = warp::test::request()
+            .method("GET")
+            .path("/v1/pipe/foo/history")
+            .reply(&api)
+            .await;
+        assert_eq!(StatusCode::OK, response.status());
+        let samples: Vec<PipeContextSample> = serde_json::from_slice(response.body()).unwrap();
+        assert_eq!(1, samples.len());
+        // unknown pipe
+        let response = warp::test::request()
+            .method("GET")
+            .path("/v1/pipe/bar/history")
+            .reply(&api)
+            .await;
+        assert_eq!(StatusCode::NOT_FOUND, response.status());
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}